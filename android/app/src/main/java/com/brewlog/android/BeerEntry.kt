@@ -1,5 +1,7 @@
 package com.brewlog.android
 
+import org.json.JSONObject
+
 data class BeerEntry(
     val id: String,
     val name: String,
@@ -7,4 +9,21 @@ data class BeerEntry(
     val volumeMl: Double,
     val date: String,
     val notes: String
-) 
\ No newline at end of file
+) {
+    companion object {
+        fun fromJson(obj: JSONObject): BeerEntry = BeerEntry(
+            id = obj.getString("id"),
+            name = obj.getString("name"),
+            alcoholPercentage = obj.getDouble("alcohol_percentage"),
+            volumeMl = obj.getDouble("volume_ml"),
+            date = obj.getString("date"),
+            notes = obj.optString("notes", "")
+        )
+
+        // Parses the result of BrewLogNative.get_beer_entries_json, throwing BrewLogException on failure
+        fun listFromJson(json: String): List<BeerEntry> {
+            val arr = NativeResult.parse(json).objectOrThrow().getJSONArray("entries")
+            return List(arr.length()) { i -> fromJson(arr.getJSONObject(i)) }
+        }
+    }
+}
@@ -14,8 +14,8 @@ class BrewLogApplication : Application() {
         // Initialize native database at app startup
         try {
             val dbPath = this.getDatabasePath("brewlog.db").absolutePath
-            val result = BrewLogNative.init_brew_log_with_path(dbPath)
-            if (!result.startsWith("OK")) {
+            val result = BrewLogNative.open(dbPath)
+            if (!NativeResult.isOk(result)) {
                 android.util.Log.e("BrewLog", "Native init failed: $result")
             }
         } catch (t: Throwable) {
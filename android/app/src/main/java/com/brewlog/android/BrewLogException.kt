@@ -0,0 +1,16 @@
+package com.brewlog.android
+
+// A failed call into the native core; code is one of the constants below (rust/src/dto.rs ErrorCode).
+// Thrown by NativeResult for errors in the envelope, and by BrewLogNative itself for null arguments and panics.
+class BrewLogException(val code: Int, message: String) : Exception(message) {
+    companion object {
+        const val OK = 0
+        const val NOT_INITIALIZED = 1
+        const val NULL_ARGUMENT = 2
+        const val INVALID_INPUT = 3
+        const val NOT_FOUND = 4
+        const val DATABASE = 5
+        const val INCOMPATIBLE_DATABASE = 6
+        const val INTERNAL = 7
+    }
+}
@@ -1,22 +1,68 @@
 package com.brewlog.android
 
+import org.json.JSONObject
+
+// Every call returns a NativeResult JSON envelope. The public functions act on the
+// database opened by open(); the native ones behind them take its handle explicitly.
 object BrewLogNative {
     init {
         try { System.loadLibrary("brewlog_core") } catch (_: Throwable) {}
     }
 
-    external fun init_brew_log(): String
-    external fun init_brew_log_with_path(path: String): String
-    external fun add_beer_entry(name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String
-    external fun get_daily_consumption(date: String): Double
-    external fun get_weekly_consumption(week_start_date: String): Double
-    external fun set_consumption_goal(daily_target: Double, weekly_target: Double, start_date: String, end_date: String): String
-    external fun get_beer_entries_json(start_date: String, end_date: String): String
-    external fun update_beer_entry_jni(id: String, name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String
-    external fun delete_beer_entry_jni(id: String): String
-    external fun update_beer_entry_date_jni(id: String, date: String): String
-    external fun add_beer_entry_full_jni(id: String, name: String, alcohol_percentage: Double, volume_ml: Double, date: String, notes: String): String
-    external fun delete_all_data(): String
-}
+    // Handle of the open database; 0 (never issued by the core) until open() succeeds
+    @Volatile
+    private var handle = 0L
+
+    // Opens the database at path in place of the current one, which is closed
+    @Synchronized
+    fun open(path: String): String {
+        val result = brewlog_open(path)
+        val opened = NativeResult.parse(result)
+        if (opened.isOk) {
+            close()
+            handle = opened.longOrThrow()
+        }
+        return result
+    }
 
+    @Synchronized
+    fun close() {
+        if (handle != 0L) {
+            brewlog_close(handle)
+            handle = 0L
+        }
+    }
+
+    fun add_beer_entry(name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String =
+        add_beer_entry(handle, name, alcohol_percentage, volume_ml, notes)
+    fun get_daily_consumption(date: String): String = get_daily_consumption(handle, date)
+    fun get_weekly_consumption(week_start_date: String): String = get_weekly_consumption(handle, week_start_date)
+    fun set_consumption_goal(daily_target: Double, weekly_target: Double, start_date: String, end_date: String): String =
+        set_consumption_goal(handle, daily_target, weekly_target, start_date, end_date)
+    fun get_beer_entries_json(start_date: String, end_date: String): String = get_beer_entries_json(handle, start_date, end_date)
+    fun update_beer_entry_jni(id: String, name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String =
+        update_beer_entry_jni(handle, id, name, alcohol_percentage, volume_ml, notes)
+    fun delete_beer_entry_jni(id: String): String = delete_beer_entry_jni(handle, id)
+    fun update_beer_entry_date_jni(id: String, date: String): String = update_beer_entry_date_jni(handle, id, date)
+    fun add_beer_entry_full_jni(id: String, name: String, alcohol_percentage: Double, volume_ml: Double, date: String, notes: String): String =
+        add_beer_entry_full_jni(handle, id, name, alcohol_percentage, volume_ml, date, notes)
+    fun delete_all_data(): String = delete_all_data(handle)
 
+    // Any BrewLog method by name, e.g. call("get_progress_stats", JSONObject().put("period_start", ...));
+    // the methods and their parameter names are listed in rust/src/bindings.rs
+    fun call(method: String, args: JSONObject = JSONObject()): String = brewlog_call(handle, method, args.toString())
+
+    private external fun brewlog_open(path: String): String
+    private external fun brewlog_close(handle: Long): String
+    private external fun add_beer_entry(handle: Long, name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String
+    private external fun get_daily_consumption(handle: Long, date: String): String
+    private external fun get_weekly_consumption(handle: Long, week_start_date: String): String
+    private external fun set_consumption_goal(handle: Long, daily_target: Double, weekly_target: Double, start_date: String, end_date: String): String
+    private external fun get_beer_entries_json(handle: Long, start_date: String, end_date: String): String
+    private external fun update_beer_entry_jni(handle: Long, id: String, name: String, alcohol_percentage: Double, volume_ml: Double, notes: String): String
+    private external fun delete_beer_entry_jni(handle: Long, id: String): String
+    private external fun update_beer_entry_date_jni(handle: Long, id: String, date: String): String
+    private external fun add_beer_entry_full_jni(handle: Long, id: String, name: String, alcohol_percentage: Double, volume_ml: Double, date: String, notes: String): String
+    private external fun delete_all_data(handle: Long): String
+    private external fun brewlog_call(handle: Long, method: String, args: String): String
+}
@@ -5,7 +5,6 @@ import androidx.appcompat.app.AppCompatActivity
 import androidx.recyclerview.widget.LinearLayoutManager
 import java.time.LocalDate
 import android.widget.CalendarView
-import org.json.JSONArray
 
 class CalendarActivity : AppCompatActivity() {
     private lateinit var adapter: BeerEntryAdapter
@@ -62,19 +61,7 @@ class CalendarActivity : AppCompatActivity() {
     private fun setDate(date: LocalDate) {
         try {
             val json = BrewLogNative.get_beer_entries_json(date.toString(), date.toString())
-            val arr = JSONArray(json)
-            val list = List(arr.length()) { i ->
-                val o = arr.getJSONObject(i)
-                BeerEntry(
-                    id = o.optString("id"),
-                    name = o.optString("name"),
-                    alcoholPercentage = o.optDouble("alcohol_percentage", o.optDouble("alcoholPercentage", 0.0)),
-                    volumeMl = o.optDouble("volume_ml", o.optDouble("volumeMl", 0.0)),
-                    date = o.optString("date"),
-                    notes = o.optString("notes", "")
-                )
-            }
-            adapter.submitList(list)
+            adapter.submitList(BeerEntry.listFromJson(json))
         } catch (_: Exception) {}
     }
 
@@ -92,7 +79,7 @@ class CalendarActivity : AppCompatActivity() {
                 val p = presets[which]
                 val id = java.util.UUID.randomUUID().toString()
                 val res = BrewLogNative.add_beer_entry_full_jni(id, p.name, p.strength.toDouble(), p.volume.toDouble(), date.toString(), "")
-                if (res.startsWith("OK")) { setDate(date) }
+                if (NativeResult.isOk(res)) { setDate(date) }
                 d.dismiss()
             }
             .setNegativeButton("Cancel", null)
@@ -113,7 +100,7 @@ class CalendarActivity : AppCompatActivity() {
                 val vol = dialogView.findViewById<android.widget.EditText>(R.id.et_volume_ml).text.toString().toDoubleOrNull() ?: entry.volumeMl
                 val notes = dialogView.findViewById<android.widget.EditText>(R.id.et_notes).text.toString()
                 val r = BrewLogNative.update_beer_entry_jni(entry.id, name, strength, vol, notes)
-                if (r.startsWith("OK")) setDate(LocalDate.parse(entry.date))
+                if (NativeResult.isOk(r)) setDate(LocalDate.parse(entry.date))
                 d.dismiss()
             }
             .setNegativeButton("Cancel", null)
@@ -122,7 +109,7 @@ class CalendarActivity : AppCompatActivity() {
 
     private fun deleteInline(entry: BeerEntry) {
         val r = BrewLogNative.delete_beer_entry_jni(entry.id)
-        if (r.startsWith("OK")) setDate(LocalDate.parse(entry.date))
+        if (NativeResult.isOk(r)) setDate(LocalDate.parse(entry.date))
     }
 
     private fun showSetTotalDialog(date: LocalDate) {
@@ -147,22 +134,8 @@ class CalendarActivity : AppCompatActivity() {
     private fun setTotalForDay(date: LocalDate, targetMl: Int, alcoholPercentage: Double = 0.0) {
         try {
             val json = BrewLogNative.get_beer_entries_json(date.toString(), date.toString())
-            val arr = JSONArray(json)
-            var currentMl = 0.0
-            val entries = mutableListOf<BeerEntry>()
-            for (i in 0 until arr.length()) {
-                val o = arr.getJSONObject(i)
-                val e = BeerEntry(
-                    id = o.optString("id"),
-                    name = o.optString("name"),
-                    alcoholPercentage = o.optDouble("alcohol_percentage", o.optDouble("alcoholPercentage", 0.0)),
-                    volumeMl = o.optDouble("volume_ml", o.optDouble("volumeMl", 0.0)),
-                    date = o.optString("date"),
-                    notes = o.optString("notes", "")
-                )
-                entries.add(e)
-                currentMl += e.volumeMl
-            }
+            val entries = BeerEntry.listFromJson(json)
+            val currentMl = entries.sumOf { it.volumeMl }
             val diff = targetMl - currentMl
             if (diff <= 0) {
                 setDate(date)
@@ -171,7 +144,7 @@ class CalendarActivity : AppCompatActivity() {
             // Add one synthetic entry to reach total using the provided alcohol percentage
             val id = java.util.UUID.randomUUID().toString()
             val res = BrewLogNative.add_beer_entry_full_jni(id, "Adjustment", alcoholPercentage, diff, date.toString(), "auto")
-            if (res.startsWith("OK")) setDate(date)
+            if (NativeResult.isOk(res)) setDate(date)
         } catch (_: Exception) {}
     }
 }
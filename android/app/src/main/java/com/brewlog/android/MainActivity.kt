@@ -191,15 +191,13 @@ class MainActivity : AppCompatActivity() {
                 val monthStart = today.minusDays(29)
 
                 val todayConsumption = try {
-                    val v = BrewLogNative.get_daily_consumption(today.toString())
-                    if (v >= 0) v else log.getDailyConsumption(today)
+                    NativeResult.parse(BrewLogNative.get_daily_consumption(today.toString())).doubleOrThrow()
                 } catch (_: Throwable) {
                     log.getDailyConsumption(today)
                 }
                 // Weekly consumption used on Progress screen
                 val weekConsumption = try {
-                    val v = BrewLogNative.get_weekly_consumption(weekStart.toString())
-                    if (v >= 0) v else log.getWeeklyConsumption(weekStart)
+                    NativeResult.parse(BrewLogNative.get_weekly_consumption(weekStart.toString())).doubleOrThrow()
                 } catch (_: Throwable) {
                     log.getWeeklyConsumption(weekStart)
                 }
@@ -209,21 +207,9 @@ class MainActivity : AppCompatActivity() {
                     totals.values.sum()
                 } catch (_: Throwable) { 0.0 }
 
-                val json = try { BrewLogNative.get_beer_entries_json(weekStart.toString(), today.toString()) } catch (_: Throwable) { "" }
-                val entries = if (json.startsWith("[")) {
-                    val arr = JSONArray(json)
-                    List(arr.length()) { i ->
-                        val o = arr.getJSONObject(i)
-                        BeerEntry(
-                            id = o.optString("id"),
-                            name = o.optString("name"),
-                            alcoholPercentage = o.optDouble("alcohol_percentage", o.optDouble("alcoholPercentage", 0.0)),
-                            volumeMl = o.optDouble("volume_ml", o.optDouble("volumeMl", 0.0)),
-                            date = o.optString("date"),
-                            notes = o.optString("notes", "")
-                        )
-                    }
-                } else {
+                val entries = try {
+                    BeerEntry.listFromJson(BrewLogNative.get_beer_entries_json(weekStart.toString(), today.toString()))
+                } catch (_: Throwable) {
                     log.getBeerEntries(weekStart.toString(), today.toString())
                 }
                 adapter.submitList(entries)
@@ -675,7 +661,7 @@ class MainActivity : AppCompatActivity() {
                     try {
                         val dateStr = selected.format(DateTimeFormatter.ISO_LOCAL_DATE)
                         val r = try { BrewLogNative.update_beer_entry_date_jni(entry.id, dateStr) } catch (_: Throwable) { "" }
-                        if (!r.startsWith("OK")) {
+                        if (!NativeResult.isOk(r)) {
                             brewLog?.updateBeerEntryDate(entry.id, selected)
                         }
                         Toast.makeText(this@MainActivity, "Date updated", Toast.LENGTH_SHORT).show()
@@ -944,20 +930,9 @@ class MainActivity : AppCompatActivity() {
             try {
                 val start = selectedStartDate ?: LocalDate.now().minusDays(6)
                 val end = selectedEndDate ?: LocalDate.now()
-                val json = try { BrewLogNative.get_beer_entries_json(start.toString(), end.toString()) } catch (_: Throwable) { "[]" }
+                val json = try { BrewLogNative.get_beer_entries_json(start.toString(), end.toString()) } catch (_: Throwable) { "" }
                 val entries = try {
-                    val arr = JSONArray(json)
-                    List(arr.length()) { i ->
-                        val o = arr.getJSONObject(i)
-                        BeerEntry(
-                            id = o.optString("id"),
-                            name = o.optString("name"),
-                            alcoholPercentage = o.optDouble("alcohol_percentage", o.optDouble("alcoholPercentage", 0.0)),
-                            volumeMl = o.optDouble("volume_ml", o.optDouble("volumeMl", 0.0)),
-                            date = o.optString("date"),
-                            notes = o.optString("notes", "")
-                        )
-                    }
+                    BeerEntry.listFromJson(json)
                 } catch (_: Throwable) { emptyList() }
                 adapter.submitList(entries)
                 findViewById<View>(R.id.empty_state).visibility = if (entries.isEmpty()) View.VISIBLE else View.GONE
@@ -971,7 +946,7 @@ class MainActivity : AppCompatActivity() {
         brewLog?.let { log ->
             try {
                 val r = try { BrewLogNative.add_beer_entry(name, alcoholPercentage, volumeMl, notes) } catch (_: Throwable) { "" }
-                if (!r.startsWith("OK")) {
+                if (!NativeResult.isOk(r)) {
                     log.addBeerEntry(name, alcoholPercentage, volumeMl, notes)
                 }
                 Toast.makeText(this, "Beer entry added successfully", Toast.LENGTH_SHORT).show()
@@ -1017,7 +992,7 @@ class MainActivity : AppCompatActivity() {
         brewLog?.let { log ->
             try {
                 val r = try { BrewLogNative.update_beer_entry_jni(id, name, alcoholPercentage, volumeMl, notes) } catch (_: Throwable) { "" }
-                if (!r.startsWith("OK")) {
+                if (!NativeResult.isOk(r)) {
                     log.updateBeerEntry(id, name, alcoholPercentage, volumeMl, notes)
                 }
                 Toast.makeText(this, "Beer entry updated successfully", Toast.LENGTH_SHORT).show()
@@ -1032,7 +1007,7 @@ class MainActivity : AppCompatActivity() {
         brewLog?.let { log ->
             try {
                 val r = try { BrewLogNative.delete_beer_entry_jni(entry.id) } catch (_: Throwable) { "" }
-                if (!r.startsWith("OK")) {
+                if (!NativeResult.isOk(r)) {
                     log.deleteBeerEntry(entry.id)
                 }
                 Toast.makeText(this, "Beer entry deleted successfully", Toast.LENGTH_SHORT).show()
@@ -1317,7 +1292,7 @@ class MainActivity : AppCompatActivity() {
                 .setPositiveButton("Delete") { d, _ ->
                     try {
                         val res = BrewLogNative.delete_all_data()
-                        if (!res.startsWith("OK")) {
+                        if (!NativeResult.isOk(res)) {
                             Toast.makeText(this, "Failed to delete data", Toast.LENGTH_SHORT).show()
                         } else {
                             // Clear prefs storing goals/baseline and presets
@@ -1449,21 +1424,15 @@ class MainActivity : AppCompatActivity() {
     private fun getDailyTotals(start: LocalDate, end: LocalDate): Map<LocalDate, Double> {
         return try {
             val json = BrewLogNative.get_beer_entries_json(start.toString(), end.toString())
-            val arr = JSONArray(json)
             val map = java.util.HashMap<LocalDate, Double>()
-            var i = 0
-            while (i < arr.length()) {
-                val o = arr.getJSONObject(i)
-                val date = LocalDate.parse(o.optString("date"))
-                val vol = o.optDouble("volume_ml", 0.0)
-                val alcoholPercentage = o.optDouble("alcohol_percentage", 0.0)
+            for (entry in BeerEntry.listFromJson(json)) {
+                val date = LocalDate.parse(entry.date)
                 
                 // Only count entries with alcohol percentage > 0 (same logic as main consumption)
-                if (alcoholPercentage > 0) {
+                if (entry.alcoholPercentage > 0) {
                     val current = map[date] ?: 0.0
-                    map[date] = current + vol
+                    map[date] = current + entry.volumeMl
                 }
-                i++
             }
             map
         } catch (e: Throwable) {
@@ -0,0 +1,37 @@
+package com.brewlog.android
+
+import org.json.JSONObject
+
+// The envelope every BrewLogNative call returns: { schema_version, code, message, payload }
+class NativeResult private constructor(val code: Int, val message: String?, private val payload: Any?) {
+    val isOk: Boolean get() = code == BrewLogException.OK
+
+    fun getOrThrow(): Any? {
+        if (!isOk) throw BrewLogException(code, message ?: "Error $code")
+        return payload
+    }
+
+    fun doubleOrThrow(): Double = (getOrThrow() as Number).toDouble()
+
+    fun longOrThrow(): Long = (getOrThrow() as Number).toLong()
+
+    fun objectOrThrow(): JSONObject = getOrThrow() as JSONObject
+
+    companion object {
+        // Revision of the core's JSON contract this app reads (rust/schema/brewlog-ffi.schema.json)
+        const val SCHEMA_VERSION = 2
+
+        fun parse(json: String): NativeResult {
+            val obj = JSONObject(json)
+            val version = obj.getInt("schema_version")
+            require(version <= SCHEMA_VERSION) { "Unsupported schema_version $version" }
+            return NativeResult(
+                obj.getInt("code"),
+                if (obj.isNull("message")) null else obj.getString("message"),
+                if (obj.isNull("payload")) null else obj.get("payload")
+            )
+        }
+
+        fun isOk(json: String): Boolean = try { parse(json).isOk } catch (_: Exception) { false }
+    }
+}
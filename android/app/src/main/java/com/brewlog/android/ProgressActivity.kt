@@ -53,14 +53,12 @@ class ProgressActivity : AppCompatActivity() {
 
 		// Use the same data source as MainActivity for consistency
 		val todayMl = try {
-			val v = BrewLogNative.get_daily_consumption(today.toString())
-			if (v >= 0) v else brewLog.getDailyConsumption(today)
+			NativeResult.parse(BrewLogNative.get_daily_consumption(today.toString())).doubleOrThrow()
 		} catch (_: Throwable) {
 			brewLog.getDailyConsumption(today)
 		}
 		val weekMl = try {
-			val v = BrewLogNative.get_weekly_consumption(weekStart.toString())
-			if (v >= 0) v else brewLog.getWeeklyConsumption(weekStart)
+			NativeResult.parse(BrewLogNative.get_weekly_consumption(weekStart.toString())).doubleOrThrow()
 		} catch (_: Throwable) {
 			brewLog.getWeeklyConsumption(weekStart)
 		}
@@ -314,8 +312,7 @@ class ProgressActivity : AppCompatActivity() {
 		val weekData = (0..6).map { dayOffset ->
 			val date = weekStart.plusDays(dayOffset.toLong())
 			val dayConsumption = try {
-				val v = BrewLogNative.get_daily_consumption(date.toString())
-				if (v >= 0) v else brewLog.getDailyConsumption(date)
+				NativeResult.parse(BrewLogNative.get_daily_consumption(date.toString())).doubleOrThrow()
 			} catch (_: Throwable) {
 				brewLog.getDailyConsumption(date)
 			}
@@ -381,8 +378,7 @@ class ProgressActivity : AppCompatActivity() {
 			val weekViewData = (0..27).map { dayOffset ->
 				val date = historicalToday.minusDays(27 - dayOffset.toLong())
 				val dayConsumption = try {
-					val v = BrewLogNative.get_daily_consumption(date.toString())
-					if (v >= 0) v else brewLog.getDailyConsumption(date)
+					NativeResult.parse(BrewLogNative.get_daily_consumption(date.toString())).doubleOrThrow()
 				} catch (_: Throwable) {
 					brewLog.getDailyConsumption(date)
 				}
@@ -401,8 +397,7 @@ class ProgressActivity : AppCompatActivity() {
 			val monthData = (0..89).map { dayOffset ->
 				val date = historicalToday.minusDays(89 - dayOffset.toLong())
 				val dayConsumption = try {
-					val v = BrewLogNative.get_daily_consumption(date.toString())
-					if (v >= 0) v else brewLog.getDailyConsumption(date)
+					NativeResult.parse(BrewLogNative.get_daily_consumption(date.toString())).doubleOrThrow()
 				} catch (_: Throwable) {
 					brewLog.getDailyConsumption(date)
 				}
@@ -447,21 +442,15 @@ class ProgressActivity : AppCompatActivity() {
 	private fun getDailyTotals(start: LocalDate, end: LocalDate): Map<LocalDate, Double> {
 		return try {
 			val json = BrewLogNative.get_beer_entries_json(start.toString(), end.toString())
-			val arr = org.json.JSONArray(json)
 			val map = java.util.HashMap<LocalDate, Double>()
-			var i = 0
-			while (i < arr.length()) {
-				val o = arr.getJSONObject(i)
-				val date = java.time.LocalDate.parse(o.optString("date"))
-				val vol = o.optDouble("volume_ml", 0.0)
-				val alcoholPercentage = o.optDouble("alcohol_percentage", 0.0)
+			for (entry in BeerEntry.listFromJson(json)) {
+				val date = java.time.LocalDate.parse(entry.date)
 				
 				// Only count entries with alcohol percentage > 0 (same logic as main consumption)
-				if (alcoholPercentage > 0) {
+				if (entry.alcoholPercentage > 0) {
 					val current = map[date] ?: 0.0
-					map[date] = current + vol
+					map[date] = current + entry.volumeMl
 				}
-				i++
 			}
 			map
 		} catch (e: Throwable) {
@@ -19,7 +19,6 @@ import java.io.FileReader
 import java.io.BufferedReader
 import java.time.LocalDate
 import java.time.DayOfWeek
-import org.json.JSONArray
 import org.json.JSONObject
 import android.net.Uri
 import androidx.activity.result.contract.ActivityResultContracts
@@ -249,15 +248,13 @@ class SettingsActivity : AppCompatActivity() {
             val startDate = LocalDate.now().minusYears(1)
             val endDate = LocalDate.now()
             val json = BrewLogNative.get_beer_entries_json(startDate.toString(), endDate.toString())
-            val entries = JSONArray(json)
             
-            for (i in 0 until entries.length()) {
-                val entry = entries.getJSONObject(i)
-                val date = entry.optString("date", "")
-                val name = entry.optString("name", "").replace(",", ";") // Replace commas to avoid CSV issues
-                val alcohol = entry.optDouble("alcoholPercentage", 0.0)
-                val volume = entry.optDouble("volume_ml", 0.0) // Fixed field name
-                val notes = entry.optString("notes", "").replace(",", ";").replace("\n", " ") // Clean notes
+            for (entry in BeerEntry.listFromJson(json)) {
+                val date = entry.date
+                val name = entry.name.replace(",", ";") // Replace commas to avoid CSV issues
+                val alcohol = entry.alcoholPercentage
+                val volume = entry.volumeMl
+                val notes = entry.notes.replace(",", ";").replace("\n", " ") // Clean notes
                 
                 csv.appendLine("$date,$name,$alcohol,$volume,$notes")
             }
@@ -298,7 +295,7 @@ class SettingsActivity : AppCompatActivity() {
                         notes
                     )
                     
-                    if (result == "OK") {
+                    if (NativeResult.isOk(result)) {
                         importedCount++
                     }
                 }
@@ -0,0 +1,399 @@
+//! `brewlog`: read and edit a BrewLog database from a terminal.
+//!
+//! Every command opens the database given by `--db` (or `BREWLOG_DB`) with
+//! `BrewLog::new_with_path`, creating it if needed. `--json` prints results in
+//! the core's serde form for scripts, and `null` for commands that only change
+//! the database; errors go to stderr with exit status 1.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use brewlog_core::{BeerEntry, BrewLog, BrewLogError, CsvMapping, DrinkCategory, ImportMode, ImportSource};
+use chrono::NaiveDate;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+
+/// Dates that bound "everything" when a range is left open.
+const FIRST_DATE: &str = "0001-01-01";
+const LAST_DATE: &str = "9999-12-31";
+
+#[derive(Parser)]
+#[command(name = "brewlog", version, about = "Log drinks, goals and baselines in a BrewLog database")]
+struct Cli {
+    /// Database file; created if it doesn't exist. Required.
+    #[arg(long, env = "BREWLOG_DB", value_name = "PATH", global = true)]
+    db: Option<PathBuf>,
+    /// Print JSON instead of text.
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log a drink. Without --date or --at it is logged now.
+    Add {
+        name: String,
+        /// Alcohol by volume, in percent.
+        #[arg(long)]
+        abv: f64,
+        /// Volume in millilitres.
+        #[arg(long)]
+        ml: f64,
+        #[arg(long, default_value = "")]
+        notes: String,
+        #[arg(long, value_parser = parse_category, default_value = "beer")]
+        category: DrinkCategory,
+        /// Drinking day to log it on.
+        #[arg(long, conflicts_with = "at")]
+        date: Option<NaiveDate>,
+        /// When it was drunk, as RFC 3339; the drinking day follows from it.
+        #[arg(long)]
+        at: Option<String>,
+    },
+    /// List entries, newest first.
+    List {
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+    /// Change fields of an entry; the rest keep their values.
+    Edit {
+        id: String,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        abv: Option<f64>,
+        #[arg(long)]
+        ml: Option<f64>,
+        #[arg(long)]
+        notes: Option<String>,
+        #[arg(long, value_parser = parse_category)]
+        category: Option<DrinkCategory>,
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// Delete an entry.
+    Delete { id: String },
+    /// Set or show the daily and weekly goal.
+    #[command(subcommand)]
+    Goal(GoalCommand),
+    /// Calculate, set or show the baseline progress is measured against.
+    #[command(subcommand)]
+    Baseline(BaselineCommand),
+    /// Totals and progress against the baseline for a date range.
+    Stats {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+    },
+    /// Write a backup or CSV to a file, or to stdout.
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// CSV only: first day to include.
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// CSV only: last day to include.
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Read a backup, an export CSV or another app's export.
+    Import {
+        file: PathBuf,
+        #[arg(long, value_enum, default_value_t = ImportFormat::Json)]
+        format: ImportFormat,
+        /// JSON backups only.
+        #[arg(long, value_enum, default_value_t = Mode::Merge)]
+        mode: Mode,
+    },
+}
+
+#[derive(Subcommand)]
+enum GoalCommand {
+    /// Set a daily and weekly limit in millilitres, replacing the current goal from --from.
+    Set {
+        #[arg(long)]
+        daily: f64,
+        #[arg(long)]
+        weekly: f64,
+        /// Defaults to the current drinking day.
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Open-ended when left out.
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+    /// Show the goal in effect today.
+    Show,
+}
+
+#[derive(Subcommand)]
+enum BaselineCommand {
+    /// Average the entries in a date range, saving the result with --save.
+    Calculate {
+        #[arg(long)]
+        from: NaiveDate,
+        #[arg(long)]
+        to: NaiveDate,
+        /// Save it as the baseline from this date.
+        #[arg(long, value_name = "EFFECTIVE_FROM")]
+        save: Option<NaiveDate>,
+    },
+    /// Save a baseline of this many millilitres a day.
+    Set {
+        daily: f64,
+        /// Defaults to the current drinking day.
+        #[arg(long)]
+        from: Option<NaiveDate>,
+    },
+    /// Show the baseline in effect today.
+    Show,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ImportFormat {
+    /// A backup written by `export --format json`.
+    Json,
+    /// A CSV written by `export --format csv`.
+    Csv,
+    UntappdCsv,
+    UntappdJson,
+    DrinkDiaryCsv,
+    SpreadsheetTemplate,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mode {
+    Merge,
+    Replace,
+    DryRun,
+}
+
+impl From<Mode> for ImportMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Merge => ImportMode::Merge,
+            Mode::Replace => ImportMode::Replace,
+            Mode::DryRun => ImportMode::DryRun,
+        }
+    }
+}
+
+fn parse_category(s: &str) -> Result<DrinkCategory, String> {
+    DrinkCategory::parse(s).ok_or_else(|| {
+        let names: Vec<&str> = DrinkCategory::ALL.iter().map(DrinkCategory::as_str).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn day(date: Option<NaiveDate>, default: &str) -> String {
+    date.map_or_else(|| default.to_string(), |d| d.to_string())
+}
+
+/// Prints `value` as JSON, or as text via `text`.
+fn print<T: Serialize>(json: bool, value: &T, text: impl FnOnce(&T)) -> Result<(), BrewLogError> {
+    if json {
+        let out = serde_json::to_string_pretty(value).map_err(|e| BrewLogError::InvalidInput(e.to_string()))?;
+        println!("{out}");
+    } else {
+        text(value);
+    }
+    Ok(())
+}
+
+fn print_entries(entries: &[BeerEntry]) {
+    for e in entries {
+        println!(
+            "{}  {:>7.0} ml  {:>5.1}%  {:<24}  {}{}",
+            e.date,
+            e.volume_ml,
+            e.alcohol_percentage,
+            e.name,
+            e.id,
+            if e.notes.is_empty() { String::new() } else { format!("  {}", e.notes) }
+        );
+    }
+}
+
+fn find_entry(log: &BrewLog, id: &str) -> Result<BeerEntry, BrewLogError> {
+    log.get_beer_entries(FIRST_DATE.to_string(), LAST_DATE.to_string())?
+        .into_iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| BrewLogError::NotFound(format!("Beer entry with id {id} not found")))
+}
+
+fn run(db: &Path, cli: Cli) -> Result<(), BrewLogError> {
+    let path = db.to_str().ok_or_else(|| BrewLogError::InvalidInput("Database path must be UTF-8".to_string()))?;
+    let log = BrewLog::new_with_path(path)?;
+    let json = cli.json;
+
+    match cli.command {
+        Command::Add { name, abv, ml, notes, category, date, at } => {
+            let consumed_at = match (&date, at) {
+                (None, None) => Some(log.get_day_boundary()?.now()?.to_rfc3339()),
+                (_, at) => at,
+            };
+            let entry = BeerEntry {
+                id: String::new(),
+                name,
+                alcohol_percentage: abv,
+                volume_ml: ml,
+                date: day(date, ""),
+                notes,
+                consumed_at,
+                category,
+                subtype: None,
+                style: None,
+                created_at: String::new(),
+            };
+            let id = log.save_beer_entry(entry)?;
+            print(json, &id, |id| println!("{id}"))
+        }
+        Command::List { from, to } => {
+            let entries = log.get_beer_entries(day(from, FIRST_DATE), day(to, LAST_DATE))?;
+            print(json, &entries, |e| print_entries(e))
+        }
+        Command::Edit { id, name, abv, ml, notes, category, date } => {
+            let mut entry = find_entry(&log, &id)?;
+            entry.name = name.unwrap_or(entry.name);
+            entry.alcohol_percentage = abv.unwrap_or(entry.alcohol_percentage);
+            entry.volume_ml = ml.unwrap_or(entry.volume_ml);
+            entry.notes = notes.unwrap_or(entry.notes);
+            entry.category = category.unwrap_or(entry.category);
+            log.save_beer_entry(entry)?;
+            if let Some(date) = date {
+                // Moves consumed_at along with the day, so the entry isn't refiled back later
+                log.update_beer_entry_date(id.clone(), date.to_string())?;
+            }
+            let entry = find_entry(&log, &id)?;
+            print(json, &entry, |e| print_entries(std::slice::from_ref(e)))
+        }
+        Command::Delete { id } => {
+            log.delete_beer_entry(id)?;
+            print(json, &(), |_| ())
+        }
+        Command::Goal(GoalCommand::Set { daily, weekly, from, to }) => {
+            let from = day(from, &log.current_drinking_day()?);
+            log.set_consumption_goal(daily, weekly, from, day(to, ""))?;
+            print(json, &(), |_| ())
+        }
+        Command::Goal(GoalCommand::Show) => {
+            let goal = log.get_current_goal()?;
+            print(json, &goal, |g| {
+                let until = g.effective_to.as_deref().unwrap_or("open-ended");
+                println!("{:.0} ml a day, {:.0} ml a week, from {} to {}", g.daily_target, g.weekly_target, g.effective_from, until);
+            })
+        }
+        Command::Baseline(BaselineCommand::Calculate { from, to, save }) => {
+            let baseline = log.calculate_baseline(from.to_string(), to.to_string())?;
+            match save {
+                Some(effective_from) => {
+                    let record = log.save_baseline(&baseline, effective_from.to_string())?;
+                    print(json, &record, |r| println!("Saved baseline version {}: {:.0} ml a day", r.version, r.average_daily_consumption))
+                }
+                None => print(json, &baseline, |b| {
+                    println!("{:.0} ml a day, {:.0} ml a week", b.average_daily_consumption, b.average_weekly_consumption)
+                }),
+            }
+        }
+        Command::Baseline(BaselineCommand::Set { daily, from }) => {
+            let from = day(from, &log.current_drinking_day()?);
+            let record = log.set_baseline(daily, from)?;
+            print(json, &record, |r| println!("Saved baseline version {}: {:.0} ml a day", r.version, r.average_daily_consumption))
+        }
+        Command::Baseline(BaselineCommand::Show) => {
+            let record = log.get_baseline_for_date(log.current_drinking_day()?)?;
+            print(json, &record, |r| {
+                println!("Version {}: {:.0} ml a day, {:.0} ml a week, from {}", r.version, r.average_daily_consumption, r.average_weekly_consumption, r.effective_from)
+            })
+        }
+        Command::Stats { from, to } => {
+            let stats = log.get_progress_stats(from.to_string(), to.to_string())?;
+            print(json, &stats, |s| {
+                let summary = &s.summary;
+                println!("{} to {}: {} days, {} drinking, {} dry", s.period_start, s.period_end, summary.days, summary.drinking_days, summary.dry_days);
+                println!("Total:          {:.0} ml, {:.1} standard drinks", summary.total.volume_ml, summary.total.standard_drinks);
+                println!("Daily average:  {:.0} ml", s.current_daily_average);
+                println!("Weekly average: {:.0} ml", s.current_weekly_average);
+                match s.baseline_version {
+                    Some(version) => println!("Reduction:      {:.1}% against baseline version {version}", s.reduction_percentage),
+                    None => println!("Reduction:      no baseline"),
+                }
+            })
+        }
+        Command::Export { format, from, to, output } => {
+            let data = match format {
+                ExportFormat::Json => log.export_json()?,
+                ExportFormat::Csv => log.export_csv(day(from, FIRST_DATE), day(to, LAST_DATE), ',')?,
+            };
+            match output {
+                Some(path) => {
+                    fs::write(&path, data).map_err(|e| BrewLogError::InvalidInput(format!("Couldn't write {}: {e}", path.display())))?;
+                    print(json, &(), |_| ())
+                }
+                None => {
+                    print!("{data}");
+                    Ok(())
+                }
+            }
+        }
+        Command::Import { file, format, mode } => {
+            let data = fs::read_to_string(&file).map_err(|e| BrewLogError::InvalidInput(format!("Couldn't read {}: {e}", file.display())))?;
+            let source = match format {
+                ImportFormat::Json => {
+                    let report = log.import_json(data, mode.into())?;
+                    return print(json, &report, |r| println!("{} created, {} updated, {} failed", r.created, r.updated, r.failed));
+                }
+                ImportFormat::Csv => {
+                    let report = log.import_csv(data, CsvMapping::default())?;
+                    return print(json, &report, |r| {
+                        println!("{} imported, {} rejected", r.imported, r.errors.len());
+                        for e in &r.errors {
+                            eprintln!("line {}: {}", e.line, e.message);
+                        }
+                    });
+                }
+                ImportFormat::UntappdCsv => ImportSource::UntappdCsv,
+                ImportFormat::UntappdJson => ImportSource::UntappdJson,
+                ImportFormat::DrinkDiaryCsv => ImportSource::DrinkDiaryCsv,
+                ImportFormat::SpreadsheetTemplate => ImportSource::SpreadsheetTemplate,
+            };
+            let report = log.import_from(source, data)?;
+            print(json, &report, |r| {
+                println!("{} imported, {} duplicates skipped, {} rejected", r.imported, r.duplicates, r.errors.len());
+                for e in &r.errors {
+                    eprintln!("line {}: {}", e.line, e.message);
+                }
+            })
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+    // clap can't require a global argument, so it is checked here
+    let Some(db) = cli.db.take() else {
+        Cli::command().error(ErrorKind::MissingRequiredArgument, "--db <PATH> is required unless BREWLOG_DB is set").exit()
+    };
+    match run(&db, cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("brewlog: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
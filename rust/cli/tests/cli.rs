@@ -0,0 +1,151 @@
+//! Runs the `brewlog` binary against a scratch database file.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+/// A database file removed when the test ends.
+struct Db(PathBuf);
+
+impl Db {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("brewlog-cli-{name}-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        Db(path)
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_brewlog")).arg("--db").arg(&self.0).args(args).output().unwrap()
+    }
+
+    fn ok(&self, args: &[&str]) -> String {
+        let out = self.run(args);
+        assert!(out.status.success(), "{args:?}: {}", String::from_utf8_lossy(&out.stderr));
+        String::from_utf8(out.stdout).unwrap()
+    }
+
+    fn json(&self, args: &[&str]) -> Value {
+        let mut args = args.to_vec();
+        args.push("--json");
+        serde_json::from_str(&self.ok(&args)).unwrap()
+    }
+}
+
+impl Drop for Db {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn entries_can_be_added_listed_edited_and_deleted() {
+    let db = Db::new("entries");
+    let id = db.ok(&["add", "Pint", "--abv", "5", "--ml", "568", "--date", "2024-03-01"]).trim().to_string();
+    let stout = db.ok(&["add", "Stout", "--abv", "4.2", "--ml", "440", "--at", "2024-03-02T20:00:00Z", "--notes", "tin"]);
+
+    let all = db.json(&["list"]);
+    assert_eq!(all.as_array().unwrap().len(), 2);
+    let first = db.json(&["list", "--from", "2024-03-01", "--to", "2024-03-01"]);
+    assert_eq!(first[0]["id"], id.as_str());
+
+    let edited = db.json(&["edit", &id, "--ml", "330", "--category", "cider"]);
+    assert_eq!(edited["volume_ml"], 330.0);
+    assert_eq!(edited["category"], "cider");
+    assert_eq!(edited["name"], "Pint");
+
+    let moved = db.json(&["edit", stout.trim(), "--date", "2024-03-05"]);
+    assert_eq!(moved["date"], "2024-03-05");
+    assert!(moved["consumed_at"].as_str().unwrap().starts_with("2024-03-05T20:00:00"), "{moved}");
+
+    assert_eq!(db.json(&["delete", &id]), Value::Null);
+    assert_eq!(db.json(&["list"]).as_array().unwrap().len(), 1);
+    assert!(db.ok(&["list"]).contains("Stout"));
+}
+
+#[test]
+fn drinks_logged_now_use_the_databases_time_zone() {
+    let db = Db::new("now");
+    let log = brewlog_core::BrewLog::new_with_path(db.0.to_str().unwrap()).unwrap();
+    log.set_day_boundary(3, "Pacific/Auckland".to_string()).unwrap();
+
+    db.ok(&["add", "Pint", "--abv", "5", "--ml", "568"]);
+    let entry = &db.json(&["list"])[0];
+    let consumed_at = entry["consumed_at"].as_str().unwrap();
+    assert!(consumed_at.ends_with("+12:00") || consumed_at.ends_with("+13:00"), "{consumed_at}");
+    assert_eq!(entry["date"], log.current_drinking_day().unwrap());
+}
+
+#[test]
+fn goals_baselines_and_stats() {
+    let db = Db::new("stats");
+    for day in 1..=7 {
+        db.ok(&["add", "Pint", "--abv", "5", "--ml", "500", "--date", &format!("2024-03-0{day}")]);
+    }
+
+    assert_eq!(db.json(&["goal", "set", "--daily", "250", "--weekly", "1750", "--from", "2024-03-01"]), Value::Null);
+    assert!(db.ok(&["goal", "set", "--daily", "250", "--weekly", "1750", "--from", "2024-03-01"]).is_empty());
+    assert_eq!(db.json(&["goal", "show"])["daily_target"], 250.0);
+
+    let baseline = db.json(&["baseline", "calculate", "--from", "2024-03-01", "--to", "2024-03-07"]);
+    assert_eq!(baseline["average_daily_consumption"], 500.0);
+    let record = db.json(&["baseline", "calculate", "--from", "2024-03-01", "--to", "2024-03-07", "--save", "2024-03-01"]);
+    assert_eq!(record["version"], 1);
+    assert_eq!(db.json(&["baseline", "set", "400", "--from", "2024-03-01"])["version"], 2);
+    assert_eq!(db.json(&["baseline", "show"])["average_daily_consumption"], 400.0);
+
+    let stats = db.json(&["stats", "--from", "2024-03-01", "--to", "2024-03-07"]);
+    assert_eq!(stats["baseline_version"], 2);
+    assert!(db.ok(&["stats", "--from", "2024-03-01", "--to", "2024-03-07"]).contains("7 drinking"));
+}
+
+#[test]
+fn exports_import_into_another_database() {
+    let source = Db::new("export");
+    source.ok(&["add", "Pint", "--abv", "5", "--ml", "568", "--date", "2024-03-01"]);
+    source.ok(&["add", "Lager", "--abv", "4", "--ml", "330", "--date", "2024-03-02"]);
+    let backup = std::env::temp_dir().join(format!("brewlog-cli-backup-{}.json", std::process::id()));
+    assert_eq!(source.json(&["export", "-o", backup.to_str().unwrap()]), Value::Null);
+    let csv = source.ok(&["export", "--format", "csv", "--from", "2024-03-02"]);
+    assert!(csv.contains("Lager") && !csv.contains("Pint"));
+
+    let target = Db::new("import");
+    let dry_run = target.json(&["import", backup.to_str().unwrap(), "--mode", "dry-run"]);
+    let entries = dry_run["records"].as_array().unwrap().iter().filter(|r| r["kind"] == "entry").count();
+    assert_eq!(entries, 2);
+    assert_eq!(dry_run["failed"], 0);
+    assert!(target.json(&["list"]).as_array().unwrap().is_empty());
+    target.ok(&["import", backup.to_str().unwrap()]);
+    assert_eq!(target.json(&["list"]), source.json(&["list"]));
+    fs::remove_file(backup).unwrap();
+}
+
+#[test]
+fn db_may_follow_the_subcommand() {
+    let db = Db::new("after");
+    let out = Command::new(env!("CARGO_BIN_EXE_brewlog"))
+        .args(["add", "Pint", "--abv", "5", "--ml", "568", "--date", "2024-03-01", "--db"])
+        .arg(&db.0)
+        .env_remove("BREWLOG_DB")
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert_eq!(db.json(&["list"]).as_array().unwrap().len(), 1);
+
+    let out = Command::new(env!("CARGO_BIN_EXE_brewlog")).arg("list").env_remove("BREWLOG_DB").output().unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--db <PATH> is required"));
+}
+
+#[test]
+fn errors_exit_non_zero() {
+    let db = Db::new("errors");
+    let out = db.run(&["delete", "no-such-id"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).starts_with("brewlog: "));
+
+    let out = db.run(&["add", "Pint", "--abv=-1", "--ml", "568"]);
+    assert!(!out.status.success());
+    assert!(db.json(&["list"]).as_array().unwrap().is_empty());
+}
@@ -0,0 +1,121 @@
+#ifndef BREWLOG_H
+#define BREWLOG_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand; rerun `BREWLOG_BLESS=1 cargo test --test c_header`. */
+
+#include <stdint.h>
+
+// Revision of the FFI JSON contract. Bump it whenever a field is renamed,
+// removed or changes meaning; adding an optional field does not need a bump.
+#define FFI_SCHEMA_VERSION 2
+
+// Stable numeric codes for the outcome of an FFI call. Values are never reused
+// or renumbered; new failures get new codes.
+enum ErrorCode
+#ifdef __cplusplus
+  : int32_t
+#endif // __cplusplus
+ {
+  ErrorCode_Ok = 0,
+  // The handle doesn't refer to an open database: it was never issued or
+  // has been closed.
+  ErrorCode_NotInitialized = 1,
+  // A required pointer or string argument was null.
+  ErrorCode_NullArgument = 2,
+  ErrorCode_InvalidInput = 3,
+  ErrorCode_NotFound = 4,
+  ErrorCode_Database = 5,
+  ErrorCode_IncompatibleDatabase = 6,
+  // A bug in BrewLog itself, e.g. a result that couldn't be serialised.
+  ErrorCode_Internal = 7,
+};
+#ifndef __cplusplus
+typedef int32_t ErrorCode;
+#endif // __cplusplus
+
+// Identifies a database opened by [`brewlog_open`]. Handles are never 0 and
+// never reused within a process.
+typedef uint64_t BrewLogHandle;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Releases a string returned by any function in this library. Null is ignored.
+//
+// # Safety
+// `s` must be null or a pointer returned by this library that hasn't already been freed.
+void brewlog_string_free(char *s);
+
+// Opens (creating if needed) the database at `path`. Payload: its handle.
+//
+// # Safety
+// `path` must be null or a valid C string.
+char *brewlog_open(const char *path);
+
+// Opens a new, empty in-memory database. Payload: its handle.
+char *brewlog_open_in_memory(void);
+
+// Closes the database behind `handle`, which can't be used afterwards.
+char *brewlog_close(BrewLogHandle handle);
+
+// # Safety
+// `name` and `notes` must be null or valid C strings.
+char *add_beer_entry(BrewLogHandle handle,
+                     const char *name,
+                     double alcohol_percentage,
+                     double volume_ml,
+                     const char *notes);
+
+// Payload: millilitres logged on `date`.
+//
+// # Safety
+// `date` must be null or a valid C string.
+char *get_daily_consumption(BrewLogHandle handle, const char *date);
+
+// Payload: millilitres logged in the week starting `week_start_date`.
+//
+// # Safety
+// `week_start_date` must be null or a valid C string.
+char *get_weekly_consumption(BrewLogHandle handle, const char *week_start_date);
+
+// # Safety
+// `start_date` and `end_date` must be null or valid C strings.
+char *set_consumption_goal(BrewLogHandle handle,
+                           double daily_target,
+                           double weekly_target,
+                           const char *start_date,
+                           const char *end_date);
+
+// Payload: the entries between the two dates as an [`EntryListDto`] document.
+//
+// # Safety
+// `start_date` and `end_date` must be null or valid C strings.
+char *get_beer_entries_json(BrewLogHandle handle, const char *start_date, const char *end_date);
+
+// # Safety
+// `id` must be null or a valid C string.
+char *delete_beer_entry_jni(BrewLogHandle handle, const char *id);
+
+// # Safety
+// `id`, `name` and `notes` must be null or valid C strings.
+char *update_beer_entry_jni(BrewLogHandle handle,
+                            const char *id,
+                            const char *name,
+                            double alcohol_percentage,
+                            double volume_ml,
+                            const char *notes);
+
+// Calls the `BrewLog` method named `method` with `args`, a JSON object of its
+// parameters by name. Payload: the method's result, as described under
+// `methods` in the published schema.
+//
+// # Safety
+// `method` and `args` must be null or valid C strings.
+char *brewlog_call(BrewLogHandle handle, const char *method, const char *args);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* BREWLOG_H */
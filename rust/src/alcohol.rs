@@ -0,0 +1,140 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul};
+
+use crate::standard_drink::{GoalUnit, StandardDrink};
+use crate::BeerEntry;
+
+/// Density of ethanol at 20 °C, in grams per millilitre.
+pub const ETHANOL_DENSITY_G_PER_ML: f64 = 0.789;
+
+/// Grams of pure ethanol in `volume_ml` of a drink at `alcohol_percentage` ABV.
+pub fn ethanol_grams(volume_ml: f64, alcohol_percentage: f64) -> f64 {
+    volume_ml * (alcohol_percentage / 100.0) * ETHANOL_DENSITY_G_PER_ML
+}
+
+impl BeerEntry {
+    /// Grams of pure ethanol in this entry.
+    pub fn ethanol_grams(&self) -> f64 {
+        ethanol_grams(self.volume_ml, self.alcohol_percentage)
+    }
+
+    /// Standard drinks in this entry under the given national definition.
+    pub fn standard_drinks(&self, definition: &StandardDrink) -> f64 {
+        definition.drinks_from_grams(self.ethanol_grams())
+    }
+}
+
+/// An amount consumed, reported as liquid volume, pure ethanol and standard drinks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct Consumption {
+    pub volume_ml: f64,
+    pub ethanol_grams: f64,
+    pub standard_drinks: f64,
+}
+
+impl Consumption {
+    pub fn from_entry(entry: &BeerEntry, definition: &StandardDrink) -> Self {
+        Consumption {
+            volume_ml: entry.volume_ml,
+            ethanol_grams: entry.ethanol_grams(),
+            standard_drinks: entry.standard_drinks(definition),
+        }
+    }
+
+    pub fn from_entries<'a, I>(entries: I, definition: &StandardDrink) -> Self
+    where
+        I: IntoIterator<Item = &'a BeerEntry>,
+    {
+        entries.into_iter().map(|e| Consumption::from_entry(e, definition)).sum()
+    }
+
+    /// The amount in the unit a goal is expressed in.
+    pub fn in_unit(&self, unit: GoalUnit) -> f64 {
+        match unit {
+            GoalUnit::VolumeMl => self.volume_ml,
+            GoalUnit::EthanolGrams => self.ethanol_grams,
+            GoalUnit::StandardDrinks => self.standard_drinks,
+        }
+    }
+}
+
+impl Add for Consumption {
+    type Output = Consumption;
+
+    fn add(self, rhs: Consumption) -> Consumption {
+        Consumption {
+            volume_ml: self.volume_ml + rhs.volume_ml,
+            ethanol_grams: self.ethanol_grams + rhs.ethanol_grams,
+            standard_drinks: self.standard_drinks + rhs.standard_drinks,
+        }
+    }
+}
+
+impl AddAssign for Consumption {
+    fn add_assign(&mut self, rhs: Consumption) {
+        *self = *self + rhs;
+    }
+}
+
+impl Div<f64> for Consumption {
+    type Output = Consumption;
+
+    fn div(self, rhs: f64) -> Consumption {
+        Consumption {
+            volume_ml: self.volume_ml / rhs,
+            ethanol_grams: self.ethanol_grams / rhs,
+            standard_drinks: self.standard_drinks / rhs,
+        }
+    }
+}
+
+impl Mul<f64> for Consumption {
+    type Output = Consumption;
+
+    fn mul(self, rhs: f64) -> Consumption {
+        Consumption {
+            volume_ml: self.volume_ml * rhs,
+            ethanol_grams: self.ethanol_grams * rhs,
+            standard_drinks: self.standard_drinks * rhs,
+        }
+    }
+}
+
+impl Sum for Consumption {
+    fn sum<I: Iterator<Item = Consumption>>(iter: I) -> Self {
+        iter.fold(Consumption::default(), Add::add)
+    }
+}
+
+/// Units drink volumes are recorded in outside BrewLog, which always stores millilitres.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeUnit {
+    #[default]
+    Ml,
+    Cl,
+    L,
+    UsFlOz,
+    UkFlOz,
+    UsPint,
+    UkPint,
+}
+
+impl VolumeUnit {
+    pub fn to_ml(&self, value: f64) -> f64 {
+        let ml_per_unit = match self {
+            VolumeUnit::Ml => 1.0,
+            VolumeUnit::Cl => 10.0,
+            VolumeUnit::L => 1000.0,
+            VolumeUnit::UsFlOz => 29.573_529_562_5,
+            VolumeUnit::UkFlOz => 28.413_062_5,
+            VolumeUnit::UsPint => 473.176_473,
+            VolumeUnit::UkPint => 568.261_25,
+        };
+        value * ml_per_unit
+    }
+}
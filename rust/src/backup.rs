@@ -0,0 +1,394 @@
+//! Whole-database backups as JSON.
+//!
+//! A backup is one object:
+//!
+//! ```json
+//! {
+//!   "format": "brewlog-backup",
+//!   "version": 1,
+//!   "exported_at": "2024-03-01T20:00:00+00:00",
+//!   "schema_version": 9,
+//!   "entries": [BeerEntry, ...],
+//!   "goals": [ConsumptionGoal, ...],
+//!   "baselines": [BaselineRecord, ...],
+//!   "presets": [DrinkPreset, ...],
+//!   "settings": { "default_beer_size": 500.0, "time_zone": "UTC", ... }
+//! }
+//! ```
+//!
+//! Records use the field names of their Rust types. `version` only changes when
+//! the document changes incompatibly; documents from a newer version are refused.
+//! Records are matched on `id` when merging. A restored end-of-day hour or time
+//! zone refiles timestamped entries, as [`BrewLog::set_day_boundary`] does.
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    entry_from_row, goal_kind_columns, parse_date, parse_timestamp, read_day_boundary, refile_entries, validate_entry,
+    write_setting, BaselineRecord, BeerEntry, BrewLog, BrewLogError, ConsumptionGoal, DrinkPreset, Setting,
+    SettingValue, ENTRY_COLUMNS,
+};
+
+pub const BACKUP_FORMAT: &str = "brewlog-backup";
+pub const BACKUP_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    /// Schema of the database the backup was taken from. Informational only.
+    pub schema_version: u32,
+    #[serde(default)]
+    pub entries: Vec<BeerEntry>,
+    #[serde(default)]
+    pub goals: Vec<ConsumptionGoal>,
+    #[serde(default)]
+    pub baselines: Vec<BaselineRecord>,
+    #[serde(default)]
+    pub presets: Vec<DrinkPreset>,
+    /// Every setting by key, defaults included, so a restore reproduces the configuration exactly.
+    #[serde(default)]
+    pub settings: BTreeMap<String, SettingValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Adds new records and overwrites existing ones with the same id.
+    Merge,
+    /// Deletes everything first, settings included.
+    Replace,
+    /// Reports what `Merge` would do without writing anything.
+    DryRun,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    Entry,
+    Goal,
+    Baseline,
+    Preset,
+    Setting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum RecordAction {
+    Created,
+    Updated,
+    Failed,
+}
+
+/// What happened to one record of the document. Settings are identified by key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct RecordOutcome {
+    pub kind: RecordKind,
+    pub id: String,
+    pub action: RecordAction,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ImportReport {
+    pub mode: ImportMode,
+    pub created: u32,
+    pub updated: u32,
+    pub failed: u32,
+    pub records: Vec<RecordOutcome>,
+}
+
+impl ImportReport {
+    fn new(mode: ImportMode) -> Self {
+        ImportReport {
+            mode,
+            created: 0,
+            updated: 0,
+            failed: 0,
+            records: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, kind: RecordKind, id: &str, result: Result<(RecordAction, Option<String>), BrewLogError>) {
+        let (action, message) = result.unwrap_or_else(|e| (RecordAction::Failed, Some(e.to_string())));
+        match action {
+            RecordAction::Created => self.created += 1,
+            RecordAction::Updated => self.updated += 1,
+            RecordAction::Failed => self.failed += 1,
+        }
+        self.records.push(RecordOutcome {
+            kind,
+            id: id.to_string(),
+            action,
+            message,
+        });
+    }
+}
+
+impl BrewLog {
+    /// Everything in the database as a [`Backup`].
+    pub fn export_backup(&self) -> Result<Backup, BrewLogError> {
+        let entries = {
+            let conn = self.conn();
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM beer_entries ORDER BY date, COALESCE(consumed_at, created_at)"
+            ))?;
+            let entries = stmt.query_map([], entry_from_row)?.collect::<Result<Vec<_>, _>>()?;
+            entries
+        };
+        let settings = self
+            .list_settings()?
+            .into_iter()
+            .map(|s| (s.setting.key().to_string(), s.value))
+            .collect();
+
+        Ok(Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            schema_version: self.schema_version()?,
+            entries,
+            goals: self.list_goals()?,
+            baselines: self.list_baselines()?,
+            presets: self.list_presets(false)?,
+            settings,
+        })
+    }
+
+    pub fn export_json(&self) -> Result<String, BrewLogError> {
+        serde_json::to_string_pretty(&self.export_backup()?).map_err(|e| BrewLogError::InvalidInput(e.to_string()))
+    }
+
+    /// Restores a backup. Records that fail validation are reported and skipped;
+    /// the rest are written in a single transaction.
+    pub fn import_backup(&self, backup: Backup, mode: ImportMode) -> Result<ImportReport, BrewLogError> {
+        if backup.format != BACKUP_FORMAT {
+            return Err(BrewLogError::InvalidInput(format!("Not a BrewLog backup: format is {:?}", backup.format)));
+        }
+        if backup.version > BACKUP_VERSION {
+            return Err(BrewLogError::IncompatibleDatabase(format!(
+                "backup version {} is newer than the {BACKUP_VERSION} this version of BrewLog reads",
+                backup.version
+            )));
+        }
+
+        let mut report = ImportReport::new(mode);
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+
+        if mode == ImportMode::Replace {
+            tx.execute_batch(
+                "DELETE FROM beer_entries;
+                 DELETE FROM consumption_goals;
+                 DELETE FROM baselines;
+                 DELETE FROM drink_presets;
+                 DELETE FROM settings;",
+            )?;
+        }
+
+        for (key, value) in &backup.settings {
+            report.record(RecordKind::Setting, key, restore_setting(&tx, key, value));
+        }
+        for entry in &backup.entries {
+            report.record(RecordKind::Entry, &entry.id, restore_entry(&tx, entry));
+        }
+        for goal in &backup.goals {
+            report.record(RecordKind::Goal, &goal.id, restore_goal(&tx, goal));
+        }
+        for baseline in &backup.baselines {
+            report.record(RecordKind::Baseline, &baseline.id, restore_baseline(&tx, baseline));
+        }
+        for preset in &backup.presets {
+            report.record(RecordKind::Preset, &preset.id, restore_preset(&tx, preset));
+        }
+        let boundary_keys = [Setting::EndOfDayHour.key(), Setting::TimeZone.key()];
+        if boundary_keys.iter().any(|key| backup.settings.contains_key(*key)) {
+            refile_entries(&tx, &read_day_boundary(&tx)?)?;
+        }
+
+        if mode == ImportMode::DryRun {
+            tx.rollback()?;
+        } else {
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+
+    /// Parses a document written by [`BrewLog::export_json`] and restores it.
+    pub fn import_json(&self, json: String, mode: ImportMode) -> Result<ImportReport, BrewLogError> {
+        let backup: Backup =
+            serde_json::from_str(&json).map_err(|e| BrewLogError::InvalidInput(format!("Invalid backup: {e}")))?;
+        self.import_backup(backup, mode)
+    }
+}
+
+type Outcome = Result<(RecordAction, Option<String>), BrewLogError>;
+
+fn exists(conn: &Connection, table: &str, id: &str) -> Result<bool, BrewLogError> {
+    Ok(conn.query_row(&format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE id = ?1)"), [id], |row| row.get(0))?)
+}
+
+fn created_or_updated(existed: bool) -> Outcome {
+    Ok((if existed { RecordAction::Updated } else { RecordAction::Created }, None))
+}
+
+fn require_id(id: &str) -> Result<(), BrewLogError> {
+    if id.is_empty() {
+        return Err(BrewLogError::InvalidInput("Record has no id".to_string()));
+    }
+    Ok(())
+}
+
+fn restore_setting(conn: &Connection, key: &str, value: &SettingValue) -> Outcome {
+    let setting = Setting::parse(key).ok_or_else(|| BrewLogError::InvalidInput(format!("Unknown setting: {key}")))?;
+    let value = setting.validate(value.clone())?;
+    let existed = crate::read_setting(conn, key)?.is_some();
+    write_setting(conn, key, &value.to_string())?;
+    created_or_updated(existed)
+}
+
+fn restore_entry(conn: &Connection, entry: &BeerEntry) -> Outcome {
+    require_id(&entry.id)?;
+    validate_entry(&entry.name, entry.alcohol_percentage, entry.volume_ml)?;
+    parse_date(&entry.date)?;
+    let consumed_at = entry.consumed_at.as_deref().map(parse_timestamp).transpose()?.map(|t| t.to_rfc3339());
+    let stored: Option<String> = conn
+        .query_row("SELECT created_at FROM beer_entries WHERE id = ?1", [&entry.id], |row| row.get(0))
+        .optional()?;
+    // created_at orders entries without a consumed_at, so it must survive the restore
+    let created_at = if entry.created_at.is_empty() {
+        stored.clone().unwrap_or_else(|| Utc::now().to_rfc3339())
+    } else {
+        parse_timestamp(&entry.created_at)?.to_rfc3339()
+    };
+    conn.execute(
+        "INSERT INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at, consumed_at, category, subtype, style)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(id) DO UPDATE SET name = excluded.name, alcohol_percentage = excluded.alcohol_percentage,
+         volume_ml = excluded.volume_ml, date = excluded.date, notes = excluded.notes, created_at = excluded.created_at,
+         consumed_at = excluded.consumed_at, category = excluded.category, subtype = excluded.subtype, style = excluded.style",
+        rusqlite::params![
+            &entry.id,
+            &entry.name,
+            &entry.alcohol_percentage,
+            &entry.volume_ml,
+            &entry.date,
+            &entry.notes,
+            &created_at,
+            &consumed_at,
+            entry.category.as_str(),
+            &entry.subtype,
+            &entry.style,
+        ],
+    )?;
+    created_or_updated(stored.is_some())
+}
+
+fn restore_goal(conn: &Connection, goal: &ConsumptionGoal) -> Outcome {
+    require_id(&goal.id)?;
+    goal.kind.validate()?;
+    parse_date(&goal.effective_from)?;
+    if let Some(to) = &goal.effective_to {
+        parse_date(to)?;
+    }
+    let (daily_target, weekly_target, alcohol_free_days, weekday_limits, session_cap, monthly_cap) =
+        goal_kind_columns(&goal.kind)?;
+    let stored: Option<String> = conn
+        .query_row("SELECT created_at FROM consumption_goals WHERE id = ?1", [&goal.id], |row| row.get(0))
+        .optional()?;
+    // created_at orders goals that start the same day, so it must survive the restore
+    let created_at = if goal.created_at.is_empty() {
+        stored.clone().unwrap_or_else(|| Utc::now().to_rfc3339())
+    } else {
+        parse_timestamp(&goal.created_at)?.to_rfc3339()
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO consumption_goals (id, kind, daily_target, weekly_target, alcohol_free_days, weekday_limits, session_cap, monthly_cap, unit, start_date, end_date, effective_from, effective_to, retired_at, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        rusqlite::params![
+            &goal.id,
+            goal.kind.name(),
+            &daily_target,
+            &weekly_target,
+            &alcohol_free_days,
+            &weekday_limits,
+            &session_cap,
+            &monthly_cap,
+            goal.unit.as_str(),
+            &goal.start_date,
+            &goal.end_date,
+            &goal.effective_from,
+            &goal.effective_to,
+            &goal.retired_at,
+            &created_at,
+        ],
+    )?;
+    created_or_updated(stored.is_some())
+}
+
+/// Baseline versions are unique. A merged baseline whose version is taken by a
+/// different baseline is stored under the next free version.
+fn restore_baseline(conn: &Connection, baseline: &BaselineRecord) -> Outcome {
+    require_id(&baseline.id)?;
+    parse_date(&baseline.effective_from)?;
+    let existed = exists(conn, "baselines", &baseline.id)?;
+    let holder: Option<String> = conn
+        .query_row("SELECT id FROM baselines WHERE version = ?1", [baseline.version], |row| row.get(0))
+        .optional()?;
+    let (version, message) = match holder {
+        Some(other) if other != baseline.id => {
+            let next: u32 = conn.query_row("SELECT COALESCE(MAX(version), 0) + 1 FROM baselines", [], |row| row.get(0))?;
+            (next, Some(format!("version {} was taken, stored as version {next}", baseline.version)))
+        }
+        _ => (baseline.version, None),
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO baselines (id, version, average_daily_consumption, average_weekly_consumption, average_daily_ethanol_grams, source_start, source_end, effective_from, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        (
+            &baseline.id,
+            &version,
+            &baseline.average_daily_consumption,
+            &baseline.average_weekly_consumption,
+            &baseline.average_daily_ethanol_grams,
+            &baseline.source_start,
+            &baseline.source_end,
+            &baseline.effective_from,
+            &baseline.created_at,
+        ),
+    )?;
+    Ok((created_or_updated(existed)?.0, message))
+}
+
+fn restore_preset(conn: &Connection, preset: &DrinkPreset) -> Outcome {
+    require_id(&preset.id)?;
+    validate_entry(&preset.name, preset.alcohol_percentage, preset.volume_ml)?;
+    let existed = exists(conn, "drink_presets", &preset.id)?;
+    conn.execute(
+        "INSERT INTO drink_presets (id, name, category, volume_ml, alcohol_percentage, favorite, position, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET name = excluded.name, category = excluded.category, volume_ml = excluded.volume_ml,
+         alcohol_percentage = excluded.alcohol_percentage, favorite = excluded.favorite, position = excluded.position",
+        (
+            &preset.id,
+            &preset.name,
+            preset.category.as_str(),
+            &preset.volume_ml,
+            &preset.alcohol_percentage,
+            &preset.favorite,
+            &preset.position,
+            Utc::now().to_rfc3339(),
+        ),
+    )?;
+    created_or_updated(existed)
+}
@@ -0,0 +1,232 @@
+//! The single list of `BrewLog` methods reachable from C and JNI.
+//!
+//! `brewlog_call(handle, method, args)` looks a method up here by name. `args`
+//! is a JSON object keyed by the parameter names below, each in the type's
+//! serde form; an `Option` parameter may be left out. The envelope payload is
+//! the type after `->`. Records, lists and reports, in and out, are the DTOs in
+//! [`crate::dto`], so they have the same shape as from the dedicated entry
+//! points; only plain values (strings, numbers, flags) and `import_csv`'s
+//! [`CsvMapping`], whose left-out fields take the core defaults, are sent as
+//! they are. [`crate::ffi_json_schema`] describes every method's arguments and
+//! payload under `methods`. `tests/bindings.rs` fails when a public method is
+//! missing from the table.
+
+use schemars::gen::SchemaGenerator;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+use crate::ffi::payload;
+use crate::{
+    BackupDto, BaselineCalculationDto, BaselineDto, BaselineListDto, BrewLog, CategoryBreakdownDto, CsvImportReportDto,
+    CsvMapping, DayBoundaryDto, DrinkCategoryDto, EntryDto, EntryListDto, ErrorCode, FfiError, GoalDto,
+    GoalEvaluationDto, GoalKindDto, GoalListDto, GoalUnitDto, ImportModeDto, ImportReportDto, ImportSourceDto,
+    ImporterReportDto, PeriodSummaryDto, PresetDto, PresetListDto, ProgressStatsDto, SettingDto, SettingListDto,
+    SettingValueDto, StandardDrinkDto, TotalsDto, WeekSchemeDto, WeekTotalsDto,
+};
+
+fn invalid(message: impl Into<String>) -> FfiError {
+    FfiError::new(ErrorCode::InvalidInput, message)
+}
+
+/// The named arguments of one call, removed as the parameters are read.
+pub(crate) struct Args(Map<String, Value>);
+
+impl Args {
+    pub(crate) fn parse(json: &str) -> Result<Self, FfiError> {
+        match serde_json::from_str(json) {
+            Ok(Value::Object(args)) => Ok(Args(args)),
+            Ok(_) => Err(invalid("Arguments must be a JSON object")),
+            Err(e) => Err(invalid(format!("Invalid arguments: {e}"))),
+        }
+    }
+
+    fn take<T: DeserializeOwned>(&mut self, name: &str) -> Result<T, FfiError> {
+        match self.0.remove(name) {
+            Some(value) => serde_json::from_value(value).map_err(|e| invalid(format!("Invalid argument {name}: {e}"))),
+            None => serde_json::from_value(Value::Null).map_err(|_| invalid(format!("Missing argument {name}"))),
+        }
+    }
+
+    /// Rejects anything left over, which is usually a misspelt parameter.
+    fn finish(self) -> Result<(), FfiError> {
+        match self.0.keys().next() {
+            Some(name) => Err(invalid(format!("Unexpected argument {name}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// One parameter in a method's schema.
+struct Param {
+    name: &'static str,
+    schema: Value,
+    /// Only parameters that may be null can be left out of `args`.
+    required: bool,
+}
+
+impl Param {
+    fn new<T: DeserializeOwned + JsonSchema>(gen: &mut SchemaGenerator, name: &'static str) -> Self {
+        let required = serde_json::from_value::<T>(Value::Null).is_err();
+        Param { name, schema: json!(gen.subschema_for::<T>()), required }
+    }
+}
+
+/// Each entry is `method(param: Type, ...) -> Payload;`. A method that doesn't
+/// take its parameters by value, or whose arguments or result are DTOs, adds
+/// `|log| call` to say how it is invoked.
+macro_rules! bindings {
+    ($($method:ident($($arg:ident: $ty:ty),*) -> $ret:ty $(|$log:ident| $call:expr)?;)*) => {
+        /// Every method `brewlog_call` dispatches, with its parameter names.
+        pub const METHODS: &[(&str, &[&str])] = &[$((stringify!($method), &[$(stringify!($arg)),*])),*];
+
+        pub(crate) fn call(log: &BrewLog, method: &str, mut args: Args) -> Result<Value, FfiError> {
+            match method {
+                $(stringify!($method) => {
+                    $(let $arg: $ty = args.take(stringify!($arg))?;)*
+                    args.finish()?;
+                    bindings!(@call log, $method($($arg),*) -> $ret $(|$log| $call)?)
+                })*
+                _ => Err(invalid(format!("Unknown method {method}"))),
+            }
+        }
+
+        /// Each method's `params` object and `result` payload as JSON Schema,
+        /// keyed by method name.
+        pub(crate) fn method_schemas(gen: &mut SchemaGenerator) -> Map<String, Value> {
+            let mut methods = Map::new();
+            $(
+                let params: Vec<Param> = vec![$(Param::new::<$ty>(gen, stringify!($arg))),*];
+                let params = json!({
+                    "type": "object",
+                    "properties": params.iter().map(|p| (p.name.to_string(), p.schema.clone())).collect::<Map<_, _>>(),
+                    "required": params.iter().filter(|p| p.required).map(|p| p.name).collect::<Vec<_>>(),
+                    "additionalProperties": false,
+                });
+                methods.insert(stringify!($method).to_string(), json!({ "params": params, "result": gen.subschema_for::<$ret>() }));
+            )*
+            methods
+        }
+    };
+    (@call $receiver:ident, $method:ident($($arg:ident),*) -> $ret:ty) => {
+        payload::<$ret>($receiver.$method($($arg),*))
+    };
+    (@call $receiver:ident, $method:ident($($arg:ident),*) -> $ret:ty |$log:ident| $call:expr) => {{
+        let $log = $receiver;
+        payload::<$ret>($call)
+    }};
+}
+
+bindings! {
+    // Settings and conventions
+    schema_version() -> u32;
+    get_standard_drink() -> StandardDrinkDto |log| log.get_standard_drink().map(StandardDrinkDto::from);
+    set_standard_drink(code: String) -> ();
+    get_day_boundary() -> DayBoundaryDto |log| log.get_day_boundary().map(DayBoundaryDto::from);
+    set_day_boundary(cutoff_hour: u32, time_zone: String) -> ();
+    current_drinking_day() -> String;
+    get_start_of_week() -> u32;
+    set_start_of_week(day: u32) -> ();
+    get_setting(setting: SettingDto) -> SettingValueDto |log| log.get_setting(setting.into()).map(SettingValueDto::from);
+    set_setting(setting: SettingDto, value: SettingValueDto) -> () |log| log.set_setting(setting.into(), value.into());
+    reset_setting(setting: SettingDto) -> () |log| log.reset_setting(setting.into());
+    list_settings() -> SettingListDto |log| log.list_settings().map(SettingListDto::new);
+
+    // Entries
+    add_beer_entry(name: String, alcohol_percentage: f64, volume_ml: f64, notes: String) -> ();
+    add_beer_entry_at(name: String, alcohol_percentage: f64, volume_ml: f64, notes: String, consumed_at: String) -> String;
+    add_beer_entry_full(
+        id: Option<String>,
+        name: String,
+        alcohol_percentage: f64,
+        volume_ml: f64,
+        date: String,
+        notes: String,
+        consumed_at: Option<String>
+    ) -> ();
+    save_beer_entry(entry: EntryDto) -> String |log| log.save_beer_entry(entry.into());
+    get_beer_entries(start_date: String, end_date: String) -> EntryListDto
+        |log| log.get_beer_entries(start_date, end_date).map(EntryListDto::new);
+    get_beer_entries_by_category(start_date: String, end_date: String, category: DrinkCategoryDto) -> EntryListDto
+        |log| log.get_beer_entries_by_category(start_date, end_date, category.into()).map(EntryListDto::new);
+    update_beer_entry(id: String, name: String, alcohol_percentage: f64, volume_ml: f64, notes: String) -> ();
+    update_beer_entry_date(id: String, date: String) -> ();
+    update_beer_entry_consumed_at(id: String, consumed_at: String) -> ();
+    set_beer_entry_category(id: String, category: DrinkCategoryDto, subtype: Option<String>, style: Option<String>) -> ()
+        |log| log.set_beer_entry_category(id, category.into(), subtype, style);
+    delete_beer_entry(id: String) -> ();
+    clear_all_data() -> ();
+
+    // Presets
+    add_preset(name: String, category: DrinkCategoryDto, volume_ml: f64, alcohol_percentage: f64, favorite: bool) -> PresetDto
+        |log| log.add_preset(name, category.into(), volume_ml, alcohol_percentage, favorite).map(PresetDto::from);
+    get_preset(id: String) -> PresetDto |log| log.get_preset(id).map(PresetDto::from);
+    list_presets(favorites_only: bool) -> PresetListDto |log| log.list_presets(favorites_only).map(PresetListDto::new);
+    update_preset(
+        id: String,
+        name: String,
+        category: DrinkCategoryDto,
+        volume_ml: f64,
+        alcohol_percentage: f64,
+        favorite: bool
+    ) -> () |log| log.update_preset(id, name, category.into(), volume_ml, alcohol_percentage, favorite);
+    set_preset_favorite(id: String, favorite: bool) -> ();
+    delete_preset(id: String) -> ();
+    reorder_presets(ids: Vec<String>) -> ();
+    add_beer_entry_from_preset(preset_id: String, notes: String) -> String;
+
+    // Goals
+    set_consumption_goal(daily_target: f64, weekly_target: f64, start_date: String, end_date: String) -> ();
+    set_consumption_goal_in_unit(
+        unit: GoalUnitDto,
+        daily_target: f64,
+        weekly_target: f64,
+        start_date: String,
+        end_date: String
+    ) -> () |log| log.set_consumption_goal_in_unit(unit.into(), daily_target, weekly_target, start_date, end_date);
+    set_goal(kind: GoalKindDto, unit: GoalUnitDto, start_date: String, end_date: String) -> String
+        |log| log.set_goal(kind.into(), unit.into(), start_date, end_date);
+    get_current_goal() -> GoalDto |log| log.get_current_goal().map(GoalDto::from);
+    get_goals_for_date(date: String) -> GoalListDto |log| log.get_goals_for_date(date).map(GoalListDto::new);
+    get_goal_for_date(date: String) -> GoalDto |log| log.get_goal_for_date(date).map(GoalDto::from);
+    list_goals() -> GoalListDto |log| log.list_goals().map(GoalListDto::new);
+    retire_goal(id: String, last_day: String) -> ();
+    evaluate_goals(start_date: String, end_date: String) -> GoalEvaluationDto
+        |log| log.evaluate_goals(start_date, end_date).map(GoalEvaluationDto::new);
+
+    // Totals and analytics
+    get_daily_consumption(date: String) -> f64;
+    get_daily_totals(date: String) -> TotalsDto |log| log.get_daily_totals(date).map(TotalsDto::new);
+    get_weekly_consumption(week_start_date: String) -> f64;
+    get_weekly_totals(week_start_date: String) -> TotalsDto |log| log.get_weekly_totals(week_start_date).map(TotalsDto::new);
+    get_week_totals(date: String, scheme: WeekSchemeDto) -> WeekTotalsDto
+        |log| log.get_week_totals(date, scheme.into()).map(WeekTotalsDto::new);
+    get_category_breakdown(start_date: String, end_date: String) -> CategoryBreakdownDto
+        |log| log.get_category_breakdown(start_date, end_date).map(CategoryBreakdownDto::new);
+    get_period_summary(start_date: String, end_date: String) -> PeriodSummaryDto
+        |log| log.get_period_summary(start_date, end_date).map(PeriodSummaryDto::from);
+    calculate_baseline(start_date: String, end_date: String) -> BaselineCalculationDto
+        |log| log.calculate_baseline(start_date, end_date).map(BaselineCalculationDto::from);
+    save_baseline(baseline: BaselineCalculationDto, effective_from: String) -> BaselineDto
+        |log| log.save_baseline(&baseline.into(), effective_from).map(BaselineDto::from);
+    set_baseline(average_daily_consumption: f64, effective_from: String) -> BaselineDto
+        |log| log.set_baseline(average_daily_consumption, effective_from).map(BaselineDto::from);
+    get_baseline() -> BaselineDto |log| log.get_baseline().map(BaselineDto::from);
+    get_baseline_for_date(date: String) -> BaselineDto |log| log.get_baseline_for_date(date).map(BaselineDto::from);
+    list_baselines() -> BaselineListDto |log| log.list_baselines().map(BaselineListDto::new);
+    get_progress_stats(period_start: String, period_end: String) -> ProgressStatsDto
+        |log| log.get_progress_stats(period_start, period_end).map(ProgressStatsDto::new);
+
+    // Import and export
+    export_backup() -> BackupDto |log| log.export_backup().map(BackupDto::from);
+    export_json() -> String;
+    import_backup(backup: BackupDto, mode: ImportModeDto) -> ImportReportDto
+        |log| log.import_backup(backup.into(), mode.into()).map(ImportReportDto::new);
+    import_json(json: String, mode: ImportModeDto) -> ImportReportDto
+        |log| log.import_json(json, mode.into()).map(ImportReportDto::new);
+    export_csv(start_date: String, end_date: String, delimiter: char) -> String;
+    import_csv(data: String, mapping: CsvMapping) -> CsvImportReportDto
+        |log| log.import_csv(data, mapping).map(CsvImportReportDto::new);
+    import_from(source: ImportSourceDto, data: String) -> ImporterReportDto
+        |log| log.import_from(source.into(), data).map(ImporterReportDto::new);
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BeerEntry, Consumption, StandardDrink};
+
+/// What kind of drink an entry or preset is. Entries logged before categories
+/// existed are beers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum DrinkCategory {
+    #[default]
+    Beer,
+    Wine,
+    Spirits,
+    Cider,
+    Cocktail,
+    NonAlcoholic,
+    Other,
+}
+
+impl DrinkCategory {
+    /// Every category, in the order breakdowns are reported.
+    pub const ALL: [DrinkCategory; 7] = [
+        DrinkCategory::Beer,
+        DrinkCategory::Wine,
+        DrinkCategory::Spirits,
+        DrinkCategory::Cider,
+        DrinkCategory::Cocktail,
+        DrinkCategory::NonAlcoholic,
+        DrinkCategory::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DrinkCategory::Beer => "beer",
+            DrinkCategory::Wine => "wine",
+            DrinkCategory::Spirits => "spirits",
+            DrinkCategory::Cider => "cider",
+            DrinkCategory::Cocktail => "cocktail",
+            DrinkCategory::NonAlcoholic => "non_alcoholic",
+            DrinkCategory::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == s)
+    }
+
+    /// Reads a category as people write it, e.g. `Wine`, `non-alcoholic` or `spirit`.
+    /// Anything unrecognised is `Other`.
+    pub fn from_label(label: &str) -> Self {
+        let label = label.trim().to_lowercase().replace(['-', ' '], "_");
+        match label.as_str() {
+            "spirit" | "liquor" => DrinkCategory::Spirits,
+            "alcohol_free" | "na" | "non_alcoholic_beer" => DrinkCategory::NonAlcoholic,
+            _ => Self::parse(&label).unwrap_or(DrinkCategory::Other),
+        }
+    }
+}
+
+/// Consumption of one category over a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct CategoryTotals {
+    pub category: DrinkCategory,
+    pub entries: u32,
+    pub totals: Consumption,
+}
+
+/// Totals per category, in [`DrinkCategory::ALL`] order, for the categories that have entries.
+pub(crate) fn category_totals(entries: &[BeerEntry], definition: &StandardDrink) -> Vec<CategoryTotals> {
+    DrinkCategory::ALL
+        .into_iter()
+        .filter_map(|category| {
+            let matching: Vec<&BeerEntry> = entries.iter().filter(|e| e.category == category).collect();
+            (!matching.is_empty()).then(|| CategoryTotals {
+                category,
+                entries: matching.len() as u32,
+                totals: Consumption::from_entries(matching.iter().copied(), definition),
+            })
+        })
+        .collect()
+}
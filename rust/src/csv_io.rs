@@ -0,0 +1,346 @@
+//! RFC 4180 CSV import and export of entries.
+//!
+//! Export always writes the columns in [`CSV_COLUMNS`]. Import reads any layout
+//! described by a [`CsvMapping`]; the default mapping reads BrewLog's own export.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    parse_timestamp, BeerEntry, BrewLog, BrewLogError, DayBoundary, DrinkCategory, Setting, SettingValue,
+    VolumeUnit,
+};
+
+/// Header of exported files, in order.
+pub const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "date",
+    "consumed_at",
+    "name",
+    "category",
+    "subtype",
+    "style",
+    "alcohol_percentage",
+    "volume_ml",
+    "notes",
+];
+
+/// A column picked by header name (matched case-insensitively) or by 1-based position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Position(usize),
+    Name(String),
+}
+
+impl From<&str> for ColumnRef {
+    fn from(name: &str) -> Self {
+        ColumnRef::Name(name.to_string())
+    }
+}
+
+/// How to read entries from a CSV file. Fields left out take their value from
+/// the default mapping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[serde(default)]
+pub struct CsvMapping {
+    /// Must be an ASCII character.
+    pub delimiter: char,
+    /// Without a header row, columns can only be picked by position.
+    pub has_header: bool,
+    /// Numbers are written `4,5` rather than `4.5`.
+    pub decimal_comma: bool,
+    pub date: ColumnRef,
+    /// chrono format of the date column. A format with a time of day sets the entry's
+    /// `consumed_at` too, read in the database's time zone unless it includes an offset.
+    pub date_format: String,
+    /// A separate `HH:MM` or `HH:MM:SS` column. When set, `date_format` describes the
+    /// date cell alone, and rows with an empty time cell get no `consumed_at`.
+    pub time: Option<ColumnRef>,
+    /// RFC 3339 timestamps, as written by export.
+    pub consumed_at: Option<ColumnRef>,
+    pub name: ColumnRef,
+    /// ABV in percent; a trailing `%` is ignored. Empty cells use the `default_beer_strength` setting.
+    pub abv: Option<ColumnRef>,
+    /// Empty cells use the `default_beer_size` setting.
+    pub volume: Option<ColumnRef>,
+    pub volume_unit: VolumeUnit,
+    pub notes: Option<ColumnRef>,
+    pub category: Option<ColumnRef>,
+    pub subtype: Option<ColumnRef>,
+    pub style: Option<ColumnRef>,
+    /// Rows with an id overwrite the entry with that id.
+    pub id: Option<ColumnRef>,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            delimiter: ',',
+            has_header: true,
+            decimal_comma: false,
+            date: "date".into(),
+            date_format: "%Y-%m-%d".to_string(),
+            time: None,
+            consumed_at: Some("consumed_at".into()),
+            name: "name".into(),
+            abv: Some("alcohol_percentage".into()),
+            volume: Some("volume_ml".into()),
+            volume_unit: VolumeUnit::Ml,
+            notes: Some("notes".into()),
+            category: Some("category".into()),
+            subtype: Some("subtype".into()),
+            style: Some("style".into()),
+            id: Some("id".into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct CsvRowError {
+    /// 1-based line the row starts on.
+    pub line: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct CsvImportReport {
+    pub imported: u32,
+    pub errors: Vec<CsvRowError>,
+}
+
+fn delimiter_byte(delimiter: char) -> Result<u8, BrewLogError> {
+    u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| BrewLogError::InvalidInput(format!("Delimiter must be an ASCII character, got {delimiter:?}")))
+}
+
+pub(crate) fn csv_error(e: csv::Error) -> BrewLogError {
+    BrewLogError::InvalidInput(format!("CSV: {e}"))
+}
+
+/// Column positions of a mapping, resolved against a header row.
+pub(crate) struct ResolvedMapping<'a> {
+    mapping: &'a CsvMapping,
+    date: usize,
+    time: Option<usize>,
+    consumed_at: Option<usize>,
+    name: usize,
+    abv: Option<usize>,
+    volume: Option<usize>,
+    notes: Option<usize>,
+    category: Option<usize>,
+    subtype: Option<usize>,
+    style: Option<usize>,
+    id: Option<usize>,
+}
+
+impl CsvMapping {
+    pub(crate) fn reader<'d>(&self, data: &'d str) -> Result<csv::Reader<&'d [u8]>, BrewLogError> {
+        Ok(csv::ReaderBuilder::new()
+            .delimiter(delimiter_byte(self.delimiter)?)
+            .has_headers(self.has_header)
+            .flexible(true)
+            .from_reader(data.as_bytes()))
+    }
+
+    /// Finds the mapped columns in `headers`. A missing column fails the whole import,
+    /// unless it is an optional column left at its default name.
+    pub(crate) fn resolve(&self, headers: Option<&csv::StringRecord>) -> Result<ResolvedMapping<'_>, BrewLogError> {
+        let find = |column: &ColumnRef| -> Result<usize, BrewLogError> {
+            match (column, headers) {
+                (ColumnRef::Position(0), _) => Err(BrewLogError::InvalidInput("Column positions start at 1".to_string())),
+                (ColumnRef::Position(p), _) => Ok(p - 1),
+                (ColumnRef::Name(name), Some(headers)) => headers
+                    .iter()
+                    .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+                    .ok_or_else(|| BrewLogError::InvalidInput(format!("No column named {name:?}"))),
+                (ColumnRef::Name(name), None) => Err(BrewLogError::InvalidInput(format!(
+                    "Column {name:?} is named but the file has no header row"
+                ))),
+            }
+        };
+        // Only the default mapping's own columns may be absent, so a file written
+        // before a column was added still imports; a name the caller chose must exist
+        let find_optional = |column: &Option<ColumnRef>, default: &Option<ColumnRef>| match column {
+            Some(c @ ColumnRef::Name(_)) if column == default => Ok(find(c).ok()),
+            Some(c) => find(c).map(Some),
+            None => Ok(None),
+        };
+        let default = CsvMapping::default();
+
+        Ok(ResolvedMapping {
+            mapping: self,
+            date: find(&self.date)?,
+            time: find_optional(&self.time, &default.time)?,
+            consumed_at: find_optional(&self.consumed_at, &default.consumed_at)?,
+            name: find(&self.name)?,
+            abv: find_optional(&self.abv, &default.abv)?,
+            volume: find_optional(&self.volume, &default.volume)?,
+            notes: find_optional(&self.notes, &default.notes)?,
+            category: find_optional(&self.category, &default.category)?,
+            subtype: find_optional(&self.subtype, &default.subtype)?,
+            style: find_optional(&self.style, &default.style)?,
+            id: find_optional(&self.id, &default.id)?,
+        })
+    }
+}
+
+/// Settings a row falls back on, read once per import.
+pub(crate) struct RowDefaults {
+    pub boundary: DayBoundary,
+    pub volume_ml: f64,
+    pub abv: f64,
+}
+
+impl RowDefaults {
+    pub(crate) fn load(log: &BrewLog) -> Result<Self, BrewLogError> {
+        let number = |setting| match log.get_setting(setting)? {
+            SettingValue::Number(n) => Ok(n),
+            other => Err(BrewLogError::DatabaseError(format!("Invalid stored {}: {other}", setting.key()))),
+        };
+        Ok(RowDefaults {
+            boundary: log.get_day_boundary()?,
+            volume_ml: number(Setting::DefaultBeerSize)?,
+            abv: number(Setting::DefaultBeerStrength)?,
+        })
+    }
+}
+
+impl ResolvedMapping<'_> {
+    /// Turns one row into an entry. The id is empty unless the mapping has an id column.
+    pub(crate) fn entry(&self, row: &csv::StringRecord, defaults: &RowDefaults) -> Result<BeerEntry, BrewLogError> {
+        let cell = |index: Option<usize>| index.and_then(|i| row.get(i)).map(str::trim).filter(|v| !v.is_empty());
+        let text = |index: Option<usize>| cell(index).map(str::to_string);
+        let number = |index: Option<usize>, what: &str| -> Result<Option<f64>, BrewLogError> {
+            cell(index)
+                .map(|v| {
+                    let v = v.trim_end_matches('%').trim();
+                    let v = if self.mapping.decimal_comma { v.replace(',', ".") } else { v.to_string() };
+                    v.parse::<f64>()
+                        .map_err(|_| BrewLogError::InvalidInput(format!("{what} is not a number: {v}")))
+                })
+                .transpose()
+        };
+
+        let date_cell = cell(Some(self.date)).ok_or_else(|| BrewLogError::InvalidInput("Date is empty".to_string()))?;
+        let format = &self.mapping.date_format;
+        let day = || {
+            NaiveDate::parse_from_str(date_cell, format)
+                .map_err(|_| BrewLogError::InvalidInput(format!("Date {date_cell:?} does not match format {format:?}")))
+        };
+        let (date, mut consumed_at) = match (self.time, cell(self.time)) {
+            (Some(_), Some(time)) => {
+                let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+                    .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+                    .map_err(|_| BrewLogError::InvalidInput(format!("Time {time:?} is not HH:MM")))?;
+                let at = defaults.boundary.localize(day()?.and_time(time))?;
+                (defaults.boundary.drinking_day(&at)?.to_string(), Some(at.to_rfc3339()))
+            }
+            (Some(_), None) => (day()?.to_string(), None),
+            (None, _) => parse_date_cell(date_cell, format, &defaults.boundary)?,
+        };
+        if let Some(timestamp) = cell(self.consumed_at) {
+            consumed_at = Some(parse_timestamp(timestamp)?.to_rfc3339());
+        }
+
+        Ok(BeerEntry {
+            id: text(self.id).unwrap_or_default(),
+            name: text(Some(self.name)).unwrap_or_default(),
+            alcohol_percentage: number(self.abv, "ABV")?.unwrap_or(defaults.abv),
+            volume_ml: number(self.volume, "Volume")?
+                .map(|v| self.mapping.volume_unit.to_ml(v))
+                .unwrap_or(defaults.volume_ml),
+            date,
+            notes: text(self.notes).unwrap_or_default(),
+            consumed_at,
+            category: cell(self.category).map(DrinkCategory::from_label).unwrap_or_default(),
+            subtype: text(self.subtype),
+            style: text(self.style),
+            created_at: String::new(),
+        })
+    }
+}
+
+/// Reads a date cell as a date with an offset, a local date and time, or a bare date,
+/// whichever `format` describes. Returns the drinking day and, when the cell has a time, `consumed_at`.
+fn parse_date_cell(value: &str, format: &str, boundary: &DayBoundary) -> Result<(String, Option<String>), BrewLogError> {
+    let timestamp = if let Ok(t) = DateTime::parse_from_str(value, format) {
+        t
+    } else if let Ok(local) = NaiveDateTime::parse_from_str(value, format) {
+        boundary.localize(local)?
+    } else {
+        let day = NaiveDate::parse_from_str(value, format)
+            .map_err(|_| BrewLogError::InvalidInput(format!("Date {value:?} does not match format {format:?}")))?;
+        return Ok((day.to_string(), None));
+    };
+    Ok((boundary.drinking_day(&timestamp)?.to_string(), Some(timestamp.to_rfc3339())))
+}
+
+impl BrewLog {
+    /// Entries between two drinking days as CSV with the [`CSV_COLUMNS`] header.
+    pub fn export_csv(&self, start_date: String, end_date: String, delimiter: char) -> Result<String, BrewLogError> {
+        let mut entries = self.get_beer_entries(start_date, end_date)?;
+        entries.reverse();
+
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter_byte(delimiter)?)
+            .from_writer(Vec::new());
+        writer.write_record(CSV_COLUMNS).map_err(csv_error)?;
+        for e in &entries {
+            writer
+                .write_record([
+                    e.id.as_str(),
+                    e.date.as_str(),
+                    e.consumed_at.as_deref().unwrap_or_default(),
+                    e.name.as_str(),
+                    e.category.as_str(),
+                    e.subtype.as_deref().unwrap_or_default(),
+                    e.style.as_deref().unwrap_or_default(),
+                    &e.alcohol_percentage.to_string(),
+                    &e.volume_ml.to_string(),
+                    e.notes.as_str(),
+                ])
+                .map_err(csv_error)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| BrewLogError::InvalidInput(e.to_string()))?;
+        String::from_utf8(bytes).map_err(|e| BrewLogError::InvalidInput(e.to_string()))
+    }
+
+    /// Adds every row of `data` as an entry. Rows that can't be read are reported
+    /// and skipped; a file whose mapped columns are missing is rejected outright,
+    /// though optional columns left at their default names may be absent.
+    pub fn import_csv(&self, data: String, mapping: CsvMapping) -> Result<CsvImportReport, BrewLogError> {
+        let mut reader = mapping.reader(&data)?;
+        let headers = if mapping.has_header { Some(reader.headers().map_err(csv_error)?.clone()) } else { None };
+        let resolved = mapping.resolve(headers.as_ref())?;
+        let defaults = RowDefaults::load(self)?;
+
+        let mut report = CsvImportReport {
+            imported: 0,
+            errors: Vec::new(),
+        };
+        for record in reader.records() {
+            let (line, result) = match record {
+                Ok(row) => (
+                    row.position().map_or(0, |p| p.line()),
+                    resolved.entry(&row, &defaults).and_then(|entry| self.save_beer_entry(entry)),
+                ),
+                Err(e) => (e.position().map_or(0, |p| p.line()), Err(csv_error(e))),
+            };
+            match result {
+                Ok(_) => report.imported += 1,
+                Err(e) => report.errors.push(CsvRowError {
+                    line,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    }
+}
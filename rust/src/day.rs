@@ -0,0 +1,65 @@
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::BrewLogError;
+
+/// Where one drinking day ends and the next begins: `cutoff_hour` o'clock in
+/// `time_zone`. A drink at 01:00 with a 3 AM cut-off counts towards the previous day.
+/// Unless configured, days end at 3 AM UTC.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct DayBoundary {
+    pub cutoff_hour: u32,
+    /// IANA time zone name, e.g. `Europe/Berlin`.
+    pub time_zone: String,
+}
+
+impl Default for DayBoundary {
+    fn default() -> Self {
+        DayBoundary {
+            cutoff_hour: 3,
+            time_zone: "UTC".to_string(),
+        }
+    }
+}
+
+impl DayBoundary {
+    pub fn validate(&self) -> Result<(), BrewLogError> {
+        if self.cutoff_hour > 23 {
+            return Err(BrewLogError::InvalidInput("Cut-off hour must be between 0 and 23".to_string()));
+        }
+        self.tz().map(|_| ())
+    }
+
+    fn tz(&self) -> Result<Tz, BrewLogError> {
+        self.time_zone
+            .parse::<Tz>()
+            .map_err(|_| BrewLogError::InvalidInput(format!("Unknown time zone: {}", self.time_zone)))
+    }
+
+    /// The drinking day an instant belongs to.
+    pub fn drinking_day<O: TimeZone>(&self, at: &DateTime<O>) -> Result<NaiveDate, BrewLogError> {
+        let local = at.with_timezone(&self.tz()?).naive_local();
+        Ok((local - Duration::hours(self.cutoff_hour as i64)).date())
+    }
+
+    pub fn today(&self) -> Result<NaiveDate, BrewLogError> {
+        self.drinking_day(&Utc::now())
+    }
+
+    /// The current time in this boundary's zone, for stamping new entries.
+    pub fn now(&self) -> Result<DateTime<FixedOffset>, BrewLogError> {
+        Ok(Utc::now().with_timezone(&self.tz()?).fixed_offset())
+    }
+
+    /// Pins a wall-clock time in this boundary's zone to an instant. Times repeated
+    /// when clocks go back resolve to the first; times skipped when they go forward are rejected.
+    pub fn localize(&self, local: NaiveDateTime) -> Result<DateTime<FixedOffset>, BrewLogError> {
+        self.tz()?
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|t| t.fixed_offset())
+            .ok_or_else(|| BrewLogError::InvalidInput(format!("{local} does not exist in {}", self.time_zone)))
+    }
+}
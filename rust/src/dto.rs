@@ -0,0 +1,1579 @@
+//! JSON documents exchanged over the C and JNI boundary.
+//!
+//! Every document is an object whose `schema_version` says which revision of
+//! this contract produced it. Each C and JNI entry point returns a [`ResultDto`]
+//! envelope whose `payload` holds what was asked for, e.g. the entry list
+//! returned by `get_beer_entries_json`:
+//!
+//! ```json
+//! {
+//!   "schema_version": 2,
+//!   "code": 0,
+//!   "message": null,
+//!   "payload": {
+//!     "schema_version": 2,
+//!     "entries": [
+//!       { "id": "…", "name": "Pale Ale", "alcohol_percentage": 5.0, "volume_ml": 500.0,
+//!         "date": "2024-03-01", "notes": "", "consumed_at": "2024-03-01T19:30:00+00:00",
+//!         "category": "beer", "subtype": null, "style": "APA",
+//!         "created_at": "2024-03-01T19:31:02+00:00" }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! A failure has a non-zero [`ErrorCode`] in `code`, the error text in
+//! `message` and a null `payload`.
+//!
+//! Field names are fixed here with explicit renames rather than following the
+//! Rust field names, so refactoring the core types can't change the wire format.
+//! The DTOs are separate types from the core records they mirror, down to the
+//! enums and totals nested in them, so changing a core type fails to compile in
+//! the `From` conversions below instead of quietly changing what is sent. Lists
+//! and reports are documents with their own `schema_version`; single records,
+//! such as a goal, a preset or a setting's value, are carried in the envelope as
+//! they are. A backup is the exception: [`BackupDto`] is the file `export_json`
+//! writes, versioned by its own `format` and `version`. Documents are parsed strictly:
+//! unknown fields (such as a camelCase `alcoholPercentage`) are an error rather
+//! than being skipped. The schema clients can validate against is
+//! [`ffi_json_schema`], published as `schema/brewlog-ffi.schema.json`.
+
+use schemars::gen::SchemaGenerator;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    Backup, Baseline, BaselineRecord, BeerEntry, BrewLogError, CategoryTotals, Consumption, ConsumptionGoal,
+    CsvImportReport, CsvRowError, DayBoundary, DrinkCategory, DrinkPreset, GoalCheck, GoalEvaluation, GoalKind,
+    GoalStatus, GoalUnit, ImportMode, ImportReport, ImportSource, ImporterReport, PeriodEvaluation, PeriodSummary,
+    ProgressStats, RecordAction, RecordKind, RecordOutcome, Setting, SettingEntry, SettingValue, StandardDrink,
+    WeekScheme, WeekTotals,
+};
+
+/// Revision of the FFI JSON contract. Bump it whenever a field is renamed,
+/// removed or changes meaning; adding an optional field does not need a bump.
+pub const FFI_SCHEMA_VERSION: u32 = 2;
+
+/// A top-level JSON document sent across the FFI.
+pub trait FfiDocument: Serialize + DeserializeOwned + JsonSchema {
+    fn schema_version(&self) -> u32;
+
+    fn to_json(&self) -> Result<String, BrewLogError> {
+        serde_json::to_string(self).map_err(|e| BrewLogError::InvalidInput(e.to_string()))
+    }
+
+    /// Parses a document, rejecting ones written by a newer contract before
+    /// looking at any other field.
+    fn from_json(json: &str) -> Result<Self, BrewLogError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| BrewLogError::InvalidInput(format!("Invalid JSON: {e}")))?;
+        match value.get("schema_version").and_then(serde_json::Value::as_u64) {
+            Some(version) if version > FFI_SCHEMA_VERSION as u64 => {
+                return Err(BrewLogError::IncompatibleDatabase(format!(
+                    "schema_version {version} is newer than the {FFI_SCHEMA_VERSION} this version of BrewLog reads"
+                )))
+            }
+            Some(_) => {}
+            None => return Err(BrewLogError::InvalidInput("Missing schema_version".to_string())),
+        }
+        serde_json::from_value(value).map_err(|e| BrewLogError::InvalidInput(format!("Invalid document: {e}")))
+    }
+}
+
+/// Declares a fieldless enum's wire form, naming every variant explicitly, and
+/// the conversions to and from the core enum it mirrors.
+macro_rules! wire_enum {
+    (
+        $(#[$meta:meta])*
+        $dto:ident as $schema:literal <=> $core:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $name:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+        #[schemars(rename = $schema)]
+        pub enum $dto {
+            $($(#[$variant_meta])* #[serde(rename = $name)] $variant,)*
+        }
+
+        impl From<$core> for $dto {
+            fn from(value: $core) -> Self {
+                match value {
+                    $($core::$variant => $dto::$variant,)*
+                }
+            }
+        }
+
+        impl From<$dto> for $core {
+            fn from(value: $dto) -> Self {
+                match value {
+                    $($dto::$variant => $core::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+wire_enum! {
+    /// What kind of drink an entry or preset is.
+    DrinkCategoryDto as "DrinkCategory" <=> DrinkCategory {
+        Beer = "beer",
+        Wine = "wine",
+        Spirits = "spirits",
+        Cider = "cider",
+        Cocktail = "cocktail",
+        NonAlcoholic = "non_alcoholic",
+        Other = "other",
+    }
+}
+
+/// Entries sent without a category are beers, as in the core.
+impl Default for DrinkCategoryDto {
+    fn default() -> Self {
+        DrinkCategoryDto::Beer
+    }
+}
+
+wire_enum! {
+    /// The quantity a goal target is expressed in.
+    GoalUnitDto as "GoalUnit" <=> GoalUnit {
+        VolumeMl = "volume_ml",
+        EthanolGrams = "ethanol_grams",
+        StandardDrinks = "standard_drinks",
+    }
+}
+
+/// An amount consumed, as liquid volume, pure ethanol and standard drinks.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Consumption")]
+pub struct ConsumptionDto {
+    #[serde(rename = "volume_ml")]
+    pub volume_ml: f64,
+    #[serde(rename = "ethanol_grams")]
+    pub ethanol_grams: f64,
+    #[serde(rename = "standard_drinks")]
+    pub standard_drinks: f64,
+}
+
+impl From<Consumption> for ConsumptionDto {
+    fn from(consumption: Consumption) -> Self {
+        ConsumptionDto {
+            volume_ml: consumption.volume_ml,
+            ethanol_grams: consumption.ethanol_grams,
+            standard_drinks: consumption.standard_drinks,
+        }
+    }
+}
+
+impl From<ConsumptionDto> for Consumption {
+    fn from(dto: ConsumptionDto) -> Self {
+        Consumption { volume_ml: dto.volume_ml, ethanol_grams: dto.ethanol_grams, standard_drinks: dto.standard_drinks }
+    }
+}
+
+/// Consumption of one category over a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CategoryTotals")]
+pub struct CategoryTotalsDto {
+    #[serde(rename = "category")]
+    pub category: DrinkCategoryDto,
+    #[serde(rename = "entries")]
+    pub entries: u32,
+    #[serde(rename = "totals")]
+    pub totals: ConsumptionDto,
+}
+
+impl From<CategoryTotals> for CategoryTotalsDto {
+    fn from(totals: CategoryTotals) -> Self {
+        CategoryTotalsDto { category: totals.category.into(), entries: totals.entries, totals: totals.totals.into() }
+    }
+}
+
+impl From<CategoryTotalsDto> for CategoryTotals {
+    fn from(dto: CategoryTotalsDto) -> Self {
+        CategoryTotals { category: dto.category.into(), entries: dto.entries, totals: dto.totals.into() }
+    }
+}
+
+/// One entry as it appears on the wire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Entry")]
+pub struct EntryDto {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "alcohol_percentage")]
+    pub alcohol_percentage: f64,
+    #[serde(rename = "volume_ml")]
+    pub volume_ml: f64,
+    /// The day the entry counts towards, `YYYY-MM-DD`.
+    #[serde(rename = "date")]
+    pub date: String,
+    #[serde(rename = "notes", default)]
+    pub notes: String,
+    /// RFC 3339 with the UTC offset in force at the time.
+    #[serde(rename = "consumed_at", default)]
+    pub consumed_at: Option<String>,
+    #[serde(rename = "category", default)]
+    pub category: DrinkCategoryDto,
+    #[serde(rename = "subtype", default)]
+    pub subtype: Option<String>,
+    #[serde(rename = "style", default)]
+    pub style: Option<String>,
+    #[serde(rename = "created_at", default)]
+    pub created_at: String,
+}
+
+impl From<BeerEntry> for EntryDto {
+    fn from(entry: BeerEntry) -> Self {
+        EntryDto {
+            id: entry.id,
+            name: entry.name,
+            alcohol_percentage: entry.alcohol_percentage,
+            volume_ml: entry.volume_ml,
+            date: entry.date,
+            notes: entry.notes,
+            consumed_at: entry.consumed_at,
+            category: entry.category.into(),
+            subtype: entry.subtype,
+            style: entry.style,
+            created_at: entry.created_at,
+        }
+    }
+}
+
+impl From<EntryDto> for BeerEntry {
+    fn from(dto: EntryDto) -> Self {
+        BeerEntry {
+            id: dto.id,
+            name: dto.name,
+            alcohol_percentage: dto.alcohol_percentage,
+            volume_ml: dto.volume_ml,
+            date: dto.date,
+            notes: dto.notes,
+            consumed_at: dto.consumed_at,
+            category: dto.category.into(),
+            subtype: dto.subtype,
+            style: dto.style,
+            created_at: dto.created_at,
+        }
+    }
+}
+
+/// The entries in a date range, as returned by `get_beer_entries_json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "EntryList")]
+pub struct EntryListDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "entries")]
+    pub entries: Vec<EntryDto>,
+}
+
+impl EntryListDto {
+    pub fn new(entries: Vec<BeerEntry>) -> Self {
+        EntryListDto { schema_version: FFI_SCHEMA_VERSION, entries: entries.into_iter().map(EntryDto::from).collect() }
+    }
+}
+
+impl FfiDocument for EntryListDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// What a goal limits, tagged by `type`. Amounts are in the goal's `unit`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", deny_unknown_fields)]
+#[schemars(rename = "GoalKind")]
+pub enum GoalKindDto {
+    /// A limit per day and a limit per week.
+    #[serde(rename = "daily_weekly")]
+    DailyWeekly {
+        #[serde(rename = "daily_target")]
+        daily_target: f64,
+        #[serde(rename = "weekly_target")]
+        weekly_target: f64,
+    },
+    /// At least this many days without alcohol in each week.
+    #[serde(rename = "alcohol_free_days")]
+    AlcoholFreeDays {
+        #[serde(rename = "days_per_week")]
+        days_per_week: u32,
+    },
+    /// A separate daily limit for each weekday, Monday first.
+    #[serde(rename = "weekday_limits")]
+    WeekdayLimits {
+        #[serde(rename = "limits")]
+        limits: [f64; 7],
+    },
+    /// A limit per drinking session; sessions break at gaps of over three hours.
+    #[serde(rename = "session_cap")]
+    SessionCap {
+        #[serde(rename = "max_per_session")]
+        max_per_session: f64,
+    },
+    /// A limit per calendar month.
+    #[serde(rename = "monthly_cap")]
+    MonthlyCap {
+        #[serde(rename = "max_per_month")]
+        max_per_month: f64,
+    },
+}
+
+impl From<GoalKind> for GoalKindDto {
+    fn from(kind: GoalKind) -> Self {
+        match kind {
+            GoalKind::DailyWeekly { daily_target, weekly_target } => {
+                GoalKindDto::DailyWeekly { daily_target, weekly_target }
+            }
+            GoalKind::AlcoholFreeDays { days_per_week } => GoalKindDto::AlcoholFreeDays { days_per_week },
+            GoalKind::WeekdayLimits { limits } => GoalKindDto::WeekdayLimits { limits },
+            GoalKind::SessionCap { max_per_session } => GoalKindDto::SessionCap { max_per_session },
+            GoalKind::MonthlyCap { max_per_month } => GoalKindDto::MonthlyCap { max_per_month },
+        }
+    }
+}
+
+impl From<GoalKindDto> for GoalKind {
+    fn from(dto: GoalKindDto) -> Self {
+        match dto {
+            GoalKindDto::DailyWeekly { daily_target, weekly_target } => {
+                GoalKind::DailyWeekly { daily_target, weekly_target }
+            }
+            GoalKindDto::AlcoholFreeDays { days_per_week } => GoalKind::AlcoholFreeDays { days_per_week },
+            GoalKindDto::WeekdayLimits { limits } => GoalKind::WeekdayLimits { limits },
+            GoalKindDto::SessionCap { max_per_session } => GoalKind::SessionCap { max_per_session },
+            GoalKindDto::MonthlyCap { max_per_month } => GoalKind::MonthlyCap { max_per_month },
+        }
+    }
+}
+
+/// One goal as it appears on the wire. `kind` holds the limits;
+/// `daily_target` and `weekly_target` repeat a `daily_weekly` kind's and are
+/// zero for other kinds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Goal")]
+pub struct GoalDto {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "kind")]
+    pub kind: GoalKindDto,
+    #[serde(rename = "daily_target")]
+    pub daily_target: f64,
+    #[serde(rename = "weekly_target")]
+    pub weekly_target: f64,
+    #[serde(rename = "unit")]
+    pub unit: GoalUnitDto,
+    #[serde(rename = "start_date")]
+    pub start_date: String,
+    #[serde(rename = "end_date")]
+    pub end_date: String,
+    /// First day the goal applies, `YYYY-MM-DD`.
+    #[serde(rename = "effective_from")]
+    pub effective_from: String,
+    /// Day a later goal took over; null while the goal is current.
+    #[serde(rename = "effective_to", default)]
+    pub effective_to: Option<String>,
+    #[serde(rename = "retired_at", default)]
+    pub retired_at: Option<String>,
+    #[serde(rename = "created_at", default)]
+    pub created_at: String,
+}
+
+impl From<ConsumptionGoal> for GoalDto {
+    fn from(goal: ConsumptionGoal) -> Self {
+        GoalDto {
+            id: goal.id,
+            kind: goal.kind.into(),
+            daily_target: goal.daily_target,
+            weekly_target: goal.weekly_target,
+            unit: goal.unit.into(),
+            start_date: goal.start_date,
+            end_date: goal.end_date,
+            effective_from: goal.effective_from,
+            effective_to: goal.effective_to,
+            retired_at: goal.retired_at,
+            created_at: goal.created_at,
+        }
+    }
+}
+
+impl From<GoalDto> for ConsumptionGoal {
+    fn from(dto: GoalDto) -> Self {
+        ConsumptionGoal {
+            id: dto.id,
+            kind: dto.kind.into(),
+            daily_target: dto.daily_target,
+            weekly_target: dto.weekly_target,
+            unit: dto.unit.into(),
+            start_date: dto.start_date,
+            end_date: dto.end_date,
+            effective_from: dto.effective_from,
+            effective_to: dto.effective_to,
+            retired_at: dto.retired_at,
+            created_at: dto.created_at,
+        }
+    }
+}
+
+/// Goals, as returned by `list_goals` and `get_goals_for_date`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "GoalList")]
+pub struct GoalListDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "goals")]
+    pub goals: Vec<GoalDto>,
+}
+
+impl GoalListDto {
+    pub fn new(goals: Vec<ConsumptionGoal>) -> Self {
+        GoalListDto { schema_version: FFI_SCHEMA_VERSION, goals: goals.into_iter().map(GoalDto::from).collect() }
+    }
+}
+
+impl FfiDocument for GoalListDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// Statistics for an inclusive date range.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "PeriodSummary")]
+pub struct PeriodSummaryDto {
+    #[serde(rename = "start_date")]
+    pub start_date: String,
+    #[serde(rename = "end_date")]
+    pub end_date: String,
+    #[serde(rename = "days")]
+    pub days: u32,
+    #[serde(rename = "drinking_days")]
+    pub drinking_days: u32,
+    #[serde(rename = "dry_days")]
+    pub dry_days: u32,
+    #[serde(rename = "total")]
+    pub total: ConsumptionDto,
+    #[serde(rename = "daily_average")]
+    pub daily_average: ConsumptionDto,
+    #[serde(rename = "weekly_average")]
+    pub weekly_average: ConsumptionDto,
+    #[serde(rename = "median_day")]
+    pub median_day: ConsumptionDto,
+    #[serde(rename = "max_day")]
+    pub max_day: ConsumptionDto,
+    /// The day with the most ethanol; null if no alcohol was logged.
+    #[serde(rename = "max_day_date", default)]
+    pub max_day_date: Option<String>,
+    #[serde(rename = "by_category", default)]
+    pub by_category: Vec<CategoryTotalsDto>,
+}
+
+impl From<PeriodSummary> for PeriodSummaryDto {
+    fn from(summary: PeriodSummary) -> Self {
+        PeriodSummaryDto {
+            start_date: summary.start_date,
+            end_date: summary.end_date,
+            days: summary.days,
+            drinking_days: summary.drinking_days,
+            dry_days: summary.dry_days,
+            total: summary.total.into(),
+            daily_average: summary.daily_average.into(),
+            weekly_average: summary.weekly_average.into(),
+            median_day: summary.median_day.into(),
+            max_day: summary.max_day.into(),
+            max_day_date: summary.max_day_date,
+            by_category: summary.by_category.into_iter().map(CategoryTotalsDto::from).collect(),
+        }
+    }
+}
+
+impl From<PeriodSummaryDto> for PeriodSummary {
+    fn from(dto: PeriodSummaryDto) -> Self {
+        PeriodSummary {
+            start_date: dto.start_date,
+            end_date: dto.end_date,
+            days: dto.days,
+            drinking_days: dto.drinking_days,
+            dry_days: dto.dry_days,
+            total: dto.total.into(),
+            daily_average: dto.daily_average.into(),
+            weekly_average: dto.weekly_average.into(),
+            median_day: dto.median_day.into(),
+            max_day: dto.max_day.into(),
+            max_day_date: dto.max_day_date,
+            by_category: dto.by_category.into_iter().map(CategoryTotals::from).collect(),
+        }
+    }
+}
+
+/// A baseline worked out from past entries but not yet saved, as returned by
+/// `calculate_baseline` and accepted by `save_baseline`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "BaselineCalculation")]
+pub struct BaselineCalculationDto {
+    #[serde(rename = "average_daily_consumption")]
+    pub average_daily_consumption: f64,
+    #[serde(rename = "average_weekly_consumption")]
+    pub average_weekly_consumption: f64,
+    #[serde(rename = "calculated_date")]
+    pub calculated_date: String,
+    #[serde(rename = "summary")]
+    pub summary: PeriodSummaryDto,
+}
+
+impl From<Baseline> for BaselineCalculationDto {
+    fn from(baseline: Baseline) -> Self {
+        BaselineCalculationDto {
+            average_daily_consumption: baseline.average_daily_consumption,
+            average_weekly_consumption: baseline.average_weekly_consumption,
+            calculated_date: baseline.calculated_date,
+            summary: baseline.summary.into(),
+        }
+    }
+}
+
+impl From<BaselineCalculationDto> for Baseline {
+    fn from(dto: BaselineCalculationDto) -> Self {
+        Baseline {
+            average_daily_consumption: dto.average_daily_consumption,
+            average_weekly_consumption: dto.average_weekly_consumption,
+            calculated_date: dto.calculated_date,
+            summary: dto.summary.into(),
+        }
+    }
+}
+
+/// A saved baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Baseline")]
+pub struct BaselineDto {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "version")]
+    #[schemars(range(min = 1))]
+    pub version: u32,
+    #[serde(rename = "average_daily_consumption")]
+    pub average_daily_consumption: f64,
+    #[serde(rename = "average_weekly_consumption")]
+    pub average_weekly_consumption: f64,
+    #[serde(rename = "average_daily_ethanol_grams", default)]
+    pub average_daily_ethanol_grams: Option<f64>,
+    /// The range the baseline was calculated from; null for one set by hand.
+    #[serde(rename = "source_start", default)]
+    pub source_start: Option<String>,
+    #[serde(rename = "source_end", default)]
+    pub source_end: Option<String>,
+    #[serde(rename = "effective_from")]
+    pub effective_from: String,
+    #[serde(rename = "created_at")]
+    pub created_at: String,
+}
+
+impl From<BaselineRecord> for BaselineDto {
+    fn from(record: BaselineRecord) -> Self {
+        BaselineDto {
+            id: record.id,
+            version: record.version,
+            average_daily_consumption: record.average_daily_consumption,
+            average_weekly_consumption: record.average_weekly_consumption,
+            average_daily_ethanol_grams: record.average_daily_ethanol_grams,
+            source_start: record.source_start,
+            source_end: record.source_end,
+            effective_from: record.effective_from,
+            created_at: record.created_at,
+        }
+    }
+}
+
+impl From<BaselineDto> for BaselineRecord {
+    fn from(dto: BaselineDto) -> Self {
+        BaselineRecord {
+            id: dto.id,
+            version: dto.version,
+            average_daily_consumption: dto.average_daily_consumption,
+            average_weekly_consumption: dto.average_weekly_consumption,
+            average_daily_ethanol_grams: dto.average_daily_ethanol_grams,
+            source_start: dto.source_start,
+            source_end: dto.source_end,
+            effective_from: dto.effective_from,
+            created_at: dto.created_at,
+        }
+    }
+}
+
+/// Every saved baseline, as returned by `list_baselines`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "BaselineList")]
+pub struct BaselineListDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "baselines")]
+    pub baselines: Vec<BaselineDto>,
+}
+
+impl BaselineListDto {
+    pub fn new(baselines: Vec<BaselineRecord>) -> Self {
+        BaselineListDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            baselines: baselines.into_iter().map(BaselineDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for BaselineListDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// Progress against the baseline, as returned by `get_progress_stats`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "ProgressStats")]
+pub struct ProgressStatsDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "current_daily_average")]
+    pub current_daily_average: f64,
+    #[serde(rename = "current_weekly_average")]
+    pub current_weekly_average: f64,
+    #[serde(rename = "reduction_percentage")]
+    pub reduction_percentage: f64,
+    /// The baseline compared against; null if none applies.
+    #[serde(rename = "baseline_version", default)]
+    pub baseline_version: Option<u32>,
+    #[serde(rename = "period_start")]
+    pub period_start: String,
+    #[serde(rename = "period_end")]
+    pub period_end: String,
+    #[serde(rename = "summary")]
+    pub summary: PeriodSummaryDto,
+}
+
+impl ProgressStatsDto {
+    pub fn new(stats: ProgressStats) -> Self {
+        ProgressStatsDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            current_daily_average: stats.current_daily_average,
+            current_weekly_average: stats.current_weekly_average,
+            reduction_percentage: stats.reduction_percentage,
+            baseline_version: stats.baseline_version,
+            period_start: stats.period_start,
+            period_end: stats.period_end,
+            summary: stats.summary.into(),
+        }
+    }
+}
+
+impl FfiDocument for ProgressStatsDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+wire_enum! {
+    /// How weeks are delimited when asking for the week a date falls in.
+    WeekSchemeDto as "WeekScheme" <=> WeekScheme {
+        /// ISO 8601 weeks, Monday to Sunday.
+        Iso = "iso",
+        /// Weeks starting on the configured start-of-week day.
+        Configured = "configured",
+    }
+}
+
+/// One week's totals, as returned by `get_week_totals`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "WeekTotals")]
+pub struct WeekTotalsDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "start_date")]
+    pub start_date: String,
+    #[serde(rename = "end_date")]
+    pub end_date: String,
+    /// Set for ISO weeks only.
+    #[serde(rename = "iso_year", default)]
+    pub iso_year: Option<i32>,
+    #[serde(rename = "iso_week", default)]
+    pub iso_week: Option<u32>,
+    #[serde(rename = "totals")]
+    pub totals: ConsumptionDto,
+}
+
+impl WeekTotalsDto {
+    pub fn new(week: WeekTotals) -> Self {
+        WeekTotalsDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            start_date: week.start_date,
+            end_date: week.end_date,
+            iso_year: week.iso_year,
+            iso_week: week.iso_week,
+            totals: week.totals.into(),
+        }
+    }
+}
+
+impl FfiDocument for WeekTotalsDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// Totals for one day or week, as returned by `get_daily_totals` and
+/// `get_weekly_totals`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Totals")]
+pub struct TotalsDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "totals")]
+    pub totals: ConsumptionDto,
+}
+
+impl TotalsDto {
+    pub fn new(totals: Consumption) -> Self {
+        TotalsDto { schema_version: FFI_SCHEMA_VERSION, totals: totals.into() }
+    }
+}
+
+impl FfiDocument for TotalsDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// Totals per category, as returned by `get_category_breakdown`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CategoryBreakdown")]
+pub struct CategoryBreakdownDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "categories")]
+    pub categories: Vec<CategoryTotalsDto>,
+}
+
+impl CategoryBreakdownDto {
+    pub fn new(categories: Vec<CategoryTotals>) -> Self {
+        CategoryBreakdownDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            categories: categories.into_iter().map(CategoryTotalsDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for CategoryBreakdownDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+wire_enum! {
+    /// Whether a period met the goals it was held to.
+    GoalStatusDto as "GoalStatus" <=> GoalStatus {
+        Within = "within",
+        Over = "over",
+        NoGoal = "no_goal",
+    }
+}
+
+/// One goal checked against one period. `margin` is positive when the goal was
+/// met; `unit` is null when the target counts days.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "GoalCheck")]
+pub struct GoalCheckDto {
+    #[serde(rename = "goal_id")]
+    pub goal_id: String,
+    /// The goal kind's `type`, e.g. `session_cap`.
+    #[serde(rename = "kind")]
+    pub kind: String,
+    #[serde(rename = "unit", default)]
+    pub unit: Option<GoalUnitDto>,
+    #[serde(rename = "target")]
+    pub target: f64,
+    #[serde(rename = "actual")]
+    pub actual: f64,
+    #[serde(rename = "status")]
+    pub status: GoalStatusDto,
+    #[serde(rename = "margin")]
+    pub margin: f64,
+}
+
+impl From<GoalCheck> for GoalCheckDto {
+    fn from(check: GoalCheck) -> Self {
+        GoalCheckDto {
+            goal_id: check.goal_id,
+            kind: check.kind,
+            unit: check.unit.map(GoalUnitDto::from),
+            target: check.target,
+            actual: check.actual,
+            status: check.status.into(),
+            margin: check.margin,
+        }
+    }
+}
+
+/// A day, week or month and how it measured up to its goals.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "PeriodEvaluation")]
+pub struct PeriodEvaluationDto {
+    #[serde(rename = "start_date")]
+    pub start_date: String,
+    #[serde(rename = "end_date")]
+    pub end_date: String,
+    #[serde(rename = "days")]
+    pub days: u32,
+    #[serde(rename = "total")]
+    pub total: ConsumptionDto,
+    #[serde(rename = "checks")]
+    pub checks: Vec<GoalCheckDto>,
+    #[serde(rename = "status")]
+    pub status: GoalStatusDto,
+}
+
+impl From<PeriodEvaluation> for PeriodEvaluationDto {
+    fn from(period: PeriodEvaluation) -> Self {
+        PeriodEvaluationDto {
+            start_date: period.start_date,
+            end_date: period.end_date,
+            days: period.days,
+            total: period.total.into(),
+            checks: period.checks.into_iter().map(GoalCheckDto::from).collect(),
+            status: period.status.into(),
+        }
+    }
+}
+
+/// Every day, week and month of a range checked against its goals, as returned
+/// by `evaluate_goals`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "GoalEvaluation")]
+pub struct GoalEvaluationDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "start_date")]
+    pub start_date: String,
+    #[serde(rename = "end_date")]
+    pub end_date: String,
+    #[serde(rename = "days")]
+    pub days: Vec<PeriodEvaluationDto>,
+    #[serde(rename = "weeks")]
+    pub weeks: Vec<PeriodEvaluationDto>,
+    #[serde(rename = "months")]
+    pub months: Vec<PeriodEvaluationDto>,
+    #[serde(rename = "days_with_goal")]
+    pub days_with_goal: u32,
+    #[serde(rename = "days_within_goal")]
+    pub days_within_goal: u32,
+    #[serde(rename = "days_over_goal")]
+    pub days_over_goal: u32,
+    #[serde(rename = "weeks_with_goal")]
+    pub weeks_with_goal: u32,
+    #[serde(rename = "weeks_within_goal")]
+    pub weeks_within_goal: u32,
+    #[serde(rename = "weeks_over_goal")]
+    pub weeks_over_goal: u32,
+    #[serde(rename = "months_with_goal")]
+    pub months_with_goal: u32,
+    #[serde(rename = "months_within_goal")]
+    pub months_within_goal: u32,
+    #[serde(rename = "months_over_goal")]
+    pub months_over_goal: u32,
+}
+
+impl GoalEvaluationDto {
+    pub fn new(evaluation: GoalEvaluation) -> Self {
+        let periods = |periods: Vec<PeriodEvaluation>| periods.into_iter().map(PeriodEvaluationDto::from).collect();
+        GoalEvaluationDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            start_date: evaluation.start_date,
+            end_date: evaluation.end_date,
+            days: periods(evaluation.days),
+            weeks: periods(evaluation.weeks),
+            months: periods(evaluation.months),
+            days_with_goal: evaluation.days_with_goal,
+            days_within_goal: evaluation.days_within_goal,
+            days_over_goal: evaluation.days_over_goal,
+            weeks_with_goal: evaluation.weeks_with_goal,
+            weeks_within_goal: evaluation.weeks_within_goal,
+            weeks_over_goal: evaluation.weeks_over_goal,
+            months_with_goal: evaluation.months_with_goal,
+            months_within_goal: evaluation.months_within_goal,
+            months_over_goal: evaluation.months_over_goal,
+        }
+    }
+}
+
+impl FfiDocument for GoalEvaluationDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// A standard-drink definition, as returned by `get_standard_drink`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "StandardDrink")]
+pub struct StandardDrinkDto {
+    #[serde(rename = "code")]
+    pub code: String,
+    #[serde(rename = "name")]
+    pub name: String,
+    /// Grams of pure ethanol in one standard drink.
+    #[serde(rename = "grams")]
+    pub grams: f64,
+}
+
+impl From<StandardDrink> for StandardDrinkDto {
+    fn from(drink: StandardDrink) -> Self {
+        StandardDrinkDto { code: drink.code.to_string(), name: drink.name.to_string(), grams: drink.grams }
+    }
+}
+
+/// Where one drinking day ends and the next begins, as returned by `get_day_boundary`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "DayBoundary")]
+pub struct DayBoundaryDto {
+    #[serde(rename = "cutoff_hour")]
+    pub cutoff_hour: u32,
+    /// IANA time zone name, e.g. `Europe/Berlin`.
+    #[serde(rename = "time_zone")]
+    pub time_zone: String,
+}
+
+impl From<DayBoundary> for DayBoundaryDto {
+    fn from(boundary: DayBoundary) -> Self {
+        DayBoundaryDto { cutoff_hour: boundary.cutoff_hour, time_zone: boundary.time_zone }
+    }
+}
+
+wire_enum! {
+    /// A user preference, by key.
+    SettingDto as "Setting" <=> Setting {
+        DefaultBeerSize = "default_beer_size",
+        DefaultBeerStrength = "default_beer_strength",
+        EndOfDayHour = "end_of_day_hour",
+        TimeZone = "time_zone",
+        StartOfWeek = "start_of_week",
+        StandardDrink = "standard_drink",
+    }
+}
+
+/// A setting's value: a number or a string, depending on the setting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+#[schemars(rename = "SettingValue")]
+pub enum SettingValueDto {
+    Integer(i64),
+    Number(f64),
+    Text(String),
+}
+
+impl From<SettingValue> for SettingValueDto {
+    fn from(value: SettingValue) -> Self {
+        match value {
+            SettingValue::Integer(i) => SettingValueDto::Integer(i),
+            SettingValue::Number(n) => SettingValueDto::Number(n),
+            SettingValue::Text(s) => SettingValueDto::Text(s),
+        }
+    }
+}
+
+impl From<SettingValueDto> for SettingValue {
+    fn from(dto: SettingValueDto) -> Self {
+        match dto {
+            SettingValueDto::Integer(i) => SettingValue::Integer(i),
+            SettingValueDto::Number(n) => SettingValue::Number(n),
+            SettingValueDto::Text(s) => SettingValue::Text(s),
+        }
+    }
+}
+
+/// A setting with its current value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "SettingEntry")]
+pub struct SettingEntryDto {
+    #[serde(rename = "setting")]
+    pub setting: SettingDto,
+    #[serde(rename = "value")]
+    pub value: SettingValueDto,
+    #[serde(rename = "is_default")]
+    pub is_default: bool,
+}
+
+impl From<SettingEntry> for SettingEntryDto {
+    fn from(entry: SettingEntry) -> Self {
+        SettingEntryDto { setting: entry.setting.into(), value: entry.value.into(), is_default: entry.is_default }
+    }
+}
+
+/// Every setting, as returned by `list_settings`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "SettingList")]
+pub struct SettingListDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "settings")]
+    pub settings: Vec<SettingEntryDto>,
+}
+
+impl SettingListDto {
+    pub fn new(settings: Vec<SettingEntry>) -> Self {
+        SettingListDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            settings: settings.into_iter().map(SettingEntryDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for SettingListDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// A saved drink for one-tap logging.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Preset")]
+pub struct PresetDto {
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "category")]
+    pub category: DrinkCategoryDto,
+    #[serde(rename = "volume_ml")]
+    pub volume_ml: f64,
+    #[serde(rename = "alcohol_percentage")]
+    pub alcohol_percentage: f64,
+    #[serde(rename = "favorite")]
+    pub favorite: bool,
+    /// Presets are listed in increasing `position`.
+    #[serde(rename = "position")]
+    pub position: u32,
+}
+
+impl From<DrinkPreset> for PresetDto {
+    fn from(preset: DrinkPreset) -> Self {
+        PresetDto {
+            id: preset.id,
+            name: preset.name,
+            category: preset.category.into(),
+            volume_ml: preset.volume_ml,
+            alcohol_percentage: preset.alcohol_percentage,
+            favorite: preset.favorite,
+            position: preset.position,
+        }
+    }
+}
+
+impl From<PresetDto> for DrinkPreset {
+    fn from(dto: PresetDto) -> Self {
+        DrinkPreset {
+            id: dto.id,
+            name: dto.name,
+            category: dto.category.into(),
+            volume_ml: dto.volume_ml,
+            alcohol_percentage: dto.alcohol_percentage,
+            favorite: dto.favorite,
+            position: dto.position,
+        }
+    }
+}
+
+/// Presets in order, as returned by `list_presets`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "PresetList")]
+pub struct PresetListDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "presets")]
+    pub presets: Vec<PresetDto>,
+}
+
+impl PresetListDto {
+    pub fn new(presets: Vec<DrinkPreset>) -> Self {
+        PresetListDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            presets: presets.into_iter().map(PresetDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for PresetListDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// A full backup, as returned by `export_backup` and accepted by
+/// `import_backup`. It is the backup file `export_json` writes, so it is
+/// versioned by `format` and `version` rather than an FFI `schema_version`;
+/// the `schema_version` here is the database's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Backup")]
+pub struct BackupDto {
+    /// Always `brewlog-backup`.
+    #[serde(rename = "format")]
+    pub format: String,
+    #[serde(rename = "version")]
+    #[schemars(range(min = 1))]
+    pub version: u32,
+    #[serde(rename = "exported_at")]
+    pub exported_at: String,
+    #[serde(rename = "schema_version")]
+    pub schema_version: u32,
+    #[serde(rename = "entries", default)]
+    pub entries: Vec<EntryDto>,
+    #[serde(rename = "goals", default)]
+    pub goals: Vec<GoalDto>,
+    #[serde(rename = "baselines", default)]
+    pub baselines: Vec<BaselineDto>,
+    #[serde(rename = "presets", default)]
+    pub presets: Vec<PresetDto>,
+    /// Every setting by key, defaults included.
+    #[serde(rename = "settings", default)]
+    pub settings: BTreeMap<String, SettingValueDto>,
+}
+
+impl From<Backup> for BackupDto {
+    fn from(backup: Backup) -> Self {
+        BackupDto {
+            format: backup.format,
+            version: backup.version,
+            exported_at: backup.exported_at,
+            schema_version: backup.schema_version,
+            entries: backup.entries.into_iter().map(EntryDto::from).collect(),
+            goals: backup.goals.into_iter().map(GoalDto::from).collect(),
+            baselines: backup.baselines.into_iter().map(BaselineDto::from).collect(),
+            presets: backup.presets.into_iter().map(PresetDto::from).collect(),
+            settings: backup.settings.into_iter().map(|(key, value)| (key, value.into())).collect(),
+        }
+    }
+}
+
+impl From<BackupDto> for Backup {
+    fn from(dto: BackupDto) -> Self {
+        Backup {
+            format: dto.format,
+            version: dto.version,
+            exported_at: dto.exported_at,
+            schema_version: dto.schema_version,
+            entries: dto.entries.into_iter().map(BeerEntry::from).collect(),
+            goals: dto.goals.into_iter().map(ConsumptionGoal::from).collect(),
+            baselines: dto.baselines.into_iter().map(BaselineRecord::from).collect(),
+            presets: dto.presets.into_iter().map(DrinkPreset::from).collect(),
+            settings: dto.settings.into_iter().map(|(key, value)| (key, value.into())).collect(),
+        }
+    }
+}
+
+wire_enum! {
+    /// How a backup is restored.
+    ImportModeDto as "ImportMode" <=> ImportMode {
+        /// Adds new records and overwrites existing ones with the same id.
+        Merge = "merge",
+        /// Deletes everything first, settings included.
+        Replace = "replace",
+        /// Reports what `merge` would do without writing anything.
+        DryRun = "dry_run",
+    }
+}
+
+wire_enum! {
+    /// The kind of record an import outcome is about.
+    RecordKindDto as "RecordKind" <=> RecordKind {
+        Entry = "entry",
+        Goal = "goal",
+        Baseline = "baseline",
+        Preset = "preset",
+        Setting = "setting",
+    }
+}
+
+wire_enum! {
+    /// What a restore did with one record.
+    RecordActionDto as "RecordAction" <=> RecordAction {
+        Created = "created",
+        Updated = "updated",
+        Failed = "failed",
+    }
+}
+
+/// What happened to one record of a backup. Settings are identified by key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "RecordOutcome")]
+pub struct RecordOutcomeDto {
+    #[serde(rename = "kind")]
+    pub kind: RecordKindDto,
+    #[serde(rename = "id")]
+    pub id: String,
+    #[serde(rename = "action")]
+    pub action: RecordActionDto,
+    /// Why the record failed; null otherwise.
+    #[serde(rename = "message", default)]
+    pub message: Option<String>,
+}
+
+impl From<RecordOutcome> for RecordOutcomeDto {
+    fn from(outcome: RecordOutcome) -> Self {
+        RecordOutcomeDto {
+            kind: outcome.kind.into(),
+            id: outcome.id,
+            action: outcome.action.into(),
+            message: outcome.message,
+        }
+    }
+}
+
+/// The outcome of restoring a backup, as returned by `import_backup` and `import_json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "ImportReport")]
+pub struct ImportReportDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "mode")]
+    pub mode: ImportModeDto,
+    #[serde(rename = "created")]
+    pub created: u32,
+    #[serde(rename = "updated")]
+    pub updated: u32,
+    #[serde(rename = "failed")]
+    pub failed: u32,
+    #[serde(rename = "records")]
+    pub records: Vec<RecordOutcomeDto>,
+}
+
+impl ImportReportDto {
+    pub fn new(report: ImportReport) -> Self {
+        ImportReportDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            mode: report.mode.into(),
+            created: report.created,
+            updated: report.updated,
+            failed: report.failed,
+            records: report.records.into_iter().map(RecordOutcomeDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for ImportReportDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// A row of an imported file that couldn't be logged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CsvRowError")]
+pub struct CsvRowErrorDto {
+    /// 1-based line the row starts on, or position in a JSON array.
+    #[serde(rename = "line")]
+    pub line: u64,
+    #[serde(rename = "message")]
+    pub message: String,
+}
+
+impl From<CsvRowError> for CsvRowErrorDto {
+    fn from(error: CsvRowError) -> Self {
+        CsvRowErrorDto { line: error.line, message: error.message }
+    }
+}
+
+/// The outcome of a CSV import, as returned by `import_csv`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "CsvImportReport")]
+pub struct CsvImportReportDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "imported")]
+    pub imported: u32,
+    #[serde(rename = "errors")]
+    pub errors: Vec<CsvRowErrorDto>,
+}
+
+impl CsvImportReportDto {
+    pub fn new(report: CsvImportReport) -> Self {
+        CsvImportReportDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            imported: report.imported,
+            errors: report.errors.into_iter().map(CsvRowErrorDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for CsvImportReportDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+wire_enum! {
+    /// Another tracker's export that `import_from` reads.
+    ImportSourceDto as "ImportSource" <=> ImportSource {
+        /// Untappd's check-in history export as CSV.
+        UntappdCsv = "untappd_csv",
+        /// Untappd's check-in history export as JSON.
+        UntappdJson = "untappd_json",
+        /// Any drink diary CSV with a header row.
+        DrinkDiaryCsv = "drink_diary_csv",
+        /// A filled-in copy of the spreadsheet template, saved as CSV.
+        SpreadsheetTemplate = "spreadsheet_template",
+    }
+}
+
+/// The outcome of importing another tracker's export, as returned by `import_from`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "ImporterReport")]
+pub struct ImporterReportDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    #[serde(rename = "source")]
+    pub source: ImportSourceDto,
+    #[serde(rename = "imported")]
+    pub imported: u32,
+    /// Rows already logged, which were skipped.
+    #[serde(rename = "duplicates")]
+    pub duplicates: u32,
+    #[serde(rename = "errors")]
+    pub errors: Vec<CsvRowErrorDto>,
+}
+
+impl ImporterReportDto {
+    pub fn new(report: ImporterReport) -> Self {
+        ImporterReportDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            source: report.source.into(),
+            imported: report.imported,
+            duplicates: report.duplicates,
+            errors: report.errors.into_iter().map(CsvRowErrorDto::from).collect(),
+        }
+    }
+}
+
+impl FfiDocument for ImporterReportDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// Stable numeric codes for the outcome of an FFI call. Values are never reused
+/// or renumbered; new failures get new codes.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    Ok = 0,
+    /// The handle doesn't refer to an open database: it was never issued or
+    /// has been closed.
+    NotInitialized = 1,
+    /// A required pointer or string argument was null.
+    NullArgument = 2,
+    InvalidInput = 3,
+    NotFound = 4,
+    Database = 5,
+    IncompatibleDatabase = 6,
+    /// A bug in BrewLog itself, e.g. a result that couldn't be serialised.
+    Internal = 7,
+}
+
+impl ErrorCode {
+    pub const ALL: [ErrorCode; 8] = [
+        ErrorCode::Ok,
+        ErrorCode::NotInitialized,
+        ErrorCode::NullArgument,
+        ErrorCode::InvalidInput,
+        ErrorCode::NotFound,
+        ErrorCode::Database,
+        ErrorCode::IncompatibleDatabase,
+        ErrorCode::Internal,
+    ];
+
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_code(code: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.code() == code)
+    }
+}
+
+impl From<&BrewLogError> for ErrorCode {
+    fn from(err: &BrewLogError) -> Self {
+        match err {
+            BrewLogError::DatabaseError(_) => ErrorCode::Database,
+            BrewLogError::InvalidInput(_) => ErrorCode::InvalidInput,
+            BrewLogError::NotFound(_) => ErrorCode::NotFound,
+            BrewLogError::IncompatibleDatabase(_) => ErrorCode::IncompatibleDatabase,
+        }
+    }
+}
+
+/// A failed FFI call, before it is wrapped in a [`ResultDto`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FfiError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl FfiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        FfiError { code, message: message.into() }
+    }
+
+    pub fn unknown_handle(handle: u64) -> Self {
+        FfiError::new(ErrorCode::NotInitialized, format!("No open log for handle {handle}"))
+    }
+
+    pub fn null_argument(name: &str) -> Self {
+        FfiError::new(ErrorCode::NullArgument, format!("{name} is null"))
+    }
+}
+
+impl From<BrewLogError> for FfiError {
+    fn from(err: BrewLogError) -> Self {
+        FfiError::new(ErrorCode::from(&err), err.to_string())
+    }
+}
+
+/// The envelope every C and JNI entry point returns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(rename = "Result")]
+pub struct ResultDto {
+    #[serde(rename = "schema_version")]
+    #[schemars(range(min = 1))]
+    pub schema_version: u32,
+    /// 0 on success, otherwise one of: 1 not initialised, 2 null argument,
+    /// 3 invalid input, 4 not found, 5 database error, 6 incompatible database,
+    /// 7 internal error.
+    #[serde(rename = "code")]
+    #[schemars(range(min = 0))]
+    pub code: i32,
+    /// What went wrong; null on success.
+    #[serde(rename = "message")]
+    pub message: Option<String>,
+    /// The value asked for; null on failure and for calls that return nothing.
+    #[serde(rename = "payload")]
+    pub payload: serde_json::Value,
+}
+
+impl ResultDto {
+    pub fn ok(payload: serde_json::Value) -> Self {
+        ResultDto { schema_version: FFI_SCHEMA_VERSION, code: ErrorCode::Ok.code(), message: None, payload }
+    }
+
+    pub fn error(error: FfiError) -> Self {
+        ResultDto {
+            schema_version: FFI_SCHEMA_VERSION,
+            code: error.code.code(),
+            message: Some(error.message),
+            payload: serde_json::Value::Null,
+        }
+    }
+
+    pub fn from_outcome(outcome: Result<serde_json::Value, FfiError>) -> Self {
+        outcome.map_or_else(ResultDto::error, ResultDto::ok)
+    }
+
+    /// The code as an [`ErrorCode`]; `None` for codes added after this version.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        ErrorCode::from_code(self.code)
+    }
+
+    /// Serialises the envelope. Falls back to a hand-written internal error,
+    /// so callers always get a parseable document.
+    pub fn into_json(self) -> String {
+        self.to_json().unwrap_or_else(|e| {
+            let message = serde_json::Value::String(e.to_string());
+            format!(
+                "{{\"schema_version\":{FFI_SCHEMA_VERSION},\"code\":{},\"message\":{message},\"payload\":null}}",
+                ErrorCode::Internal.code()
+            )
+        })
+    }
+}
+
+impl FfiDocument for ResultDto {
+    fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+}
+
+/// JSON Schema (draft 7) for every document in [`FfiDocument`] form, with the
+/// documents listed under `oneOf`, the arguments and payload of each
+/// `brewlog_call` method under `methods`, and the records and shared types
+/// under `definitions`.
+pub fn ffi_json_schema() -> serde_json::Value {
+    let mut gen = SchemaGenerator::default();
+    let documents = vec![
+        gen.subschema_for::<ResultDto>(),
+        gen.subschema_for::<EntryListDto>(),
+        gen.subschema_for::<GoalListDto>(),
+        gen.subschema_for::<BaselineListDto>(),
+        gen.subschema_for::<ProgressStatsDto>(),
+        gen.subschema_for::<WeekTotalsDto>(),
+        gen.subschema_for::<TotalsDto>(),
+        gen.subschema_for::<CategoryBreakdownDto>(),
+        gen.subschema_for::<GoalEvaluationDto>(),
+        gen.subschema_for::<SettingListDto>(),
+        gen.subschema_for::<PresetListDto>(),
+        gen.subschema_for::<ImportReportDto>(),
+        gen.subschema_for::<CsvImportReportDto>(),
+        gen.subschema_for::<ImporterReportDto>(),
+    ];
+    // Records sent on their own as payloads, outside any list document.
+    gen.subschema_for::<BaselineCalculationDto>();
+    let methods = crate::bindings::method_schemas(&mut gen);
+    serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "BrewLog FFI documents",
+        "description": format!("JSON exchanged with the BrewLog core over C and JNI, schema_version {FFI_SCHEMA_VERSION}."),
+        "oneOf": documents,
+        "methods": methods,
+        "definitions": gen.definitions(),
+    })
+}
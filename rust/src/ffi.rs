@@ -0,0 +1,257 @@
+//! C ABI, declared in `include/brewlog.h`.
+//!
+//! Every function except [`brewlog_string_free`] returns a [`ResultDto`] JSON
+//! envelope: `code` is an [`ErrorCode`], `message` says what went wrong and
+//! `payload` holds the value asked for.
+//!
+//! [`brewlog_open`] returns a [`BrewLogHandle`] as its payload, and the other
+//! calls take one to say which database they act on. Any number of databases
+//! can be open at once. After [`brewlog_close`] the handle is refused with
+//! [`ErrorCode::NotInitialized`]; a call already running on it finishes first.
+//!
+//! [`brewlog_call`] reaches every method listed in [`crate::bindings`]; the
+//! other functions are typed shortcuts for the most common ones.
+//!
+//! String arguments must be NUL-terminated; invalid UTF-8 is replaced rather
+//! than rejected. A null argument is reported as [`ErrorCode::NullArgument`]
+//! instead of being dereferenced, and a panic becomes [`ErrorCode::Internal`]
+//! rather than unwinding into C. Every `char *` returned is owned by the
+//! caller and must be released with [`brewlog_string_free`], never with
+//! `free()`: it was allocated by Rust, not by the C runtime.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+use serde::Serialize;
+
+use crate::bindings::{self, Args};
+use crate::{BrewLog, BrewLogError, EntryListDto, ErrorCode, FfiError, ResultDto};
+
+/// Identifies a database opened by [`brewlog_open`]. Handles are never 0 and
+/// never reused within a process.
+pub type BrewLogHandle = u64;
+
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+/// The open logs, shared with the JNI entry points.
+fn handles() -> MutexGuard<'static, HashMap<BrewLogHandle, Arc<BrewLog>>> {
+    static HANDLES: OnceLock<Mutex<HashMap<BrewLogHandle, Arc<BrewLog>>>> = OnceLock::new();
+    HANDLES.get_or_init(Default::default).lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Hands a string to the caller. C strings can't hold NUL, so any in `s` are dropped.
+fn into_c_string(s: String) -> *mut c_char {
+    let c = CString::new(s).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|&b| b != 0);
+        CString::new(bytes).expect("NUL bytes were removed")
+    });
+    c.into_raw()
+}
+
+/// Reads a string argument, or names the one that was null.
+///
+/// # Safety
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn read_arg(ptr: *const c_char, name: &str) -> Result<String, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::null_argument(name));
+    }
+    Ok(unsafe { CStr::from_ptr(ptr) }.to_string_lossy().into_owned())
+}
+
+/// The log behind `handle`. It stays open until the caller is done with it,
+/// even if the handle is closed meanwhile.
+pub(crate) fn log(handle: BrewLogHandle) -> Result<Arc<BrewLog>, FfiError> {
+    handles().get(&handle).cloned().ok_or_else(|| FfiError::unknown_handle(handle))
+}
+
+/// Turns a core result into an envelope payload; `()` becomes `null`.
+pub(crate) fn payload<T: Serialize>(result: Result<T, BrewLogError>) -> Result<serde_json::Value, FfiError> {
+    let value = result?;
+    serde_json::to_value(value).map_err(|e| FfiError::new(ErrorCode::Internal, e.to_string()))
+}
+
+/// Registers a newly opened log; the payload is its handle.
+pub(crate) fn open(log: Result<BrewLog, BrewLogError>) -> Result<serde_json::Value, FfiError> {
+    payload(log.map(|log| {
+        let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+        handles().insert(handle, Arc::new(log));
+        handle
+    }))
+}
+
+pub(crate) fn close(handle: BrewLogHandle) -> Result<serde_json::Value, FfiError> {
+    // Dropped outside the registry lock, since closing the connection can take a while
+    let log = handles().remove(&handle).ok_or_else(|| FfiError::unknown_handle(handle))?;
+    drop(log);
+    Ok(serde_json::Value::Null)
+}
+
+/// Runs `body`, turning a panic into an [`ErrorCode::Internal`] error.
+pub(crate) fn catch_panic<T>(body: impl FnOnce() -> Result<T, FfiError>) -> Result<T, FfiError> {
+    panic::catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|payload| Err(FfiError::new(ErrorCode::Internal, panic_message(payload.as_ref()))))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown cause");
+    format!("Panicked: {message}")
+}
+
+fn respond(body: impl FnOnce() -> Result<serde_json::Value, FfiError>) -> *mut c_char {
+    into_c_string(ResultDto::from_outcome(catch_panic(body)).into_json())
+}
+
+/// Releases a string returned by any function in this library. Null is ignored.
+///
+/// # Safety
+/// `s` must be null or a pointer returned by this library that hasn't already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn brewlog_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Opens (creating if needed) the database at `path`. Payload: its handle.
+///
+/// # Safety
+/// `path` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn brewlog_open(path: *const c_char) -> *mut c_char {
+    respond(|| {
+        let path = unsafe { read_arg(path, "path")? };
+        open(BrewLog::new_with_path(&path))
+    })
+}
+
+/// Opens a new, empty in-memory database. Payload: its handle.
+#[no_mangle]
+pub extern "C" fn brewlog_open_in_memory() -> *mut c_char {
+    respond(|| open(BrewLog::new()))
+}
+
+/// Closes the database behind `handle`, which can't be used afterwards.
+#[no_mangle]
+pub extern "C" fn brewlog_close(handle: BrewLogHandle) -> *mut c_char {
+    respond(|| close(handle))
+}
+
+/// # Safety
+/// `name` and `notes` must be null or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn add_beer_entry(
+    handle: BrewLogHandle,
+    name: *const c_char,
+    alcohol_percentage: f64,
+    volume_ml: f64,
+    notes: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let (name, notes) = unsafe { (read_arg(name, "name")?, read_arg(notes, "notes")?) };
+        payload(log(handle)?.add_beer_entry(name, alcohol_percentage, volume_ml, notes))
+    })
+}
+
+/// Payload: millilitres logged on `date`.
+///
+/// # Safety
+/// `date` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn get_daily_consumption(handle: BrewLogHandle, date: *const c_char) -> *mut c_char {
+    respond(|| {
+        let date = unsafe { read_arg(date, "date")? };
+        payload(log(handle)?.get_daily_consumption(date))
+    })
+}
+
+/// Payload: millilitres logged in the week starting `week_start_date`.
+///
+/// # Safety
+/// `week_start_date` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn get_weekly_consumption(handle: BrewLogHandle, week_start_date: *const c_char) -> *mut c_char {
+    respond(|| {
+        let date = unsafe { read_arg(week_start_date, "week_start_date")? };
+        payload(log(handle)?.get_weekly_consumption(date))
+    })
+}
+
+/// # Safety
+/// `start_date` and `end_date` must be null or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn set_consumption_goal(
+    handle: BrewLogHandle,
+    daily_target: f64,
+    weekly_target: f64,
+    start_date: *const c_char,
+    end_date: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let (start, end) = unsafe { (read_arg(start_date, "start_date")?, read_arg(end_date, "end_date")?) };
+        payload(log(handle)?.set_consumption_goal(daily_target, weekly_target, start, end))
+    })
+}
+
+/// Payload: the entries between the two dates as an [`EntryListDto`] document.
+///
+/// # Safety
+/// `start_date` and `end_date` must be null or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn get_beer_entries_json(handle: BrewLogHandle, start_date: *const c_char, end_date: *const c_char) -> *mut c_char {
+    respond(|| {
+        let (start, end) = unsafe { (read_arg(start_date, "start_date")?, read_arg(end_date, "end_date")?) };
+        payload(log(handle)?.get_beer_entries(start, end).map(EntryListDto::new))
+    })
+}
+
+/// # Safety
+/// `id` must be null or a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn delete_beer_entry_jni(handle: BrewLogHandle, id: *const c_char) -> *mut c_char {
+    respond(|| {
+        let id = unsafe { read_arg(id, "id")? };
+        payload(log(handle)?.delete_beer_entry(id))
+    })
+}
+
+/// # Safety
+/// `id`, `name` and `notes` must be null or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn update_beer_entry_jni(
+    handle: BrewLogHandle,
+    id: *const c_char,
+    name: *const c_char,
+    alcohol_percentage: f64,
+    volume_ml: f64,
+    notes: *const c_char,
+) -> *mut c_char {
+    respond(|| {
+        let (id, name, notes) = unsafe { (read_arg(id, "id")?, read_arg(name, "name")?, read_arg(notes, "notes")?) };
+        payload(log(handle)?.update_beer_entry(id, name, alcohol_percentage, volume_ml, notes))
+    })
+}
+
+/// Calls the `BrewLog` method named `method` with `args`, a JSON object of its
+/// parameters by name. Payload: the method's result, as described under
+/// `methods` in the published schema.
+///
+/// # Safety
+/// `method` and `args` must be null or valid C strings.
+#[no_mangle]
+pub unsafe extern "C" fn brewlog_call(handle: BrewLogHandle, method: *const c_char, args: *const c_char) -> *mut c_char {
+    respond(|| {
+        let (method, args) = unsafe { (read_arg(method, "method")?, read_arg(args, "args")?) };
+        bindings::call(&*log(handle)?, &method, Args::parse(&args)?)
+    })
+}
@@ -0,0 +1,300 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{BeerEntry, BrewLogError, Consumption, ConsumptionGoal, GoalUnit, StandardDrink};
+
+/// Timed drinks more than this many hours apart belong to different sessions.
+const SESSION_GAP_HOURS: i64 = 3;
+
+/// What a goal limits. Amounts are in the goal's `unit`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GoalKind {
+    /// A limit per day and a limit per week.
+    DailyWeekly { daily_target: f64, weekly_target: f64 },
+    /// At least this many days without alcohol in each week.
+    AlcoholFreeDays { days_per_week: u32 },
+    /// A separate daily limit for each weekday, Monday first.
+    WeekdayLimits { limits: [f64; 7] },
+    /// A limit for a single drinking session. Sessions break wherever consecutive
+    /// drinks are more than three hours apart; drinks without a time make up one
+    /// session for their day.
+    SessionCap { max_per_session: f64 },
+    /// A limit per calendar month.
+    MonthlyCap { max_per_month: f64 },
+}
+
+impl GoalKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GoalKind::DailyWeekly { .. } => "daily_weekly",
+            GoalKind::AlcoholFreeDays { .. } => "alcohol_free_days",
+            GoalKind::WeekdayLimits { .. } => "weekday_limits",
+            GoalKind::SessionCap { .. } => "session_cap",
+            GoalKind::MonthlyCap { .. } => "monthly_cap",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), BrewLogError> {
+        let non_negative = |value: f64, what: &str| {
+            if value < 0.0 || value.is_nan() {
+                Err(BrewLogError::InvalidInput(format!("{what} must be non-negative")))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            GoalKind::DailyWeekly { daily_target, weekly_target } => {
+                non_negative(*daily_target, "Daily target")?;
+                non_negative(*weekly_target, "Weekly target")
+            }
+            GoalKind::AlcoholFreeDays { days_per_week } if *days_per_week > 7 => Err(BrewLogError::InvalidInput(
+                "Alcohol-free days per week must be at most 7".to_string(),
+            )),
+            GoalKind::AlcoholFreeDays { .. } => Ok(()),
+            GoalKind::WeekdayLimits { limits } => limits.iter().try_for_each(|l| non_negative(*l, "Weekday limit")),
+            GoalKind::SessionCap { max_per_session } => non_negative(*max_per_session, "Session cap"),
+            GoalKind::MonthlyCap { max_per_month } => non_negative(*max_per_month, "Monthly cap"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Within,
+    Over,
+    NoGoal,
+}
+
+/// One goal checked against one period. `margin` is the room left: `target - actual`
+/// for limits, and `actual - target` for alcohol-free days, so positive is always good.
+/// `unit` is `None` when the target counts days rather than an amount.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct GoalCheck {
+    pub goal_id: String,
+    pub kind: String,
+    pub unit: Option<GoalUnit>,
+    pub target: f64,
+    pub actual: f64,
+    pub status: GoalStatus,
+    pub margin: f64,
+}
+
+/// A day, week or month and the goals it is held to: a day's own goals, or
+/// those in effect on the last day of a week or month. Weeks and months at
+/// either end of the evaluated range are cut to the range. `status` is `Over` if
+/// any check failed, `Within` if all passed, and `NoGoal` if nothing applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct PeriodEvaluation {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: u32,
+    pub total: Consumption,
+    pub checks: Vec<GoalCheck>,
+    pub status: GoalStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct GoalEvaluation {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: Vec<PeriodEvaluation>,
+    pub weeks: Vec<PeriodEvaluation>,
+    pub months: Vec<PeriodEvaluation>,
+    pub days_with_goal: u32,
+    pub days_within_goal: u32,
+    pub days_over_goal: u32,
+    pub weeks_with_goal: u32,
+    pub weeks_within_goal: u32,
+    pub weeks_over_goal: u32,
+    pub months_with_goal: u32,
+    pub months_within_goal: u32,
+    pub months_over_goal: u32,
+}
+
+/// Goals in effect on `date`, given every goal ordered by `effective_from` then creation.
+pub(crate) fn goals_on<'a>(goals: &'a [ConsumptionGoal], date: &str) -> Vec<&'a ConsumptionGoal> {
+    goals
+        .iter()
+        .filter(|g| g.effective_from.as_str() <= date && g.effective_to.as_deref().is_none_or(|to| to >= date))
+        .collect()
+}
+
+fn limit_check(goal: &ConsumptionGoal, target: f64, total: &Consumption) -> GoalCheck {
+    let actual = total.in_unit(goal.unit);
+    GoalCheck {
+        goal_id: goal.id.clone(),
+        kind: goal.kind.name().to_string(),
+        unit: Some(goal.unit),
+        target,
+        actual,
+        status: if actual <= target { GoalStatus::Within } else { GoalStatus::Over },
+        margin: target - actual,
+    }
+}
+
+/// The drinking sessions in `entries`, filed under the day of each session's first drink.
+pub(crate) fn sessions(entries: &[BeerEntry], definition: &StandardDrink) -> HashMap<NaiveDate, Vec<Consumption>> {
+    let mut by_day: HashMap<NaiveDate, Vec<Consumption>> = HashMap::new();
+    let mut untimed: HashMap<NaiveDate, Consumption> = HashMap::new();
+    let mut timed = Vec::new();
+    for entry in entries {
+        let Ok(date) = NaiveDate::parse_from_str(&entry.date, "%Y-%m-%d") else { continue };
+        let consumption = Consumption::from_entry(entry, definition);
+        match entry.consumed_at.as_deref().and_then(|t| DateTime::parse_from_rfc3339(t).ok()) {
+            Some(at) => timed.push((at, date, consumption)),
+            None => *untimed.entry(date).or_default() += consumption,
+        }
+    }
+    timed.sort_by_key(|(at, _, _)| *at);
+
+    let mut runs: Vec<(NaiveDate, Consumption)> = Vec::new();
+    let mut previous = None;
+    for (at, date, consumption) in timed {
+        match (previous, runs.last_mut()) {
+            (Some(last), Some((_, session))) if at - last <= Duration::hours(SESSION_GAP_HOURS) => *session += consumption,
+            _ => runs.push((date, consumption)),
+        }
+        previous = Some(at);
+    }
+    for (date, session) in runs.into_iter().chain(untimed) {
+        by_day.entry(date).or_default().push(session);
+    }
+    by_day
+}
+
+fn day_check(goal: &ConsumptionGoal, date: NaiveDate, total: &Consumption, sessions: &[Consumption]) -> Option<GoalCheck> {
+    match &goal.kind {
+        GoalKind::DailyWeekly { daily_target, .. } => Some(limit_check(goal, *daily_target, total)),
+        GoalKind::WeekdayLimits { limits } => {
+            Some(limit_check(goal, limits[date.weekday().num_days_from_monday() as usize], total))
+        }
+        // The day's largest session decides whether the cap held
+        GoalKind::SessionCap { max_per_session } => {
+            let largest = sessions
+                .iter()
+                .copied()
+                .max_by(|a, b| a.in_unit(goal.unit).total_cmp(&b.in_unit(goal.unit)))
+                .unwrap_or_default();
+            Some(limit_check(goal, *max_per_session, &largest))
+        }
+        _ => None,
+    }
+}
+
+fn week_check(goal: &ConsumptionGoal, days: &[(NaiveDate, Consumption)], total: &Consumption) -> Option<GoalCheck> {
+    match &goal.kind {
+        GoalKind::DailyWeekly { weekly_target, .. } => Some(limit_check(goal, *weekly_target, total)),
+        GoalKind::AlcoholFreeDays { days_per_week } => {
+            let target = *days_per_week as f64;
+            let dry = days.iter().filter(|(_, c)| c.ethanol_grams <= 0.0).count() as f64;
+            // A week cut short by the range can still reach the target in its remaining days
+            let reachable = dry + (7 - days.len()) as f64;
+            Some(GoalCheck {
+                goal_id: goal.id.clone(),
+                kind: goal.kind.name().to_string(),
+                unit: None,
+                target,
+                actual: dry,
+                status: if reachable >= target { GoalStatus::Within } else { GoalStatus::Over },
+                margin: dry - target,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn month_check(goal: &ConsumptionGoal, total: &Consumption) -> Option<GoalCheck> {
+    match &goal.kind {
+        GoalKind::MonthlyCap { max_per_month } => Some(limit_check(goal, *max_per_month, total)),
+        _ => None,
+    }
+}
+
+fn period(days: &[(NaiveDate, Consumption)], checks: Vec<GoalCheck>) -> PeriodEvaluation {
+    let status = if checks.is_empty() {
+        GoalStatus::NoGoal
+    } else if checks.iter().any(|c| c.status == GoalStatus::Over) {
+        GoalStatus::Over
+    } else {
+        GoalStatus::Within
+    };
+    PeriodEvaluation {
+        start_date: days[0].0.to_string(),
+        end_date: days[days.len() - 1].0.to_string(),
+        days: days.len() as u32,
+        total: days.iter().map(|(_, c)| *c).sum(),
+        checks,
+        status,
+    }
+}
+
+pub(crate) fn evaluate(
+    daily: &[(NaiveDate, Consumption)],
+    sessions: &HashMap<NaiveDate, Vec<Consumption>>,
+    goals: &[ConsumptionGoal],
+    week_start: Weekday,
+) -> GoalEvaluation {
+    let days: Vec<PeriodEvaluation> = daily
+        .iter()
+        .map(|(date, total)| {
+            let day_sessions = sessions.get(date).map_or(&[][..], Vec::as_slice);
+            let checks = goals_on(goals, &date.to_string())
+                .into_iter()
+                .filter_map(|g| day_check(g, *date, total, day_sessions))
+                .collect();
+            period(std::slice::from_ref(&(*date, *total)), checks)
+        })
+        .collect();
+
+    // Weeks and months are held to the goals in effect on their last day, so a
+    // goal set partway through already counts for that week or month
+    let weeks: Vec<PeriodEvaluation> = daily
+        .chunk_by(|_, next| next.0.weekday() != week_start)
+        .map(|week| {
+            let total: Consumption = week.iter().map(|(_, c)| *c).sum();
+            let checks = goals_on(goals, &week[week.len() - 1].0.to_string())
+                .into_iter()
+                .filter_map(|g| week_check(g, week, &total))
+                .collect();
+            period(week, checks)
+        })
+        .collect();
+
+    let months: Vec<PeriodEvaluation> = daily
+        .chunk_by(|a, b| (a.0.year(), a.0.month()) == (b.0.year(), b.0.month()))
+        .map(|month| {
+            let total: Consumption = month.iter().map(|(_, c)| *c).sum();
+            let checks = goals_on(goals, &month[month.len() - 1].0.to_string())
+                .into_iter()
+                .filter_map(|g| month_check(g, &total))
+                .collect();
+            period(month, checks)
+        })
+        .collect();
+
+    let count = |periods: &[PeriodEvaluation], status: GoalStatus| periods.iter().filter(|p| p.status == status).count() as u32;
+
+    GoalEvaluation {
+        start_date: daily.first().map(|(d, _)| d.to_string()).unwrap_or_default(),
+        end_date: daily.last().map(|(d, _)| d.to_string()).unwrap_or_default(),
+        days_with_goal: days.len() as u32 - count(&days, GoalStatus::NoGoal),
+        days_within_goal: count(&days, GoalStatus::Within),
+        days_over_goal: count(&days, GoalStatus::Over),
+        weeks_with_goal: weeks.len() as u32 - count(&weeks, GoalStatus::NoGoal),
+        weeks_within_goal: count(&weeks, GoalStatus::Within),
+        weeks_over_goal: count(&weeks, GoalStatus::Over),
+        months_with_goal: months.len() as u32 - count(&months, GoalStatus::NoGoal),
+        months_within_goal: count(&months, GoalStatus::Within),
+        months_over_goal: count(&months, GoalStatus::Over),
+        days,
+        weeks,
+        months,
+    }
+}
@@ -0,0 +1,399 @@
+//! Importers for other trackers' exports.
+//!
+//! Every importer turns its rows into [`BeerEntry`] records and logs them through
+//! [`BrewLog::save_beer_entry`]. Rows matching an entry that was already in the
+//! database are counted as duplicates and skipped, so importing the same export
+//! twice is harmless.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::csv_io::{csv_error, RowDefaults};
+use crate::{BeerEntry, BrewLog, BrewLogError, ColumnRef, CsvMapping, CsvRowError, DrinkCategory, VolumeUnit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    /// Untappd's check-in history export as CSV.
+    UntappdCsv,
+    /// Untappd's check-in history export as JSON.
+    UntappdJson,
+    /// Any drink diary CSV with a header row. Columns, delimiter and date format are
+    /// recognised from the header and the data.
+    DrinkDiaryCsv,
+    /// A copy of [`SPREADSHEET_TEMPLATE`] filled in by hand and saved as CSV.
+    SpreadsheetTemplate,
+}
+
+/// Header of the spreadsheet template users can keep a drinking log in. Dates are
+/// `YYYY-MM-DD`, times `HH:MM`, and either `,` or `;` may separate the columns.
+pub const SPREADSHEET_TEMPLATE: &str = "Date,Time,Drink,Category,Style,ABV %,Volume ml,Notes\n";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct ImporterReport {
+    pub source: ImportSource,
+    pub imported: u32,
+    pub duplicates: u32,
+    /// For JSON sources `line` is the 1-based position of the record in the array.
+    pub errors: Vec<CsvRowError>,
+}
+
+/// Untappd doesn't record volume. Tasters get a taster's measure and everything
+/// else the `default_beer_size` setting.
+const UNTAPPD_TASTER_ML: f64 = 150.0;
+
+/// Date formats tried, in order, when a diary doesn't say which it uses. Day-first
+/// comes before month-first, so `03/04/2024` is read as 3 April.
+const DIARY_DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d",
+    "%d/%m/%Y %H:%M",
+    "%d/%m/%Y",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y",
+    "%d.%m.%Y %H:%M",
+    "%d.%m.%Y",
+];
+
+impl BrewLog {
+    pub fn import_from(&self, source: ImportSource, data: String) -> Result<ImporterReport, BrewLogError> {
+        let defaults = RowDefaults::load(self)?;
+        let rows = match source {
+            ImportSource::UntappdCsv => untappd_csv(&data, &defaults)?,
+            ImportSource::UntappdJson => untappd_json(&data, &defaults)?,
+            ImportSource::DrinkDiaryCsv => mapped_csv(&data, &diary_mapping(&data)?, &defaults)?,
+            ImportSource::SpreadsheetTemplate => mapped_csv(&data, &template_mapping(&data), &defaults)?,
+        };
+
+        let mut report = ImporterReport {
+            source,
+            imported: 0,
+            duplicates: 0,
+            errors: Vec::new(),
+        };
+        let mut existing = self.existing_keys(&rows)?;
+        for (line, row) in rows {
+            match row.and_then(|entry| self.import_entry(entry, &mut existing)) {
+                Ok(true) => report.imported += 1,
+                Ok(false) => report.duplicates += 1,
+                Err(e) => report.errors.push(CsvRowError {
+                    line,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    }
+
+    /// Which of the rows' ids are already logged, on any day, and the duplicate keys
+    /// of the entries logged on the days the import covers.
+    fn existing_keys(&self, rows: &[(u64, Result<BeerEntry, BrewLogError>)]) -> Result<Existing, BrewLogError> {
+        let entries = || rows.iter().filter_map(|(_, r)| r.as_ref().ok());
+        let mut existing = Existing::default();
+        {
+            let conn = self.conn();
+            let mut stmt = conn.prepare("SELECT 1 FROM beer_entries WHERE id = ?1")?;
+            for entry in entries().filter(|e| !e.id.is_empty()) {
+                if stmt.exists([&entry.id])? {
+                    existing.ids.insert(entry.id.clone());
+                }
+            }
+        }
+
+        let dates = entries().map(|e| e.date.as_str());
+        let (Some(first), Some(last)) = (dates.clone().min(), dates.max()) else {
+            return Ok(existing);
+        };
+        for entry in self.get_beer_entries(first.to_string(), last.to_string())? {
+            existing.drinks.entry(DuplicateKey::of(&entry)).or_default().push(minute(&entry));
+        }
+        Ok(existing)
+    }
+
+    /// Logs `entry` unless it duplicates one already logged. Returns whether it was logged.
+    fn import_entry(&self, mut entry: BeerEntry, existing: &mut Existing) -> Result<bool, BrewLogError> {
+        if existing.ids.contains(&entry.id) {
+            return Ok(false);
+        }
+        if existing.take_duplicate(&entry) {
+            return Ok(false);
+        }
+
+        if entry.id.is_empty() {
+            entry.id = Uuid::new_v4().to_string();
+        }
+        // One statement, so an entry is never left without its category
+        let id = self.save_beer_entry(entry)?;
+        existing.ids.insert(id);
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+struct Existing {
+    ids: HashSet<String>,
+    /// The minute of each logged drink with the key, `None` for untimed ones.
+    drinks: HashMap<DuplicateKey, Vec<Option<String>>>,
+}
+
+impl Existing {
+    /// Uses up the logged drink `entry` duplicates, if there is one left. Each logged
+    /// drink accounts for one imported row, so a file with two identical pints imports
+    /// both. A drink at the same minute is taken first, then one missing a time.
+    fn take_duplicate(&mut self, entry: &BeerEntry) -> bool {
+        let Some(minutes) = self.drinks.get_mut(&DuplicateKey::of(entry)) else {
+            return false;
+        };
+        let minute = minute(entry);
+        let found = minutes
+            .iter()
+            .position(|m| *m == minute)
+            .or_else(|| minutes.iter().position(|m| m.is_none() || minute.is_none()));
+        found.map(|i| minutes.swap_remove(i)).is_some()
+    }
+}
+
+/// What makes two entries the same drink: same day, name, strength and size, and
+/// the same minute if both were timestamped. Minutes are compared in
+/// [`Existing::take_duplicate`], since an untimed drink matches any minute.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct DuplicateKey {
+    date: String,
+    name: String,
+    abv_hundredths: i64,
+    volume_ml: i64,
+}
+
+impl DuplicateKey {
+    fn of(entry: &BeerEntry) -> Self {
+        DuplicateKey {
+            date: entry.date.clone(),
+            name: entry.name.trim().to_lowercase(),
+            abv_hundredths: (entry.alcohol_percentage * 100.0).round() as i64,
+            volume_ml: entry.volume_ml.round() as i64,
+        }
+    }
+}
+
+fn minute(entry: &BeerEntry) -> Option<String> {
+    entry
+        .consumed_at
+        .as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.naive_utc().format("%Y-%m-%dT%H:%M").to_string())
+}
+
+type Rows = Vec<(u64, Result<BeerEntry, BrewLogError>)>;
+
+fn mapped_csv(data: &str, mapping: &CsvMapping, defaults: &RowDefaults) -> Result<Rows, BrewLogError> {
+    let mut reader = mapping.reader(data)?;
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    let resolved = mapping.resolve(Some(&headers))?;
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(row) => (row.position().map_or(0, |p| p.line()), resolved.entry(&row, defaults)),
+            Err(e) => (e.position().map_or(0, |p| p.line()), Err(csv_error(e))),
+        })
+        .collect())
+}
+
+/// The most frequent of `,`, `;` and tab in the header line.
+fn sniff_delimiter(data: &str) -> char {
+    let header = data.lines().next().unwrap_or_default();
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| header.matches(*d).count())
+        .unwrap_or(',')
+}
+
+fn template_mapping(data: &str) -> CsvMapping {
+    let delimiter = sniff_delimiter(data);
+    CsvMapping {
+        delimiter,
+        decimal_comma: delimiter == ';',
+        date: "Date".into(),
+        date_format: "%Y-%m-%d".to_string(),
+        time: Some("Time".into()),
+        consumed_at: None,
+        name: "Drink".into(),
+        abv: Some("ABV %".into()),
+        volume: Some("Volume ml".into()),
+        volume_unit: VolumeUnit::Ml,
+        notes: Some("Notes".into()),
+        category: Some("Category".into()),
+        subtype: None,
+        style: Some("Style".into()),
+        id: None,
+        ..CsvMapping::default()
+    }
+}
+
+/// Works out a mapping for a diary from its header names and the shape of its dates.
+fn diary_mapping(data: &str) -> Result<CsvMapping, BrewLogError> {
+    let delimiter = sniff_delimiter(data);
+    let probe = CsvMapping {
+        delimiter,
+        ..CsvMapping::default()
+    };
+    let mut reader = probe.reader(data)?;
+    let headers: Vec<String> = reader.headers().map_err(csv_error)?.iter().map(|h| h.trim().to_lowercase()).collect();
+
+    // Headers match an alias exactly or with a unit after it, e.g. `Volume (cl)`
+    let find = |aliases: &[&str]| {
+        headers
+            .iter()
+            .position(|h| aliases.iter().any(|a| h == a || h.starts_with(&format!("{a} ("))))
+    };
+    let column = |index: usize| ColumnRef::Position(index + 1);
+    let date_index = find(&["date", "day", "when", "datetime", "timestamp"])
+        .ok_or_else(|| BrewLogError::InvalidInput("No date column found".to_string()))?;
+    let name_index = find(&["drink", "name", "beverage", "beer", "item"])
+        .ok_or_else(|| BrewLogError::InvalidInput("No drink name column found".to_string()))?;
+    let time_index = find(&["time"]);
+    let volume_index = find(&["volume", "volume ml", "size", "amount", "quantity", "ml"]);
+
+    // The first format that reads every date in the file wins
+    let samples: Vec<String> = reader
+        .records()
+        .filter_map(Result::ok)
+        .filter_map(|r| r.get(date_index).map(|d| d.trim().to_string()))
+        .filter(|d| !d.is_empty())
+        .collect();
+    // With a separate time column the date column holds dates only
+    let date_format = DIARY_DATE_FORMATS
+        .iter()
+        .filter(|format| time_index.is_none() || !format.contains("%H"))
+        .find(|format| samples.iter().all(|d| parses_as(d, format)))
+        .ok_or_else(|| BrewLogError::InvalidInput("Could not recognise the date format".to_string()))?;
+
+    Ok(CsvMapping {
+        delimiter,
+        has_header: true,
+        decimal_comma: delimiter == ';',
+        date: column(date_index),
+        date_format: date_format.to_string(),
+        time: time_index.map(column),
+        consumed_at: None,
+        name: column(name_index),
+        abv: find(&["abv", "abv %", "abv%", "alcohol", "alcohol %", "strength"]).map(column),
+        volume: volume_index.map(column),
+        volume_unit: volume_index.map_or(VolumeUnit::Ml, |i| unit_from_header(&headers[i])),
+        notes: find(&["notes", "note", "comment", "comments", "description"]).map(column),
+        category: find(&["category", "type", "kind"]).map(column),
+        subtype: None,
+        style: find(&["style"]).map(column),
+        id: None,
+    })
+}
+
+fn parses_as(value: &str, format: &str) -> bool {
+    NaiveDateTime::parse_from_str(value, format).is_ok() || chrono::NaiveDate::parse_from_str(value, format).is_ok()
+}
+
+/// Reads a unit from a header like `Volume (oz)` or `Size (cl)`.
+fn unit_from_header(header: &str) -> VolumeUnit {
+    let unit = header
+        .split_once('(')
+        .map(|(_, rest)| rest.trim_end_matches(')').trim())
+        .unwrap_or_default();
+    match unit {
+        "cl" => VolumeUnit::Cl,
+        "l" => VolumeUnit::L,
+        "oz" | "fl oz" | "us fl oz" => VolumeUnit::UsFlOz,
+        "uk fl oz" => VolumeUnit::UkFlOz,
+        "pint" | "pints" | "uk pint" => VolumeUnit::UkPint,
+        "us pint" => VolumeUnit::UsPint,
+        _ => VolumeUnit::Ml,
+    }
+}
+
+/// Builds an entry from one Untappd check-in, whichever format it came from.
+/// `created_at` is in UTC.
+fn untappd_entry(field: impl Fn(&str) -> Option<String>, defaults: &RowDefaults) -> Result<BeerEntry, BrewLogError> {
+    let beer = field("beer_name").ok_or_else(|| BrewLogError::InvalidInput("beer_name is empty".to_string()))?;
+    let created_at = field("created_at").ok_or_else(|| BrewLogError::InvalidInput("created_at is empty".to_string()))?;
+    let consumed_at = NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S")
+        .map_err(|_| BrewLogError::InvalidInput(format!("created_at is not a date and time: {created_at}")))?
+        .and_utc();
+    let date = defaults.boundary.drinking_day(&consumed_at)?.to_string();
+
+    let abv = match field("beer_abv") {
+        Some(abv) => abv
+            .parse::<f64>()
+            .map_err(|_| BrewLogError::InvalidInput(format!("beer_abv is not a number: {abv}")))?,
+        None => defaults.abv,
+    };
+    let volume_ml = match field("serving_type").as_deref().map(str::to_lowercase).as_deref() {
+        Some("taster") => UNTAPPD_TASTER_ML,
+        _ => defaults.volume_ml,
+    };
+    // Untappd styles look like "Cider - Dry" or "Non-Alcoholic Beer - Pale Ale"
+    let style = field("beer_type");
+    let category = match style.as_deref().map(str::to_lowercase) {
+        Some(s) if s.starts_with("cider") => DrinkCategory::Cider,
+        Some(s) if s.starts_with("non-alcoholic") => DrinkCategory::NonAlcoholic,
+        _ => DrinkCategory::Beer,
+    };
+    let name = match field("brewery_name") {
+        Some(brewery) => format!("{beer} ({brewery})"),
+        None => beer,
+    };
+
+    Ok(BeerEntry {
+        id: field("checkin_id").map(|id| format!("untappd-{id}")).unwrap_or_default(),
+        name,
+        alcohol_percentage: abv,
+        volume_ml,
+        date,
+        notes: field("comment").unwrap_or_default(),
+        consumed_at: Some(consumed_at.to_rfc3339()),
+        category,
+        subtype: None,
+        style,
+        created_at: String::new(),
+    })
+}
+
+fn untappd_csv(data: &str, defaults: &RowDefaults) -> Result<Rows, BrewLogError> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(data.as_bytes());
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(row) => {
+                let field = |name: &str| {
+                    let i = headers.iter().position(|h| h == name)?;
+                    row.get(i).map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+                };
+                (row.position().map_or(0, |p| p.line()), untappd_entry(field, defaults))
+            }
+            Err(e) => (e.position().map_or(0, |p| p.line()), Err(csv_error(e))),
+        })
+        .collect())
+}
+
+fn untappd_json(data: &str, defaults: &RowDefaults) -> Result<Rows, BrewLogError> {
+    let checkins: Vec<Value> =
+        serde_json::from_str(data).map_err(|e| BrewLogError::InvalidInput(format!("Invalid Untappd export: {e}")))?;
+    Ok(checkins
+        .iter()
+        .enumerate()
+        .map(|(i, checkin)| {
+            // Untappd writes numbers as strings in some exports and as numbers in others
+            let field = |name: &str| match checkin.get(name)? {
+                Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            };
+            (i as u64 + 1, untappd_entry(field, defaults))
+        })
+        .collect())
+}
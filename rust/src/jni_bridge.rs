@@ -0,0 +1,186 @@
+//! JNI entry points for `com.brewlog.android.BrewLogNative`.
+//!
+//! Each returns the same [`ResultDto`] envelope as its C counterpart in
+//! `ffi.rs`, so errors reported by the core come back as a non-zero code. A
+//! call that can't produce an envelope at all — a null string argument, a
+//! failed JNI call or a panic — raises `com.brewlog.android.BrewLogException`
+//! with the [`ErrorCode`] and message instead, and returns null. Nothing
+//! unwinds into the JVM.
+//!
+//! Handles are passed as `long`; see [`crate::ffi::BrewLogHandle`].
+
+use std::ptr;
+
+use jni::objects::{JClass, JString, JThrowable, JValue};
+use jni::sys::{jdouble, jlong, jstring};
+use jni::JNIEnv;
+
+use crate::bindings::{self, Args};
+use crate::ffi::{catch_panic, close, log, open, payload, BrewLogHandle};
+use crate::{BrewLog, EntryListDto, ErrorCode, FfiError, ResultDto};
+
+/// `BrewLogException(int code, String message)` in the Android app.
+pub const EXCEPTION_CLASS: &str = "com/brewlog/android/BrewLogException";
+
+/// What the core made of a call, before it is wrapped in an envelope.
+type Outcome = Result<serde_json::Value, FfiError>;
+
+fn read_arg(env: &mut JNIEnv, s: &JString, name: &str) -> Result<String, FfiError> {
+    if s.is_null() {
+        return Err(FfiError::null_argument(name));
+    }
+    env.get_string(s)
+        .map(Into::into)
+        .map_err(|e| FfiError::new(ErrorCode::Internal, format!("Couldn't read {name}: {e}")))
+}
+
+fn throw(env: &mut JNIEnv, error: &FfiError) {
+    // A failed JNI call may already have left its own exception pending
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+    let thrown = (|| {
+        let message = env.new_string(&error.message)?;
+        let exception = env.new_object(
+            EXCEPTION_CLASS,
+            "(ILjava/lang/String;)V",
+            &[JValue::Int(error.code.code()), JValue::Object(&message)],
+        )?;
+        env.throw(JThrowable::from(exception))
+    })();
+    if thrown.is_err() && !env.exception_check().unwrap_or(true) {
+        let _ = env.throw_new("java/lang/RuntimeException", &error.message);
+    }
+}
+
+/// Runs an entry point. `body` fails for problems with the call itself, which
+/// are thrown; the outcome it returns is sent back as an envelope.
+fn respond(mut env: JNIEnv, body: impl FnOnce(&mut JNIEnv) -> Result<Outcome, FfiError>) -> jstring {
+    let result = catch_panic(|| {
+        let outcome = body(&mut env)?;
+        env.new_string(ResultDto::from_outcome(outcome).into_json())
+            .map_err(|e| FfiError::new(ErrorCode::Internal, format!("Couldn't return result: {e}")))
+    });
+    match result {
+        Ok(s) => s.into_raw(),
+        Err(error) => {
+            throw(&mut env, &error);
+            ptr::null_mut()
+        }
+    }
+}
+
+// Java has no unsigned long, but handles never get near the sign bit
+fn from_jlong(handle: jlong) -> BrewLogHandle {
+    handle as BrewLogHandle
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_brewlog_1open(env: JNIEnv, _cls: JClass, path: JString) -> jstring {
+    respond(env, |env| {
+        let path = read_arg(env, &path, "path")?;
+        Ok(open(BrewLog::new_with_path(&path)))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_brewlog_1close(env: JNIEnv, _cls: JClass, handle: jlong) -> jstring {
+    respond(env, |_| Ok(close(from_jlong(handle))))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_add_1beer_1entry(env: JNIEnv, _cls: JClass, handle: jlong, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, notes: JString) -> jstring {
+    respond(env, |env| {
+        let name = read_arg(env, &name, "name")?;
+        let notes = read_arg(env, &notes, "notes")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.add_beer_entry(name, alcohol_percentage, volume_ml, notes))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1daily_1consumption(env: JNIEnv, _cls: JClass, handle: jlong, date: JString) -> jstring {
+    respond(env, |env| {
+        let date = read_arg(env, &date, "date")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.get_daily_consumption(date))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1weekly_1consumption(env: JNIEnv, _cls: JClass, handle: jlong, week_start_date: JString) -> jstring {
+    respond(env, |env| {
+        let date = read_arg(env, &week_start_date, "week_start_date")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.get_weekly_consumption(date))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_set_1consumption_1goal(env: JNIEnv, _cls: JClass, handle: jlong, daily_target: jdouble, weekly_target: jdouble, start_date: JString, end_date: JString) -> jstring {
+    respond(env, |env| {
+        let start = read_arg(env, &start_date, "start_date")?;
+        let end = read_arg(env, &end_date, "end_date")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.set_consumption_goal(daily_target, weekly_target, start, end))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1beer_1entries_1json(env: JNIEnv, _cls: JClass, handle: jlong, start_date: JString, end_date: JString) -> jstring {
+    respond(env, |env| {
+        let start = read_arg(env, &start_date, "start_date")?;
+        let end = read_arg(env, &end_date, "end_date")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.get_beer_entries(start, end).map(EntryListDto::new))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_delete_1beer_1entry_1jni(env: JNIEnv, _cls: JClass, handle: jlong, id: JString) -> jstring {
+    respond(env, |env| {
+        let id = read_arg(env, &id, "id")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.delete_beer_entry(id))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_update_1beer_1entry_1jni(env: JNIEnv, _cls: JClass, handle: jlong, id: JString, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, notes: JString) -> jstring {
+    respond(env, |env| {
+        let id = read_arg(env, &id, "id")?;
+        let name = read_arg(env, &name, "name")?;
+        let notes = read_arg(env, &notes, "notes")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.update_beer_entry(id, name, alcohol_percentage, volume_ml, notes))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_update_1beer_1entry_1date_1jni(env: JNIEnv, _cls: JClass, handle: jlong, id: JString, date: JString) -> jstring {
+    respond(env, |env| {
+        let id = read_arg(env, &id, "id")?;
+        let date = read_arg(env, &date, "date")?;
+        Ok(log(from_jlong(handle)).and_then(|log| payload(log.update_beer_entry_date(id, date))))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_add_1beer_1entry_1full_1jni(env: JNIEnv, _cls: JClass, handle: jlong, id: JString, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, date: JString, notes: JString) -> jstring {
+    respond(env, |env| {
+        let id = read_arg(env, &id, "id")?;
+        let name = read_arg(env, &name, "name")?;
+        let date = read_arg(env, &date, "date")?;
+        let notes = read_arg(env, &notes, "notes")?;
+        Ok(log(from_jlong(handle)).and_then(|log| {
+            payload(log.add_beer_entry_full(Some(id), name, alcohol_percentage, volume_ml, date, notes, None))
+        }))
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_delete_1all_1data(env: JNIEnv, _cls: JClass, handle: jlong) -> jstring {
+    respond(env, |_| Ok(log(from_jlong(handle)).and_then(|log| payload(log.clear_all_data()))))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_brewlog_android_BrewLogNative_brewlog_1call(env: JNIEnv, _cls: JClass, handle: jlong, method: JString, args: JString) -> jstring {
+    respond(env, |env| {
+        let method = read_arg(env, &method, "method")?;
+        let args = read_arg(env, &args, "args")?;
+        Ok(log(from_jlong(handle)).and_then(|log| bindings::call(&log, &method, Args::parse(&args)?)))
+    })
+}
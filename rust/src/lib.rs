@@ -1,16 +1,62 @@
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use chrono::{Utc, NaiveDate};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc, Weekday};
 use uuid::Uuid;
-use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
 // use std::fmt; // not used
-use jni::objects::{JClass, JString};
-use jni::sys::{jdouble, jstring as jni_jstring};
-use jni::JNIEnv;
+
+mod alcohol;
+mod backup;
+pub mod bindings;
+mod category;
+mod csv_io;
+mod day;
+mod dto;
+pub mod ffi;
+mod goals;
+mod importers;
+pub mod jni_bridge;
+mod migrations;
+mod presets;
+mod settings;
+mod standard_drink;
+mod stats;
+#[cfg(feature = "uniffi")]
+mod uniffi_api;
+mod week;
+
+pub use alcohol::{ethanol_grams, Consumption, VolumeUnit, ETHANOL_DENSITY_G_PER_ML};
+pub use backup::{
+    Backup, ImportMode, ImportReport, RecordAction, RecordKind, RecordOutcome, BACKUP_FORMAT, BACKUP_VERSION,
+};
+pub use category::{CategoryTotals, DrinkCategory};
+pub use csv_io::{ColumnRef, CsvImportReport, CsvMapping, CsvRowError, CSV_COLUMNS};
+pub use day::DayBoundary;
+pub use dto::{
+    ffi_json_schema, BackupDto, BaselineCalculationDto, BaselineDto, BaselineListDto, CategoryBreakdownDto,
+    CategoryTotalsDto, ConsumptionDto, CsvImportReportDto, CsvRowErrorDto, DayBoundaryDto, DrinkCategoryDto, EntryDto,
+    EntryListDto, ErrorCode, FfiDocument, FfiError, GoalCheckDto, GoalDto, GoalEvaluationDto, GoalKindDto, GoalListDto,
+    GoalStatusDto, GoalUnitDto, ImportModeDto, ImportReportDto, ImportSourceDto, ImporterReportDto,
+    PeriodEvaluationDto, PeriodSummaryDto, PresetDto, PresetListDto, ProgressStatsDto, RecordActionDto, RecordKindDto,
+    RecordOutcomeDto, ResultDto, SettingDto, SettingEntryDto, SettingListDto, SettingValueDto, StandardDrinkDto,
+    TotalsDto, WeekSchemeDto, WeekTotalsDto, FFI_SCHEMA_VERSION,
+};
+pub use goals::{GoalCheck, GoalEvaluation, GoalKind, GoalStatus, PeriodEvaluation};
+pub use importers::{ImportSource, ImporterReport, SPREADSHEET_TEMPLATE};
+pub use migrations::SCHEMA_VERSION;
+pub use presets::DrinkPreset;
+pub use settings::{Setting, SettingEntry, SettingValue};
+pub use standard_drink::{
+    default_standard_drink, find_standard_drink, GoalUnit, StandardDrink, DEFAULT_STANDARD_DRINK, STANDARD_DRINKS,
+};
+pub use stats::PeriodSummary;
+pub use week::{week_bounds, weekday_from_iso, WeekScheme, WeekTotals};
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
 
 #[derive(Debug, thiserror::Error)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Error), uniffi(flat_error))]
 pub enum BrewLogError {
     #[error("Database error: {0}")]
     DatabaseError(String),
@@ -18,6 +64,8 @@ pub enum BrewLogError {
     InvalidInput(String),
     #[error("Not found: {0}")]
     NotFound(String),
+    #[error("Incompatible database: {0}")]
+    IncompatibleDatabase(String),
 }
 
 impl From<rusqlite::Error> for BrewLogError {
@@ -27,6 +75,7 @@ impl From<rusqlite::Error> for BrewLogError {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct BeerEntry {
     pub id: String,
     pub name: String,
@@ -34,33 +83,82 @@ pub struct BeerEntry {
     pub volume_ml: f64,
     pub date: String,
     pub notes: String,
+    /// When the drink was had, as RFC 3339 with the UTC offset in force at the time.
+    /// `None` for entries logged before timestamps were recorded.
+    pub consumed_at: Option<String>,
+    #[serde(default)]
+    pub category: DrinkCategory,
+    /// Finer grouping within the category, e.g. `red` for a wine or `whisky` for spirits.
+    #[serde(default)]
+    pub subtype: Option<String>,
+    /// Free-form style, e.g. `IPA` or `Rioja`.
+    #[serde(default)]
+    pub style: Option<String>,
+    /// When the entry was logged; orders entries on the same day that have no `consumed_at`.
+    #[serde(default)]
+    pub created_at: String,
 }
 
+/// A goal and the window it actually applied in. `start_date`/`end_date` are what
+/// the user asked for; `effective_to` is pulled in when a later goal of the same kind
+/// supersedes this one or it is retired. `daily_target`/`weekly_target` mirror a
+/// `DailyWeekly` kind and are zero for other kinds.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct ConsumptionGoal {
     pub id: String,
+    pub kind: GoalKind,
     pub daily_target: f64,
     pub weekly_target: f64,
+    pub unit: GoalUnit,
     pub start_date: String,
     pub end_date: String,
+    pub effective_from: String,
+    pub effective_to: Option<String>,
+    pub retired_at: Option<String>,
+    /// When the goal was set; breaks ties between goals starting the same day.
+    #[serde(default)]
+    pub created_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct Baseline {
     pub average_daily_consumption: f64,
     pub average_weekly_consumption: f64,
     pub calculated_date: String,
+    pub summary: PeriodSummary,
+}
+
+/// A baseline saved to the database. Each save gets the next `version`; the one
+/// that applies on a date is the highest version whose `effective_from` is on or before it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct BaselineRecord {
+    pub id: String,
+    pub version: u32,
+    pub average_daily_consumption: f64,
+    pub average_weekly_consumption: f64,
+    pub average_daily_ethanol_grams: Option<f64>,
+    pub source_start: Option<String>,
+    pub source_end: Option<String>,
+    pub effective_from: String,
+    pub created_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
 pub struct ProgressStats {
     pub current_daily_average: f64,
     pub current_weekly_average: f64,
     pub reduction_percentage: f64,
+    pub baseline_version: Option<u32>,
     pub period_start: String,
     pub period_end: String,
+    pub summary: PeriodSummary,
 }
 
+#[cfg_attr(feature = "uniffi", derive(uniffi::Object))]
 pub struct BrewLog {
     db: Mutex<Connection>,
 }
@@ -82,37 +180,68 @@ impl BrewLog {
         Ok(log)
     }
 
+    /// Locks the connection. A panic while it was held leaves the mutex poisoned,
+    /// but SQLite has already rolled back whatever that call had in flight, so the
+    /// connection is still safe to use.
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.db.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
     fn init_database(&self) -> Result<(), BrewLogError> {
-        let conn = self.db.lock().unwrap();
-        
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS beer_entries (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                alcohol_percentage REAL NOT NULL,
-                volume_ml REAL NOT NULL,
-                date TEXT NOT NULL,
-                notes TEXT,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+        let mut conn = self.conn();
+        migrations::migrate(&mut conn)
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS consumption_goals (
-                id TEXT PRIMARY KEY,
-                daily_target REAL NOT NULL,
-                weekly_target REAL NOT NULL,
-                start_date TEXT NOT NULL,
-                end_date TEXT NOT NULL,
-                created_at TEXT NOT NULL
-            )",
-            [],
-        )?;
+    /// The schema version of the open database.
+    pub fn schema_version(&self) -> Result<u32, BrewLogError> {
+        let conn = self.conn();
+        migrations::user_version(&conn)
+    }
+
+    /// The standard-drink definition this database converts through.
+    pub fn get_standard_drink(&self) -> Result<StandardDrink, BrewLogError> {
+        let conn = self.conn();
+        let code = read_setting(&conn, Setting::StandardDrink.key())?;
+        Ok(code
+            .as_deref()
+            .and_then(find_standard_drink)
+            .copied()
+            .unwrap_or_else(|| *default_standard_drink()))
+    }
+
+    pub fn set_standard_drink(&self, code: String) -> Result<(), BrewLogError> {
+        if find_standard_drink(&code).is_none() {
+            return Err(BrewLogError::InvalidInput(format!("Unknown standard drink definition: {code}")));
+        }
+        let conn = self.conn();
+        write_setting(&conn, Setting::StandardDrink.key(), &code)?;
+        Ok(())
+    }
+
+    /// Where drinking days begin and end. Every entry's `date` is the drinking day of its `consumed_at`.
+    pub fn get_day_boundary(&self) -> Result<DayBoundary, BrewLogError> {
+        read_day_boundary(&self.conn())
+    }
 
+    /// Changes the day boundary and refiles every timestamped entry under its new drinking day.
+    pub fn set_day_boundary(&self, cutoff_hour: u32, time_zone: String) -> Result<(), BrewLogError> {
+        let boundary = DayBoundary { cutoff_hour, time_zone };
+        boundary.validate()?;
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        write_setting(&tx, Setting::EndOfDayHour.key(), &boundary.cutoff_hour.to_string())?;
+        write_setting(&tx, Setting::TimeZone.key(), &boundary.time_zone)?;
+        refile_entries(&tx, &boundary)?;
+        tx.commit()?;
         Ok(())
     }
 
+    /// Today's drinking day under the configured boundary.
+    pub fn current_drinking_day(&self) -> Result<String, BrewLogError> {
+        Ok(self.get_day_boundary()?.today()?.to_string())
+    }
+
     pub fn add_beer_entry(
         &self,
         name: String,
@@ -120,55 +249,86 @@ impl BrewLog {
         volume_ml: f64,
         notes: String,
     ) -> Result<(), BrewLogError> {
-        if name.is_empty() {
-            return Err(BrewLogError::InvalidInput("Name cannot be empty".to_string()));
-        }
-        if !(0.0..=100.0).contains(&alcohol_percentage) {
-            return Err(BrewLogError::InvalidInput("Alcohol percentage must be between 0 and 100".to_string()));
-        }
-        if volume_ml <= 0.0 {
-            return Err(BrewLogError::InvalidInput("Volume must be positive".to_string()));
-        }
+        validate_entry(&name, alcohol_percentage, volume_ml)?;
+
+        let boundary = self.get_day_boundary()?;
+        let consumed_at = boundary.now()?;
+        let today = boundary.drinking_day(&consumed_at)?.to_string();
+        let consumed_at = consumed_at.to_rfc3339();
 
-        let conn = self.db.lock().unwrap();
+        let conn = self.conn();
         let id = Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
-        let today = Utc::now().date_naive().to_string();
 
         conn.execute(
-            "INSERT INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            (&id, &name, &alcohol_percentage, &volume_ml, &today, &notes, &now),
+            "INSERT INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at, consumed_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            (&id, &name, &alcohol_percentage, &volume_ml, &today, &notes, &now, &consumed_at),
         )?;
 
         Ok(())
     }
 
+    /// Logs a drink at a given RFC 3339 time, filed under its drinking day.
+    /// Returns the new entry's id.
+    pub fn add_beer_entry_at(
+        &self,
+        name: String,
+        alcohol_percentage: f64,
+        volume_ml: f64,
+        notes: String,
+        consumed_at: String,
+    ) -> Result<String, BrewLogError> {
+        let id = Uuid::new_v4().to_string();
+        let date = self.get_day_boundary()?.drinking_day(&parse_timestamp(&consumed_at)?)?.to_string();
+        self.add_beer_entry_full(Some(id.clone()), name, alcohol_percentage, volume_ml, date, notes, Some(consumed_at))?;
+        Ok(id)
+    }
+
     pub fn get_beer_entries(&self, start_date: String, end_date: String) -> Result<Vec<BeerEntry>, BrewLogError> {
-        let conn = self.db.lock().unwrap();
+        let conn = self.conn();
         
-        let mut stmt = conn.prepare(
-            "SELECT id, name, alcohol_percentage, volume_ml, date, notes 
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} 
              FROM beer_entries 
              WHERE date BETWEEN ?1 AND ?2 
-             ORDER BY date DESC, created_at DESC",
-        )?;
+             ORDER BY date DESC, COALESCE(consumed_at, created_at) DESC"
+        ))?;
 
-        let entries = stmt.query_map([&start_date, &end_date], |row| {
-            Ok(BeerEntry {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                alcohol_percentage: row.get(2)?,
-                volume_ml: row.get(3)?,
-                date: row.get(4)?,
-                notes: row.get(5)?,
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+        let entries = stmt.query_map([&start_date, &end_date], entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(entries)
     }
 
+    /// Entries of one category between two drinking days, newest first.
+    pub fn get_beer_entries_by_category(
+        &self,
+        start_date: String,
+        end_date: String,
+        category: DrinkCategory,
+    ) -> Result<Vec<BeerEntry>, BrewLogError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} 
+             FROM beer_entries 
+             WHERE date BETWEEN ?1 AND ?2 AND category = ?3 
+             ORDER BY date DESC, COALESCE(consumed_at, created_at) DESC"
+        ))?;
+        let entries = stmt
+            .query_map((&start_date, &end_date, category.as_str()), entry_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    /// Consumption per category between two drinking days. Categories without entries are left out.
+    pub fn get_category_breakdown(&self, start_date: String, end_date: String) -> Result<Vec<CategoryTotals>, BrewLogError> {
+        parse_date(&start_date)?;
+        parse_date(&end_date)?;
+        let entries = self.get_beer_entries(start_date, end_date)?;
+        Ok(category::category_totals(&entries, &self.get_standard_drink()?))
+    }
+
     pub fn set_consumption_goal(
         &self,
         daily_target: f64,
@@ -176,117 +336,366 @@ impl BrewLog {
         start_date: String,
         end_date: String,
     ) -> Result<(), BrewLogError> {
-        if daily_target < 0.0 {
-            return Err(BrewLogError::InvalidInput("Daily target must be non-negative".to_string()));
-        }
-        if weekly_target < 0.0 {
-            return Err(BrewLogError::InvalidInput("Weekly target must be non-negative".to_string()));
-        }
+        self.set_consumption_goal_in_unit(GoalUnit::VolumeMl, daily_target, weekly_target, start_date, end_date)
+    }
 
-        let conn = self.db.lock().unwrap();
+    pub fn set_consumption_goal_in_unit(
+        &self,
+        unit: GoalUnit,
+        daily_target: f64,
+        weekly_target: f64,
+        start_date: String,
+        end_date: String,
+    ) -> Result<(), BrewLogError> {
+        self.set_goal(GoalKind::DailyWeekly { daily_target, weekly_target }, unit, start_date, end_date)?;
+        Ok(())
+    }
+
+    /// Adds a goal of any kind, superseding earlier goals of the same kind from
+    /// `start_date` and replacing one that starts the same day. A goal of the same
+    /// kind that starts later keeps its window: the new goal ends the day before it.
+    /// Goals of different kinds apply side by side. Returns the new goal's id.
+    pub fn set_goal(
+        &self,
+        kind: GoalKind,
+        unit: GoalUnit,
+        start_date: String,
+        end_date: String,
+    ) -> Result<String, BrewLogError> {
+        kind.validate()?;
+        let start = parse_date(&start_date)?;
+        let mut effective_to = if end_date.is_empty() {
+            None
+        } else if parse_date(&end_date)? < start {
+            return Err(BrewLogError::InvalidInput("End date must not be before start date".to_string()));
+        } else {
+            Some(end_date.clone())
+        };
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
         let id = Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
 
-        // Delete any existing goals
-        conn.execute("DELETE FROM consumption_goals", [])?;
+        // Earlier goals of this kind stop applying the day before this one starts
+        let day_before = start.pred_opt().unwrap_or(start).to_string();
+        tx.execute(
+            "UPDATE consumption_goals SET effective_to = ?1 
+             WHERE kind = ?2 AND effective_from < ?3 AND (effective_to IS NULL OR effective_to > ?1)",
+            [&day_before, kind.name(), &start_date],
+        )?;
+        // One set for the same day is replaced; it stays in the history but never applies
+        tx.execute(
+            "UPDATE consumption_goals SET effective_to = ?1 WHERE kind = ?2 AND effective_from = ?3",
+            [&day_before, kind.name(), &start_date],
+        )?;
 
-        conn.execute(
-            "INSERT INTO consumption_goals (id, daily_target, weekly_target, start_date, end_date, created_at) 
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            (&id, &daily_target, &weekly_target, &start_date, &end_date, &now),
+        // A later goal of this kind keeps its window, so this one ends before it
+        let next_start: Option<String> = tx.query_row(
+            "SELECT MIN(effective_from) FROM consumption_goals 
+             WHERE kind = ?1 AND effective_from > ?2 AND (effective_to IS NULL OR effective_to >= effective_from)",
+            [kind.name(), &start_date],
+            |row| row.get(0),
         )?;
+        if let Some(next_start) = next_start {
+            let last_day = parse_date(&next_start)?.pred_opt().unwrap_or(start).to_string();
+            if effective_to.as_ref().is_none_or(|to| *to > last_day) {
+                effective_to = Some(last_day);
+            }
+        }
 
-        Ok(())
+        let (daily_target, weekly_target, alcohol_free_days, weekday_limits, session_cap, monthly_cap) =
+            goal_kind_columns(&kind)?;
+
+        tx.execute(
+            "INSERT INTO consumption_goals (id, kind, daily_target, weekly_target, alcohol_free_days, weekday_limits, session_cap, monthly_cap, unit, start_date, end_date, effective_from, effective_to, created_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            rusqlite::params![
+                &id,
+                kind.name(),
+                &daily_target,
+                &weekly_target,
+                &alcohol_free_days,
+                &weekday_limits,
+                &session_cap,
+                &monthly_cap,
+                unit.as_str(),
+                &start_date,
+                &end_date,
+                &start_date,
+                &effective_to,
+                &now,
+            ],
+        )?;
+        tx.commit()?;
+
+        Ok(id)
     }
 
+    /// The daily/weekly goal that applies today.
     pub fn get_current_goal(&self) -> Result<ConsumptionGoal, BrewLogError> {
-        let conn = self.db.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, daily_target, weekly_target, start_date, end_date 
-             FROM consumption_goals 
-             ORDER BY created_at DESC 
-             LIMIT 1",
+        self.get_goal_for_date(self.current_drinking_day()?)
+    }
+
+    /// Every goal in effect on `date`, at most one per kind.
+    pub fn get_goals_for_date(&self, date: String) -> Result<Vec<ConsumptionGoal>, BrewLogError> {
+        parse_date(&date)?;
+        let goals = self.list_goals()?;
+        Ok(goals::goals_on(&goals, &date).into_iter().cloned().collect())
+    }
+
+    /// The most recently started daily/weekly goal that applied on `date`, taking
+    /// supersession and retirement into account. Other kinds, whose daily and weekly
+    /// targets are zero, come from `get_goals_for_date`.
+    pub fn get_goal_for_date(&self, date: String) -> Result<ConsumptionGoal, BrewLogError> {
+        parse_date(&date)?;
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM consumption_goals 
+             WHERE kind = 'daily_weekly' AND effective_from <= ?1 AND (effective_to IS NULL OR effective_to >= ?1) 
+             ORDER BY effective_from DESC, created_at DESC 
+             LIMIT 1"
+        ))?;
+        let mut rows = stmt.query_map([&date], goal_from_row)?;
+        rows.next()
+            .transpose()?
+            .ok_or_else(|| BrewLogError::NotFound(format!("No goal in effect on {date}")))
+    }
+
+    /// Every goal ever set, oldest first, including superseded and retired ones.
+    pub fn list_goals(&self) -> Result<Vec<ConsumptionGoal>, BrewLogError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {GOAL_COLUMNS} FROM consumption_goals ORDER BY effective_from, created_at"
+        ))?;
+        let goals = stmt.query_map([], goal_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(goals)
+    }
+
+    /// Stops a goal applying after `last_day`, keeping it in the history.
+    pub fn retire_goal(&self, id: String, last_day: String) -> Result<(), BrewLogError> {
+        parse_date(&last_day)?;
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE consumption_goals 
+             SET effective_to = CASE WHEN effective_to IS NULL OR effective_to > ?1 THEN ?1 ELSE effective_to END, 
+                 retired_at = ?2 
+             WHERE id = ?3",
+            (&last_day, Utc::now().to_rfc3339(), &id),
         )?;
+        if rows == 0 {
+            return Err(BrewLogError::NotFound(format!("Goal with id {id} not found")));
+        }
+        Ok(())
+    }
 
-        let goal = stmt.query_row([], |row| {
-            Ok(ConsumptionGoal {
-                id: row.get(0)?,
-                daily_target: row.get(1)?,
-                weekly_target: row.get(2)?,
-                start_date: row.get(3)?,
-                end_date: row.get(4)?,
-            })
-        })?;
+    /// Compares each day, week and month in the range with the goals in effect at the
+    /// time: a day's own goals, and for weeks and months those in effect on their last
+    /// day. Weeks begin on the configured start-of-week day.
+    pub fn evaluate_goals(&self, start_date: String, end_date: String) -> Result<GoalEvaluation, BrewLogError> {
+        let start = parse_date(&start_date)?;
+        let end = parse_date(&end_date)?;
+        let entries = self.get_beer_entries(start_date, end_date)?;
+        let definition = self.get_standard_drink()?;
+        let daily = stats::daily_totals(start, end, &entries, &definition)?;
+        let sessions = goals::sessions(&entries, &definition);
+        Ok(goals::evaluate(&daily, &sessions, &self.list_goals()?, self.week_start()?))
+    }
 
-        Ok(goal)
+    /// Summarises every calendar day from `start_date` to `end_date` inclusive.
+    pub fn get_period_summary(&self, start_date: String, end_date: String) -> Result<PeriodSummary, BrewLogError> {
+        let start = parse_date(&start_date)?;
+        let end = parse_date(&end_date)?;
+        let entries = self.get_beer_entries(start_date, end_date)?;
+        PeriodSummary::from_entries(start, end, &entries, &self.get_standard_drink()?)
     }
 
     pub fn calculate_baseline(&self, start_date: String, end_date: String) -> Result<Baseline, BrewLogError> {
-        let entries = self.get_beer_entries(start_date.clone(), end_date.clone())?;
+        let summary = self.get_period_summary(start_date, end_date)?;
         
-        if entries.is_empty() {
+        if summary.drinking_days == 0 {
             return Err(BrewLogError::NotFound("No entries found for baseline calculation".to_string()));
         }
 
-        let total_volume: f64 = entries.iter().map(|e| e.volume_ml).sum();
-        let days = entries.len() as f64; // Simplified - in reality you'd calculate actual days
-        
-        let average_daily = total_volume / days;
-        let average_weekly = average_daily * 7.0;
-
         Ok(Baseline {
-            average_daily_consumption: average_daily,
-            average_weekly_consumption: average_weekly,
+            average_daily_consumption: summary.daily_average.volume_ml,
+            average_weekly_consumption: summary.weekly_average.volume_ml,
             calculated_date: Utc::now().to_rfc3339(),
+            summary,
         })
     }
 
-    pub fn get_progress_stats(&self, period_start: String, period_end: String) -> Result<ProgressStats, BrewLogError> {
-        let current_entries = self.get_beer_entries(period_start.clone(), period_end.clone())?;
-        
-        if current_entries.is_empty() {
-            return Err(BrewLogError::NotFound("No entries found for progress calculation".to_string()));
+    /// Saves a calculated baseline as a new version that applies from `effective_from`.
+    pub fn save_baseline(&self, baseline: &Baseline, effective_from: String) -> Result<BaselineRecord, BrewLogError> {
+        self.insert_baseline(
+            baseline.average_daily_consumption,
+            Some(baseline.summary.daily_average.ethanol_grams),
+            Some(baseline.summary.start_date.clone()),
+            Some(baseline.summary.end_date.clone()),
+            effective_from,
+        )
+    }
+
+    /// Saves a baseline entered by hand, in millilitres per day.
+    pub fn set_baseline(&self, average_daily_consumption: f64, effective_from: String) -> Result<BaselineRecord, BrewLogError> {
+        if !(average_daily_consumption.is_finite() && average_daily_consumption >= 0.0) {
+            return Err(BrewLogError::InvalidInput("Baseline must be a non-negative number".to_string()));
         }
+        self.insert_baseline(average_daily_consumption, None, None, None, effective_from)
+    }
 
-        let total_volume: f64 = current_entries.iter().map(|e| e.volume_ml).sum();
-        let days = current_entries.len() as f64; // Simplified calculation
-        
-        let current_daily_average = total_volume / days;
-        let current_weekly_average = current_daily_average * 7.0;
+    fn insert_baseline(
+        &self,
+        average_daily_consumption: f64,
+        average_daily_ethanol_grams: Option<f64>,
+        source_start: Option<String>,
+        source_end: Option<String>,
+        effective_from: String,
+    ) -> Result<BaselineRecord, BrewLogError> {
+        parse_date(&effective_from)?;
+        let conn = self.conn();
+        let version: u32 = conn.query_row("SELECT COALESCE(MAX(version), 0) + 1 FROM baselines", [], |row| row.get(0))?;
+        let record = BaselineRecord {
+            id: Uuid::new_v4().to_string(),
+            version,
+            average_daily_consumption,
+            average_weekly_consumption: average_daily_consumption * 7.0,
+            average_daily_ethanol_grams,
+            source_start,
+            source_end,
+            effective_from,
+            created_at: Utc::now().to_rfc3339(),
+        };
+
+        conn.execute(
+            "INSERT INTO baselines (id, version, average_daily_consumption, average_weekly_consumption, average_daily_ethanol_grams, source_start, source_end, effective_from, created_at) 
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            (
+                &record.id,
+                &record.version,
+                &record.average_daily_consumption,
+                &record.average_weekly_consumption,
+                &record.average_daily_ethanol_grams,
+                &record.source_start,
+                &record.source_end,
+                &record.effective_from,
+                &record.created_at,
+            ),
+        )?;
+
+        Ok(record)
+    }
 
-        // For now, we'll use a simple reduction calculation
-        // In a real app, you'd compare against the baseline
-        let reduction_percentage = 0.0; // Placeholder
+    /// The most recently saved baseline.
+    pub fn get_baseline(&self) -> Result<BaselineRecord, BrewLogError> {
+        self.latest_baseline("", [])?
+            .ok_or_else(|| BrewLogError::NotFound("No baseline has been set".to_string()))
+    }
+
+    /// The baseline that applied on `date`.
+    pub fn get_baseline_for_date(&self, date: String) -> Result<BaselineRecord, BrewLogError> {
+        parse_date(&date)?;
+        self.latest_baseline("WHERE effective_from <= ?1", [&date])?
+            .ok_or_else(|| BrewLogError::NotFound(format!("No baseline in effect on {date}")))
+    }
+
+    pub fn list_baselines(&self) -> Result<Vec<BaselineRecord>, BrewLogError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {BASELINE_COLUMNS} FROM baselines ORDER BY version"))?;
+        let records = stmt.query_map([], baseline_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+
+    fn latest_baseline<P: rusqlite::Params>(&self, filter: &str, params: P) -> Result<Option<BaselineRecord>, BrewLogError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {BASELINE_COLUMNS} FROM baselines {filter} ORDER BY version DESC LIMIT 1"
+        ))?;
+        let mut rows = stmt.query_map(params, baseline_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    pub fn get_progress_stats(&self, period_start: String, period_end: String) -> Result<ProgressStats, BrewLogError> {
+        let summary = self.get_period_summary(period_start.clone(), period_end.clone())?;
+
+        let baseline = match self.get_baseline_for_date(period_end.clone()) {
+            Ok(b) => Some(b),
+            Err(BrewLogError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        let reduction_percentage = baseline.as_ref().map_or(0.0, |b| reduction_percentage(b, &summary));
 
         Ok(ProgressStats {
-            current_daily_average,
-            current_weekly_average,
+            current_daily_average: summary.daily_average.volume_ml,
+            current_weekly_average: summary.weekly_average.volume_ml,
             reduction_percentage,
+            baseline_version: baseline.map(|b| b.version),
             period_start,
             period_end,
+            summary,
         })
     }
 
     pub fn get_daily_consumption(&self, date: String) -> Result<f64, BrewLogError> {
+        Ok(self.get_daily_totals(date)?.volume_ml)
+    }
+
+    pub fn get_daily_totals(&self, date: String) -> Result<Consumption, BrewLogError> {
         let entries = self.get_beer_entries(date.clone(), date)?;
-        let total_volume: f64 = entries.iter().map(|e| e.volume_ml).sum();
-        Ok(total_volume)
+        Ok(Consumption::from_entries(&entries, &self.get_standard_drink()?))
     }
 
     pub fn get_weekly_consumption(&self, week_start_date: String) -> Result<f64, BrewLogError> {
+        Ok(self.get_weekly_totals(week_start_date)?.volume_ml)
+    }
+
+    /// The configured first day of the week, as an ISO day number (1 = Monday … 7 = Sunday).
+    pub fn get_start_of_week(&self) -> Result<u32, BrewLogError> {
+        let conn = self.conn();
+        match read_setting(&conn, Setting::StartOfWeek.key())? {
+            Some(day) => day
+                .parse()
+                .map_err(|_| BrewLogError::DatabaseError(format!("Invalid stored start of week: {day}"))),
+            None => Ok(1),
+        }
+    }
+
+    pub fn set_start_of_week(&self, day: u32) -> Result<(), BrewLogError> {
+        weekday_from_iso(day)?;
+        let conn = self.conn();
+        write_setting(&conn, Setting::StartOfWeek.key(), &day.to_string())
+    }
+
+    fn week_start(&self) -> Result<Weekday, BrewLogError> {
+        weekday_from_iso(self.get_start_of_week()?)
+    }
+
+    /// Totals for the whole week that `date` falls in, delimited by `scheme`.
+    pub fn get_week_totals(&self, date: String, scheme: WeekScheme) -> Result<WeekTotals, BrewLogError> {
+        let day = parse_date(&date)?;
+        let (first, last) = week_bounds(day, scheme.start(self.week_start()?));
+        let iso = (scheme == WeekScheme::Iso).then(|| day.iso_week());
+        let entries = self.get_beer_entries(first.to_string(), last.to_string())?;
+        Ok(WeekTotals {
+            start_date: first.to_string(),
+            end_date: last.to_string(),
+            iso_year: iso.map(|w| w.year()),
+            iso_week: iso.map(|w| w.week()),
+            totals: Consumption::from_entries(&entries, &self.get_standard_drink()?),
+        })
+    }
+
+    pub fn get_weekly_totals(&self, week_start_date: String) -> Result<Consumption, BrewLogError> {
         // Calculate end of week (7 days later)
-        let start_date = NaiveDate::parse_from_str(&week_start_date, "%Y-%m-%d")
-            .map_err(|_| BrewLogError::InvalidInput("Invalid date format".to_string()))?;
+        let start_date = parse_date(&week_start_date)?;
         let end_date = start_date + chrono::Duration::days(6);
         
         let entries = self.get_beer_entries(week_start_date, end_date.to_string())?;
-        let total_volume: f64 = entries.iter().map(|e| e.volume_ml).sum();
-        Ok(total_volume)
+        Ok(Consumption::from_entries(&entries, &self.get_standard_drink()?))
     }
 
     pub fn delete_beer_entry(&self, id: String) -> Result<(), BrewLogError> {
-        let conn = self.db.lock().unwrap();
+        let conn = self.conn();
         
         let rows_affected = conn.execute("DELETE FROM beer_entries WHERE id = ?1", [&id])?;
         
@@ -305,17 +714,9 @@ impl BrewLog {
         volume_ml: f64,
         notes: String,
     ) -> Result<(), BrewLogError> {
-        if name.is_empty() {
-            return Err(BrewLogError::InvalidInput("Name cannot be empty".to_string()));
-        }
-        if !(0.0..=100.0).contains(&alcohol_percentage) {
-            return Err(BrewLogError::InvalidInput("Alcohol percentage must be between 0 and 100".to_string()));
-        }
-        if volume_ml <= 0.0 {
-            return Err(BrewLogError::InvalidInput("Volume must be positive".to_string()));
-        }
+        validate_entry(&name, alcohol_percentage, volume_ml)?;
 
-        let conn = self.db.lock().unwrap();
+        let conn = self.conn();
         
         let rows_affected = conn.execute(
             "UPDATE beer_entries 
@@ -331,11 +732,56 @@ impl BrewLog {
         Ok(())
     }
 
+    /// Moves an entry to another drinking day. A timestamped entry keeps its time
+    /// of day and is shifted by the same number of days.
     pub fn update_beer_entry_date(&self, id: String, date: String) -> Result<(), BrewLogError> {
-        let conn = self.db.lock().unwrap();
+        let new_date = parse_date(&date)?;
+        let conn = self.conn();
+        let (old_date, consumed_at): (String, Option<String>) = conn
+            .query_row("SELECT date, consumed_at FROM beer_entries WHERE id = ?1", [&id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => BrewLogError::NotFound(format!("Beer entry with id {id} not found")),
+                e => e.into(),
+            })?;
+        let consumed_at = match (consumed_at, parse_date(&old_date)) {
+            (Some(t), Ok(old)) => Some((parse_timestamp(&t)? + (new_date - old)).to_rfc3339()),
+            (t, _) => t,
+        };
+        conn.execute(
+            "UPDATE beer_entries SET date = ?1, consumed_at = ?2 WHERE id = ?3",
+            (&date, &consumed_at, &id),
+        )?;
+        Ok(())
+    }
+
+    /// Moves an entry to a new RFC 3339 time, refiling it under that time's drinking day.
+    pub fn update_beer_entry_consumed_at(&self, id: String, consumed_at: String) -> Result<(), BrewLogError> {
+        let timestamp = parse_timestamp(&consumed_at)?;
+        let date = self.get_day_boundary()?.drinking_day(&timestamp)?;
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE beer_entries SET consumed_at = ?1, date = ?2 WHERE id = ?3",
+            (timestamp.to_rfc3339(), date.to_string(), &id),
+        )?;
+        if rows == 0 {
+            return Err(BrewLogError::NotFound(format!("Beer entry with id {id} not found")));
+        }
+        Ok(())
+    }
+
+    pub fn set_beer_entry_category(
+        &self,
+        id: String,
+        category: DrinkCategory,
+        subtype: Option<String>,
+        style: Option<String>,
+    ) -> Result<(), BrewLogError> {
+        let conn = self.conn();
         let rows = conn.execute(
-            "UPDATE beer_entries SET date = ?1 WHERE id = ?2",
-            (&date, &id),
+            "UPDATE beer_entries SET category = ?1, subtype = ?2, style = ?3 WHERE id = ?4",
+            (category.as_str(), &subtype, &style, &id),
         )?;
         if rows == 0 {
             return Err(BrewLogError::NotFound(format!("Beer entry with id {id} not found")));
@@ -343,6 +789,44 @@ impl BrewLog {
         Ok(())
     }
 
+    /// Inserts `entry`, or replaces the entry with the same id, keeping its original
+    /// created_at. An empty id gets a new one, and an empty date is taken from
+    /// `consumed_at`'s drinking day. Returns the entry's id.
+    pub fn save_beer_entry(&self, entry: BeerEntry) -> Result<String, BrewLogError> {
+        validate_entry(&entry.name, entry.alcohol_percentage, entry.volume_ml)?;
+        let consumed_at = entry.consumed_at.as_deref().map(parse_timestamp).transpose()?;
+        let date = match (entry.date.as_str(), &consumed_at) {
+            ("", Some(t)) => self.get_day_boundary()?.drinking_day(t)?.to_string(),
+            ("", None) => return Err(BrewLogError::InvalidInput("Entry needs a date or a consumed_at time".to_string())),
+            (date, _) => parse_date(date)?.to_string(),
+        };
+        let id = if entry.id.is_empty() { Uuid::new_v4().to_string() } else { entry.id };
+
+        let conn = self.conn();
+        conn.execute(
+            "INSERT INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at, consumed_at, category, subtype, style)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, alcohol_percentage = excluded.alcohol_percentage, 
+             volume_ml = excluded.volume_ml, date = excluded.date, notes = excluded.notes, consumed_at = excluded.consumed_at, 
+             category = excluded.category, subtype = excluded.subtype, style = excluded.style",
+            rusqlite::params![
+                &id,
+                &entry.name,
+                &entry.alcohol_percentage,
+                &entry.volume_ml,
+                &date,
+                &entry.notes,
+                Utc::now().to_rfc3339(),
+                consumed_at.map(|t| t.to_rfc3339()),
+                entry.category.as_str(),
+                &entry.subtype,
+                &entry.style,
+            ],
+        )?;
+        Ok(id)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn add_beer_entry_full(
         &self,
         id: Option<String>,
@@ -351,310 +835,215 @@ impl BrewLog {
         volume_ml: f64,
         date: String,
         notes: String,
+        consumed_at: Option<String>,
     ) -> Result<(), BrewLogError> {
-        if name.is_empty() { return Err(BrewLogError::InvalidInput("Name cannot be empty".to_string())); }
-        if !(0.0..=100.0).contains(&alcohol_percentage) { return Err(BrewLogError::InvalidInput("Alcohol percentage must be between 0 and 100".to_string())); }
-        if volume_ml <= 0.0 { return Err(BrewLogError::InvalidInput("Volume must be positive".to_string())); }
-        let conn = self.db.lock().unwrap();
+        validate_entry(&name, alcohol_percentage, volume_ml)?;
+        let consumed_at = consumed_at.map(|t| parse_timestamp(&t)).transpose()?.map(|t| t.to_rfc3339());
+        let conn = self.conn();
         let idv = id.unwrap_or_else(|| Uuid::new_v4().to_string());
         let now = Utc::now().to_rfc3339();
+        // Re-importing an existing id keeps its original created_at
         conn.execute(
-            "INSERT OR REPLACE INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            (&idv, &name, &alcohol_percentage, &volume_ml, &date, &notes, &now),
+            "INSERT INTO beer_entries (id, name, alcohol_percentage, volume_ml, date, notes, created_at, consumed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, alcohol_percentage = excluded.alcohol_percentage, volume_ml = excluded.volume_ml, 
+             date = excluded.date, notes = excluded.notes, consumed_at = excluded.consumed_at",
+            (&idv, &name, &alcohol_percentage, &volume_ml, &date, &notes, &now, &consumed_at),
         )?;
         Ok(())
     }
 
     pub fn clear_all_data(&self) -> Result<(), BrewLogError> {
-        let conn = self.db.lock().unwrap();
+        let conn = self.conn();
         conn.execute("DELETE FROM beer_entries", [])?;
         conn.execute("DELETE FROM consumption_goals", [])?;
+        conn.execute("DELETE FROM baselines", [])?;
+        conn.execute("DELETE FROM drink_presets", [])?;
         Ok(())
     }
 }
 
-// Global instance for JNI/FFI
-static LOG: OnceLock<BrewLog> = OnceLock::new();
-
-// JNI Functions
-#[no_mangle]
-pub extern "C" fn init_brew_log() -> *mut c_char {
-    let msg = match BrewLog::new() {
-        Ok(log) => { let _ = LOG.set(log); "OK".to_string() },
-        Err(e) => format!("Error: {e}"),
-    };
-    CString::new(msg).unwrap().into_raw()
+const ENTRY_COLUMNS: &str =
+    "id, name, alcohol_percentage, volume_ml, date, notes, consumed_at, category, subtype, style, created_at";
+
+fn entry_from_row(row: &rusqlite::Row) -> rusqlite::Result<BeerEntry> {
+    let category: String = row.get(7)?;
+    Ok(BeerEntry {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        alcohol_percentage: row.get(2)?,
+        volume_ml: row.get(3)?,
+        date: row.get(4)?,
+        notes: row.get(5)?,
+        consumed_at: row.get(6)?,
+        category: DrinkCategory::parse(&category).unwrap_or(DrinkCategory::Other),
+        subtype: row.get(8)?,
+        style: row.get(9)?,
+        created_at: row.get(10)?,
+    })
 }
 
-#[no_mangle]
-/// # Safety
-/// `path` must be a valid, non-null C string pointer.
-pub unsafe extern "C" fn init_brew_log_with_path(path: *const c_char) -> *mut c_char {
-    if path.is_null() {
-        return CString::new("Error: path is null").unwrap().into_raw();
-    }
-    let path_str = unsafe { CStr::from_ptr(path).to_string_lossy().into_owned() };
-    let msg = match BrewLog::new_with_path(&path_str) {
-        Ok(log) => { let _ = LOG.set(log); "OK".to_string() },
-        Err(e) => format!("Error: {e}"),
+/// The per-kind goal columns: daily and weekly targets, alcohol_free_days,
+/// weekday_limits (as JSON), session_cap and monthly_cap.
+type GoalKindColumns = (f64, f64, Option<u32>, Option<String>, Option<f64>, Option<f64>);
+
+fn goal_kind_columns(kind: &GoalKind) -> Result<GoalKindColumns, BrewLogError> {
+    let (daily_target, weekly_target) = match kind {
+        GoalKind::DailyWeekly { daily_target, weekly_target } => (*daily_target, *weekly_target),
+        _ => (0.0, 0.0),
+    };
+    let alcohol_free_days = match kind {
+        GoalKind::AlcoholFreeDays { days_per_week } => Some(*days_per_week),
+        _ => None,
+    };
+    let weekday_limits = match kind {
+        GoalKind::WeekdayLimits { limits } => Some(serde_json::to_string(limits).map_err(|e| BrewLogError::InvalidInput(e.to_string()))?),
+        _ => None,
     };
-    CString::new(msg).unwrap().into_raw()
+    let session_cap = match kind {
+        GoalKind::SessionCap { max_per_session } => Some(*max_per_session),
+        _ => None,
+    };
+    let monthly_cap = match kind {
+        GoalKind::MonthlyCap { max_per_month } => Some(*max_per_month),
+        _ => None,
+    };
+    Ok((daily_target, weekly_target, alcohol_free_days, weekday_limits, session_cap, monthly_cap))
 }
 
-#[no_mangle]
-/// # Safety
-/// All pointer arguments must be valid, non-null C string pointers.
-pub unsafe extern "C" fn add_beer_entry(
-    name: *const c_char,
-    alcohol_percentage: f64,
-    volume_ml: f64,
-    notes: *const c_char,
-) -> *mut c_char {
-    unsafe {
-        let Some(log) = LOG.get() else { return CString::new("Error: Log not initialized").unwrap().into_raw(); };
-        let name_str = CStr::from_ptr(name).to_string_lossy().into_owned();
-        let notes_str = CStr::from_ptr(notes).to_string_lossy().into_owned();
-        match log.add_beer_entry(name_str, alcohol_percentage, volume_ml, notes_str) {
-            Ok(_) => CString::new("OK").unwrap().into_raw(),
-            Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
+const GOAL_COLUMNS: &str = "id, daily_target, weekly_target, unit, start_date, end_date, effective_from, \
+     effective_to, retired_at, kind, alcohol_free_days, weekday_limits, session_cap, monthly_cap, created_at";
+
+fn goal_from_row(row: &rusqlite::Row) -> rusqlite::Result<ConsumptionGoal> {
+    let unit: String = row.get(3)?;
+    let daily_target: f64 = row.get(1)?;
+    let weekly_target: f64 = row.get(2)?;
+    let kind_name: String = row.get(9)?;
+    let invalid = |what: String| {
+        rusqlite::Error::FromSqlConversionFailure(9, rusqlite::types::Type::Text, what.into())
+    };
+    let kind = match kind_name.as_str() {
+        "daily_weekly" => GoalKind::DailyWeekly { daily_target, weekly_target },
+        "alcohol_free_days" => GoalKind::AlcoholFreeDays { days_per_week: row.get(10)? },
+        "weekday_limits" => {
+            let limits: String = row.get(11)?;
+            GoalKind::WeekdayLimits {
+                limits: serde_json::from_str(&limits).map_err(|e| invalid(e.to_string()))?,
+            }
         }
-    }
+        "session_cap" => GoalKind::SessionCap { max_per_session: row.get(12)? },
+        "monthly_cap" => GoalKind::MonthlyCap { max_per_month: row.get(13)? },
+        other => return Err(invalid(format!("unknown goal kind {other}"))),
+    };
+    Ok(ConsumptionGoal {
+        id: row.get(0)?,
+        kind,
+        daily_target,
+        weekly_target,
+        unit: GoalUnit::parse(&unit).unwrap_or(GoalUnit::VolumeMl),
+        start_date: row.get(4)?,
+        end_date: row.get(5)?,
+        effective_from: row.get(6)?,
+        effective_to: row.get(7)?,
+        retired_at: row.get(8)?,
+        created_at: row.get(14)?,
+    })
 }
 
-#[no_mangle]
-/// # Safety
-/// `date` must be a valid, non-null C string pointer.
-pub unsafe extern "C" fn get_daily_consumption(date: *const c_char) -> f64 {
-    unsafe {
-        let Some(log) = LOG.get() else { return -1.0; };
-        let date_str = CStr::from_ptr(date).to_string_lossy().into_owned();
-        log.get_daily_consumption(date_str).unwrap_or(-1.0)
-    }
+const BASELINE_COLUMNS: &str = "id, version, average_daily_consumption, average_weekly_consumption, \
+     average_daily_ethanol_grams, source_start, source_end, effective_from, created_at";
+
+fn baseline_from_row(row: &rusqlite::Row) -> rusqlite::Result<BaselineRecord> {
+    Ok(BaselineRecord {
+        id: row.get(0)?,
+        version: row.get(1)?,
+        average_daily_consumption: row.get(2)?,
+        average_weekly_consumption: row.get(3)?,
+        average_daily_ethanol_grams: row.get(4)?,
+        source_start: row.get(5)?,
+        source_end: row.get(6)?,
+        effective_from: row.get(7)?,
+        created_at: row.get(8)?,
+    })
 }
 
-#[no_mangle]
-/// # Safety
-/// `week_start_date` must be a valid, non-null C string pointer.
-pub unsafe extern "C" fn get_weekly_consumption(week_start_date: *const c_char) -> f64 {
-    unsafe {
-        let Some(log) = LOG.get() else { return -1.0; };
-        let date_str = CStr::from_ptr(week_start_date).to_string_lossy().into_owned();
-        log.get_weekly_consumption(date_str).unwrap_or(-1.0)
+/// Percentage drop from the baseline's daily average to the period's. Compares
+/// ethanol when the baseline recorded it, otherwise volume. Negative means an increase.
+fn reduction_percentage(baseline: &BaselineRecord, summary: &PeriodSummary) -> f64 {
+    let (before, after) = match baseline.average_daily_ethanol_grams {
+        Some(grams) if grams > 0.0 => (grams, summary.daily_average.ethanol_grams),
+        _ => (baseline.average_daily_consumption, summary.daily_average.volume_ml),
+    };
+    if before <= 0.0 {
+        return 0.0;
     }
+    (before - after) / before * 100.0
 }
 
-#[no_mangle]
-/// # Safety
-/// All pointer arguments must be valid, non-null C string pointers.
-pub unsafe extern "C" fn set_consumption_goal(
-    daily_target: f64,
-    weekly_target: f64,
-    start_date: *const c_char,
-    end_date: *const c_char,
-) -> *mut c_char {
-    unsafe {
-        let Some(log) = LOG.get() else { return CString::new("Error: Log not initialized").unwrap().into_raw(); };
-        let start_date_str = CStr::from_ptr(start_date).to_string_lossy().into_owned();
-        let end_date_str = CStr::from_ptr(end_date).to_string_lossy().into_owned();
-        match log.set_consumption_goal(daily_target, weekly_target, start_date_str, end_date_str) {
-            Ok(_) => CString::new("OK").unwrap().into_raw(),
-            Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
-        }
+fn validate_entry(name: &str, alcohol_percentage: f64, volume_ml: f64) -> Result<(), BrewLogError> {
+    if name.is_empty() {
+        return Err(BrewLogError::InvalidInput("Name cannot be empty".to_string()));
     }
-}
-
-#[no_mangle]
-/// # Safety
-/// `start_date` and `end_date` must be valid, non-null C string pointers.
-pub unsafe extern "C" fn get_beer_entries_json(start_date: *const c_char, end_date: *const c_char) -> *mut c_char {
-    unsafe {
-        let Some(log) = LOG.get() else { return CString::new("Error: Log not initialized").unwrap().into_raw(); };
-        let start = CStr::from_ptr(start_date).to_string_lossy().into_owned();
-        let end = CStr::from_ptr(end_date).to_string_lossy().into_owned();
-        match log.get_beer_entries(start, end) {
-            Ok(entries) => match serde_json::to_string(&entries) {
-                Ok(s) => CString::new(s).unwrap().into_raw(),
-                Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
-            },
-            Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
-        }
+    if !(0.0..=100.0).contains(&alcohol_percentage) {
+        return Err(BrewLogError::InvalidInput("Alcohol percentage must be between 0 and 100".to_string()));
     }
-}
-
-#[no_mangle]
-/// # Safety
-/// `id` must be a valid, non-null C string pointer.
-pub unsafe extern "C" fn delete_beer_entry_jni(id: *const c_char) -> *mut c_char {
-    unsafe {
-        let Some(log) = LOG.get() else { return CString::new("Error: Log not initialized").unwrap().into_raw(); };
-        let id_str = CStr::from_ptr(id).to_string_lossy().into_owned();
-        match log.delete_beer_entry(id_str) {
-            Ok(()) => CString::new("OK").unwrap().into_raw(),
-            Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
-        }
+    if !(volume_ml.is_finite() && volume_ml > 0.0) {
+        return Err(BrewLogError::InvalidInput("Volume must be positive".to_string()));
     }
+    Ok(())
 }
 
-#[no_mangle]
-/// # Safety
-/// All pointer arguments must be valid, non-null C string pointers.
-pub unsafe extern "C" fn update_beer_entry_jni(
-    id: *const c_char,
-    name: *const c_char,
-    alcohol_percentage: f64,
-    volume_ml: f64,
-    notes: *const c_char,
-) -> *mut c_char {
-    unsafe {
-        let Some(log) = LOG.get() else { return CString::new("Error: Log not initialized").unwrap().into_raw(); };
-        let id_str = CStr::from_ptr(id).to_string_lossy().into_owned();
-        let name_str = CStr::from_ptr(name).to_string_lossy().into_owned();
-        let notes_str = CStr::from_ptr(notes).to_string_lossy().into_owned();
-        match log.update_beer_entry(id_str, name_str, alcohol_percentage, volume_ml, notes_str) {
-            Ok(()) => CString::new("OK").unwrap().into_raw(),
-            Err(e) => CString::new(format!("Error: {e}")).unwrap().into_raw(),
-        }
-    }
-}
-
-// JNI wrappers for Android (class: com.brewlog.android.BrewLogNative)
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_init_1brew_1log(env: JNIEnv, _cls: JClass) -> jni_jstring {
-    let msg = match BrewLog::new() {
-        Ok(log) => { let _ = LOG.set(log); "OK".to_string() },
-        Err(e) => format!("Error: {e}"),
-    };
-    env.new_string(msg).unwrap().into_raw()
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_init_1brew_1log_1with_1path(mut env: JNIEnv, _cls: JClass, path: JString) -> jni_jstring {
-    let path_str: String = env.get_string(&path).unwrap().into();
-    let msg = match BrewLog::new_with_path(&path_str) {
-        Ok(log) => { let _ = LOG.set(log); "OK".to_string() },
-        Err(e) => format!("Error: {e}"),
-    };
-    env.new_string(msg).unwrap().into_raw()
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_add_1beer_1entry(mut env: JNIEnv, _cls: JClass, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, notes: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let n: String = env.get_string(&name).unwrap().into();
-        let notes_s: String = env.get_string(&notes).unwrap().into();
-        match log.add_beer_entry(n, alcohol_percentage, volume_ml, notes_s) {
-            Ok(_) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1daily_1consumption(mut env: JNIEnv, _cls: JClass, date: JString) -> jdouble {
-    if let Some(log) = LOG.get() {
-        let d: String = env.get_string(&date).unwrap().into();
-        log.get_daily_consumption(d).unwrap_or(-1.0)
-    } else { -1.0 }
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1weekly_1consumption(mut env: JNIEnv, _cls: JClass, week_start_date: JString) -> jdouble {
-    if let Some(log) = LOG.get() {
-        let d: String = env.get_string(&week_start_date).unwrap().into();
-        log.get_weekly_consumption(d).unwrap_or(-1.0)
-    } else { -1.0 }
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_set_1consumption_1goal(mut env: JNIEnv, _cls: JClass, daily_target: jdouble, weekly_target: jdouble, start_date: JString, end_date: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let s: String = env.get_string(&start_date).unwrap().into();
-        let e: String = env.get_string(&end_date).unwrap().into();
-        match log.set_consumption_goal(daily_target, weekly_target, s, e) {
-            Ok(_) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
-}
-
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_get_1beer_1entries_1json(mut env: JNIEnv, _cls: JClass, start_date: JString, end_date: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let s: String = env.get_string(&start_date).unwrap().into();
-        let e: String = env.get_string(&end_date).unwrap().into();
-        match log.get_beer_entries(s, e) {
-            Ok(entries) => serde_json::to_string(&entries).unwrap_or_else(|e| format!("Error: {e}")),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<FixedOffset>, BrewLogError> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map_err(|_| BrewLogError::InvalidInput("Invalid timestamp, expected RFC 3339 with a UTC offset".to_string()))
 }
 
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_delete_1beer_1entry_1jni(mut env: JNIEnv, _cls: JClass, id: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let id_s: String = env.get_string(&id).unwrap().into();
-        match log.delete_beer_entry(id_s) {
-            Ok(()) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+fn parse_date(date: &str) -> Result<NaiveDate, BrewLogError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| BrewLogError::InvalidInput("Invalid date format".to_string()))
 }
 
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_update_1beer_1entry_1jni(mut env: JNIEnv, _cls: JClass, id: JString, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, notes: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let id_s: String = env.get_string(&id).unwrap().into();
-        let name_s: String = env.get_string(&name).unwrap().into();
-        let notes_s: String = env.get_string(&notes).unwrap().into();
-        match log.update_beer_entry(id_s, name_s, alcohol_percentage, volume_ml, notes_s) {
-            Ok(()) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+fn read_setting(conn: &Connection, key: &str) -> Result<Option<String>, BrewLogError> {
+    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
+    let mut rows = stmt.query_map([key], |row| row.get::<_, String>(0))?;
+    Ok(rows.next().transpose()?)
 }
 
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_update_1beer_1entry_1date_1jni(mut env: JNIEnv, _cls: JClass, id: JString, date: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let id_s: String = env.get_string(&id).unwrap().into();
-        let date_s: String = env.get_string(&date).unwrap().into();
-        match log.update_beer_entry_date(id_s, date_s) {
-            Ok(()) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+fn read_day_boundary(conn: &Connection) -> Result<DayBoundary, BrewLogError> {
+    let mut boundary = DayBoundary::default();
+    if let Some(hour) = read_setting(conn, Setting::EndOfDayHour.key())? {
+        boundary.cutoff_hour = hour
+            .parse()
+            .map_err(|_| BrewLogError::DatabaseError(format!("Invalid stored cut-off hour: {hour}")))?;
+    }
+    if let Some(zone) = read_setting(conn, Setting::TimeZone.key())? {
+        boundary.time_zone = zone;
+    }
+    Ok(boundary)
 }
 
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_add_1beer_1entry_1full_1jni(mut env: JNIEnv, _cls: JClass, id: JString, name: JString, alcohol_percentage: jdouble, volume_ml: jdouble, date: JString, notes: JString) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        let id_s: String = env.get_string(&id).unwrap().into();
-        let name_s: String = env.get_string(&name).unwrap().into();
-        let date_s: String = env.get_string(&date).unwrap().into();
-        let notes_s: String = env.get_string(&notes).unwrap().into();
-        match log.add_beer_entry_full(Some(id_s), name_s, alcohol_percentage, volume_ml, date_s, notes_s) {
-            Ok(()) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+/// Files every timestamped entry under its drinking day for `boundary`.
+fn refile_entries(conn: &Connection, boundary: &DayBoundary) -> Result<(), BrewLogError> {
+    let stamped = {
+        let mut stmt = conn.prepare("SELECT id, consumed_at FROM beer_entries WHERE consumed_at IS NOT NULL")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+    for (id, consumed_at) in stamped {
+        let day = boundary.drinking_day(&parse_timestamp(&consumed_at)?)?;
+        conn.execute("UPDATE beer_entries SET date = ?1 WHERE id = ?2", (day.to_string(), &id))?;
+    }
+    Ok(())
 }
 
-#[no_mangle]
-pub extern "system" fn Java_com_brewlog_android_BrewLogNative_delete_1all_1data(env: JNIEnv, _cls: JClass) -> jni_jstring {
-    let msg = if let Some(log) = LOG.get() {
-        match log.clear_all_data() {
-            Ok(()) => "OK".to_string(),
-            Err(e) => format!("Error: {e}"),
-        }
-    } else { "Error: Log not initialized".to_string() };
-    env.new_string(msg).unwrap().into_raw()
+fn write_setting(conn: &Connection, key: &str, value: &str) -> Result<(), BrewLogError> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        (key, value),
+    )?;
+    Ok(())
 }
 
 // Test functions
@@ -726,7 +1115,7 @@ mod tests {
             "Test notes".to_string(),
         ).unwrap();
         
-        let today = chrono::Utc::now().date_naive().to_string();
+        let today = log.current_drinking_day().unwrap();
         let entries = log.get_beer_entries(today.clone(), today);
         
         assert!(entries.is_ok());
@@ -741,8 +1130,8 @@ mod tests {
     fn test_set_and_get_goals() {
         let log = BrewLog::new().unwrap();
         
-        let today = chrono::Utc::now().date_naive().to_string();
-        let end_date = (chrono::Utc::now().date_naive() + chrono::Duration::days(30)).to_string();
+        let today = log.current_drinking_day().unwrap();
+        let end_date = (parse_date(&today).unwrap() + chrono::Duration::days(30)).to_string();
         
         let result = log.set_consumption_goal(500.0, 3500.0, today, end_date);
         assert!(result.is_ok());
@@ -754,6 +1143,467 @@ mod tests {
         assert_eq!(goal.weekly_target, 3500.0);
     }
 
+    #[test]
+    fn test_goal_history() {
+        let log = BrewLog::new().unwrap();
+        
+        log.set_consumption_goal(1000.0, 7000.0, "2024-01-01".to_string(), String::new()).unwrap();
+        log.set_consumption_goal(500.0, 3500.0, "2024-02-01".to_string(), "2024-02-29".to_string()).unwrap();
+        
+        let goals = log.list_goals().unwrap();
+        assert_eq!(goals.len(), 2);
+        assert_eq!(goals[0].effective_to.as_deref(), Some("2024-01-31"));
+        
+        assert_eq!(log.get_goal_for_date("2024-01-15".to_string()).unwrap().daily_target, 1000.0);
+        assert_eq!(log.get_goal_for_date("2024-02-15".to_string()).unwrap().daily_target, 500.0);
+        assert!(log.get_goal_for_date("2024-03-01".to_string()).is_err());
+        
+        log.retire_goal(goals[1].id.clone(), "2024-02-10".to_string()).unwrap();
+        assert!(log.get_goal_for_date("2024-02-15".to_string()).is_err());
+        let retired = log.list_goals().unwrap().remove(1);
+        assert!(retired.retired_at.is_some());
+        assert_eq!(retired.effective_to.as_deref(), Some("2024-02-10"));
+        
+        // A later goal of another kind doesn't displace the daily/weekly one
+        log.set_goal(GoalKind::SessionCap { max_per_session: 750.0 }, GoalUnit::VolumeMl, "2024-01-10".to_string(), String::new()).unwrap();
+        assert_eq!(log.get_goal_for_date("2024-01-15".to_string()).unwrap().daily_target, 1000.0);
+        assert!(log.get_goal_for_date("2024-02-15".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_goals_set_out_of_order() {
+        let log = BrewLog::new().unwrap();
+
+        log.set_consumption_goal(300.0, 2100.0, "2024-06-01".to_string(), String::new()).unwrap();
+        log.set_consumption_goal(500.0, 3500.0, "2024-03-01".to_string(), String::new()).unwrap();
+        log.set_consumption_goal(400.0, 2800.0, "2024-04-01".to_string(), "2024-08-31".to_string()).unwrap();
+
+        let windows: Vec<(String, Option<String>)> =
+            log.list_goals().unwrap().into_iter().map(|g| (g.effective_from, g.effective_to)).collect();
+        assert_eq!(
+            windows,
+            [
+                ("2024-03-01".to_string(), Some("2024-03-31".to_string())),
+                ("2024-04-01".to_string(), Some("2024-05-31".to_string())),
+                ("2024-06-01".to_string(), None),
+            ]
+        );
+        assert_eq!(log.get_goal_for_date("2024-03-15".to_string()).unwrap().daily_target, 500.0);
+        assert_eq!(log.get_goal_for_date("2024-05-15".to_string()).unwrap().daily_target, 400.0);
+        assert_eq!(log.get_goal_for_date("2024-07-01".to_string()).unwrap().daily_target, 300.0);
+
+        // Setting a goal again for the same day replaces it
+        log.set_consumption_goal(250.0, 1750.0, "2024-06-01".to_string(), String::new()).unwrap();
+        assert_eq!(log.get_goals_for_date("2024-07-01".to_string()).unwrap().len(), 1);
+        assert_eq!(log.get_goal_for_date("2024-07-01".to_string()).unwrap().daily_target, 250.0);
+    }
+
+    #[test]
+    fn test_evaluate_goals() {
+        let log = BrewLog::new().unwrap();
+        
+        log.set_consumption_goal(600.0, 2000.0, "2024-03-02".to_string(), String::new()).unwrap();
+        for (date, volume) in [("2024-03-01", 900.0), ("2024-03-02", 500.0), ("2024-03-03", 1000.0), ("2024-03-09", 200.0)] {
+            log.add_beer_entry_full(None, "Beer".to_string(), 5.0, volume, date.to_string(), String::new(), None).unwrap();
+        }
+        
+        let eval = log.evaluate_goals("2024-03-01".to_string(), "2024-03-10".to_string()).unwrap();
+        assert_eq!(eval.days.len(), 10);
+        assert_eq!(eval.days[0].status, GoalStatus::NoGoal);
+        assert_eq!(eval.days[1].status, GoalStatus::Within);
+        assert_eq!(eval.days[1].checks[0].margin, 100.0);
+        assert_eq!(eval.days[2].status, GoalStatus::Over);
+        assert_eq!((eval.days_with_goal, eval.days_within_goal, eval.days_over_goal), (9, 8, 1));
+        
+        // 2024-03-01 is a Friday, so the first Monday-based week is cut to three days
+        assert_eq!(eval.weeks.len(), 2);
+        assert_eq!(eval.weeks[0].days, 3);
+        // The goal starts on the Saturday, so that week's 2400 ml counts against it
+        assert_eq!(eval.weeks[0].status, GoalStatus::Over);
+        assert_eq!(eval.weeks[0].checks[0].actual, 2400.0);
+        assert_eq!(eval.weeks[1].start_date, "2024-03-04");
+        assert_eq!(eval.weeks[1].days, 7);
+        assert_eq!(eval.weeks[1].status, GoalStatus::Within);
+        assert_eq!((eval.weeks_with_goal, eval.weeks_within_goal, eval.weeks_over_goal), (2, 1, 1));
+    }
+
+    #[test]
+    fn test_goal_kinds_apply_side_by_side() {
+        let log = BrewLog::new().unwrap();
+        
+        // 2024-04-01 is a Monday
+        let mut limits = [500.0; 7];
+        limits[5] = 1500.0;
+        log.set_goal(GoalKind::WeekdayLimits { limits }, GoalUnit::VolumeMl, "2024-04-01".to_string(), String::new()).unwrap();
+        log.set_goal(GoalKind::AlcoholFreeDays { days_per_week: 4 }, GoalUnit::VolumeMl, "2024-04-01".to_string(), String::new()).unwrap();
+        log.set_goal(GoalKind::MonthlyCap { max_per_month: 3000.0 }, GoalUnit::VolumeMl, "2024-04-01".to_string(), String::new()).unwrap();
+        assert!(log.set_goal(GoalKind::AlcoholFreeDays { days_per_week: 8 }, GoalUnit::VolumeMl, "2024-04-01".to_string(), String::new()).is_err());
+        
+        for (date, volume) in [("2024-04-01", 400.0), ("2024-04-03", 400.0), ("2024-04-06", 1200.0), ("2024-04-07", 600.0)] {
+            log.add_beer_entry_full(None, "Beer".to_string(), 5.0, volume, date.to_string(), String::new(), None).unwrap();
+        }
+        
+        assert_eq!(log.get_goals_for_date("2024-04-10".to_string()).unwrap().len(), 3);
+        let kinds: Vec<GoalKind> = log.list_goals().unwrap().into_iter().map(|g| g.kind).collect();
+        assert!(kinds.contains(&GoalKind::WeekdayLimits { limits }));
+        
+        let eval = log.evaluate_goals("2024-04-01".to_string(), "2024-04-30".to_string()).unwrap();
+        // Saturday allowance covers 1200 ml, Sunday's 600 ml breaks the 500 ml limit
+        assert_eq!(eval.days[5].status, GoalStatus::Within);
+        assert_eq!(eval.days[6].status, GoalStatus::Over);
+        assert_eq!(eval.days_over_goal, 1);
+        // Four drinking days leaves three dry days, one short
+        assert_eq!(eval.weeks[0].checks[0].actual, 3.0);
+        assert_eq!(eval.weeks[0].status, GoalStatus::Over);
+        assert_eq!(eval.months.len(), 1);
+        assert_eq!(eval.months[0].status, GoalStatus::Within);
+        assert_eq!(eval.months[0].checks[0].margin, 400.0);
+    }
+
+    #[test]
+    fn test_session_cap_splits_sessions_on_gaps() {
+        let log = BrewLog::new().unwrap();
+        
+        log.set_goal(GoalKind::SessionCap { max_per_session: 1000.0 }, GoalUnit::VolumeMl, "2024-05-01".to_string(), String::new()).unwrap();
+        // Lunch and evening are separate sessions; the next evening's drinks are never three hours apart
+        for (at, volume) in [
+            ("2024-05-01T12:00:00+00:00", 500.0),
+            ("2024-05-01T12:30:00+00:00", 400.0),
+            ("2024-05-01T20:00:00+00:00", 600.0),
+            ("2024-05-02T19:00:00+00:00", 500.0),
+            ("2024-05-02T21:00:00+00:00", 300.0),
+            ("2024-05-02T23:30:00+00:00", 300.0),
+        ] {
+            log.add_beer_entry_at("Beer".to_string(), 5.0, volume, String::new(), at.to_string()).unwrap();
+        }
+        // Without times a day's drinks are one session
+        for _ in 0..2 {
+            log.add_beer_entry_full(None, "Beer".to_string(), 5.0, 600.0, "2024-05-03".to_string(), String::new(), None).unwrap();
+        }
+        
+        let eval = log.evaluate_goals("2024-05-01".to_string(), "2024-05-04".to_string()).unwrap();
+        let actual: Vec<f64> = eval.days.iter().map(|d| d.checks[0].actual).collect();
+        assert_eq!(actual, [900.0, 1100.0, 1200.0, 0.0]);
+        let statuses: Vec<GoalStatus> = eval.days.iter().map(|d| d.status).collect();
+        assert_eq!(statuses, [GoalStatus::Within, GoalStatus::Over, GoalStatus::Over, GoalStatus::Within]);
+    }
+
+    #[test]
+    fn test_consumed_at_timestamps() {
+        let log = BrewLog::new().unwrap();
+        
+        let id = log.add_beer_entry_at("Late one".to_string(), 5.0, 500.0, String::new(), "2024-03-01T23:30:00+01:00".to_string()).unwrap();
+        let entries = log.get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap();
+        assert_eq!(entries[0].consumed_at.as_deref(), Some("2024-03-01T23:30:00+01:00"));
+        assert!(log.add_beer_entry_at("Bad".to_string(), 5.0, 500.0, String::new(), "2024-03-01 23:30".to_string()).is_err());
+        
+        log.update_beer_entry_consumed_at(id.clone(), "2024-03-02T10:15:00+01:00".to_string()).unwrap();
+        let entries = log.get_beer_entries("2024-03-02".to_string(), "2024-03-02".to_string()).unwrap();
+        assert_eq!(entries[0].id, id);
+        assert_eq!(entries[0].consumed_at.as_deref(), Some("2024-03-02T10:15:00+01:00"));
+        
+        log.add_beer_entry("Now".to_string(), 5.0, 330.0, String::new()).unwrap();
+        let today = log.current_drinking_day().unwrap();
+        let entries = log.get_beer_entries(today.clone(), today).unwrap();
+        assert!(entries[0].consumed_at.is_some());
+    }
+
+    #[test]
+    fn test_day_boundary_buckets_entries() {
+        let log = BrewLog::new().unwrap();
+        
+        // Drinks before the default 3 AM cut-off count towards the previous day
+        let id = log.add_beer_entry_at("Nightcap".to_string(), 5.0, 500.0, String::new(), "2024-03-02T00:30:00Z".to_string()).unwrap();
+        assert_eq!(log.get_daily_consumption("2024-03-01".to_string()).unwrap(), 500.0);
+        log.set_day_boundary(0, "UTC".to_string()).unwrap();
+        assert_eq!(log.get_daily_consumption("2024-03-02".to_string()).unwrap(), 500.0);
+        
+        // 00:30 UTC on the 2nd is 01:30 in Berlin
+        assert!(log.set_day_boundary(24, "UTC".to_string()).is_err());
+        assert!(log.set_day_boundary(3, "Mars/Olympus".to_string()).is_err());
+        log.set_day_boundary(3, "Europe/Berlin".to_string()).unwrap();
+        assert_eq!(log.get_day_boundary().unwrap(), DayBoundary { cutoff_hour: 3, time_zone: "Europe/Berlin".to_string() });
+        assert_eq!(log.get_daily_consumption("2024-03-01".to_string()).unwrap(), 500.0);
+        assert_eq!(log.get_daily_consumption("2024-03-02".to_string()).unwrap(), 0.0);
+        
+        log.update_beer_entry_date(id.clone(), "2024-03-05".to_string()).unwrap();
+        let entries = log.get_beer_entries("2024-03-05".to_string(), "2024-03-05".to_string()).unwrap();
+        assert_eq!(entries[0].consumed_at.as_deref(), Some("2024-03-06T00:30:00+00:00"));
+    }
+
+    #[test]
+    fn test_start_of_week() {
+        let log = BrewLog::new().unwrap();
+        
+        log.add_beer_entry_full(None, "Sunday".to_string(), 5.0, 500.0, "2024-03-10".to_string(), String::new(), None).unwrap();
+        log.add_beer_entry_full(None, "Monday".to_string(), 5.0, 300.0, "2024-03-11".to_string(), String::new(), None).unwrap();
+        
+        assert_eq!(log.get_start_of_week().unwrap(), 1);
+        let iso = log.get_week_totals("2024-03-10".to_string(), WeekScheme::Iso).unwrap();
+        assert_eq!((iso.start_date.as_str(), iso.end_date.as_str()), ("2024-03-04", "2024-03-10"));
+        assert_eq!((iso.iso_year, iso.iso_week), (Some(2024), Some(10)));
+        assert_eq!(iso.totals.volume_ml, 500.0);
+        
+        assert!(log.set_start_of_week(0).is_err());
+        log.set_start_of_week(7).unwrap();
+        let custom = log.get_week_totals("2024-03-11".to_string(), WeekScheme::Configured).unwrap();
+        assert_eq!((custom.start_date.as_str(), custom.end_date.as_str()), ("2024-03-10", "2024-03-16"));
+        assert_eq!(custom.iso_week, None);
+        assert_eq!(custom.totals.volume_ml, 800.0);
+        
+        let eval = log.evaluate_goals("2024-03-08".to_string(), "2024-03-16".to_string()).unwrap();
+        assert_eq!(eval.weeks[1].start_date, "2024-03-10");
+    }
+
+    #[test]
+    fn test_drink_presets() {
+        let log = BrewLog::new().unwrap();
+        
+        let pint = log.add_preset("Pint".to_string(), DrinkCategory::Beer, 568.0, 4.5, false).unwrap();
+        let wine = log.add_preset("Glass of red".to_string(), DrinkCategory::Wine, 175.0, 13.0, true).unwrap();
+        let dram = log.add_preset("Dram".to_string(), DrinkCategory::Spirits, 25.0, 40.0, false).unwrap();
+        assert!(log.add_preset(String::new(), DrinkCategory::Beer, 568.0, 4.5, false).is_err());
+        
+        log.reorder_presets(vec![dram.id.clone(), pint.id.clone()]).unwrap();
+        let names: Vec<String> = log.list_presets(false).unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["Dram", "Pint", "Glass of red"]);
+        assert!(log.reorder_presets(vec!["missing".to_string()]).is_err());
+        
+        log.set_preset_favorite(pint.id.clone(), true).unwrap();
+        assert_eq!(log.list_presets(true).unwrap().len(), 2);
+        
+        log.update_preset(wine.id.clone(), "Large red".to_string(), DrinkCategory::Wine, 250.0, 13.5, true).unwrap();
+        assert_eq!(log.get_preset(wine.id.clone()).unwrap().volume_ml, 250.0);
+        
+        let entry_id = log.add_beer_entry_from_preset(wine.id.clone(), "with dinner".to_string()).unwrap();
+        let today = log.current_drinking_day().unwrap();
+        let entries = log.get_beer_entries(today.clone(), today).unwrap();
+        assert_eq!(entries[0].id, entry_id);
+        assert_eq!((entries[0].name.as_str(), entries[0].volume_ml), ("Large red", 250.0));
+        assert_eq!(entries[0].category, DrinkCategory::Wine);
+        
+        log.delete_preset(dram.id.clone()).unwrap();
+        assert!(log.get_preset(dram.id).is_err());
+    }
+
+    #[test]
+    fn test_drink_categories() {
+        let log = BrewLog::new().unwrap();
+        
+        log.add_beer_entry_at("Lager".to_string(), 5.0, 500.0, String::new(), "2024-03-01T18:00:00+00:00".to_string()).unwrap();
+        let wine = log.save_beer_entry(BeerEntry {
+            id: String::new(),
+            name: "Rioja".to_string(),
+            alcohol_percentage: 13.5,
+            volume_ml: 175.0,
+            date: String::new(),
+            notes: String::new(),
+            consumed_at: Some("2024-03-01T20:00:00+00:00".to_string()),
+            category: DrinkCategory::Wine,
+            subtype: Some("red".to_string()),
+            style: Some("Rioja".to_string()),
+            created_at: String::new(),
+        }).unwrap();
+        let dram = log.add_beer_entry_at("Islay".to_string(), 46.0, 25.0, String::new(), "2024-03-02T21:00:00+00:00".to_string()).unwrap();
+        log.set_beer_entry_category(dram.clone(), DrinkCategory::Spirits, Some("whisky".to_string()), None).unwrap();
+        assert!(log.set_beer_entry_category("missing".to_string(), DrinkCategory::Wine, None, None).is_err());
+        
+        let wines = log.get_beer_entries_by_category("2024-03-01".to_string(), "2024-03-02".to_string(), DrinkCategory::Wine).unwrap();
+        assert_eq!(wines.len(), 1);
+        assert_eq!(wines[0].id, wine);
+        assert_eq!((wines[0].date.as_str(), wines[0].subtype.as_deref()), ("2024-03-01", Some("red")));
+        
+        let breakdown = log.get_category_breakdown("2024-03-01".to_string(), "2024-03-02".to_string()).unwrap();
+        let categories: Vec<DrinkCategory> = breakdown.iter().map(|c| c.category).collect();
+        assert_eq!(categories, [DrinkCategory::Beer, DrinkCategory::Wine, DrinkCategory::Spirits]);
+        assert_eq!(breakdown[2].totals.volume_ml, 25.0);
+        
+        let summary = log.get_period_summary("2024-03-01".to_string(), "2024-03-02".to_string()).unwrap();
+        assert_eq!(summary.by_category.len(), 3);
+        assert_eq!(summary.by_category[0].entries, 1);
+    }
+
+    #[test]
+    fn test_settings() {
+        let log = BrewLog::new().unwrap();
+        
+        assert_eq!(log.get_setting(Setting::DefaultBeerSize).unwrap(), SettingValue::Number(500.0));
+        assert!(log.list_settings().unwrap().iter().all(|s| s.is_default));
+        
+        log.set_setting(Setting::DefaultBeerSize, SettingValue::Integer(330)).unwrap();
+        assert_eq!(log.get_setting(Setting::DefaultBeerSize).unwrap(), SettingValue::Number(330.0));
+        assert!(log.set_setting(Setting::DefaultBeerStrength, SettingValue::Number(120.0)).is_err());
+        assert!(log.set_setting(Setting::StartOfWeek, SettingValue::Integer(8)).is_err());
+        assert!(log.set_setting(Setting::TimeZone, SettingValue::Text("Mars/Olympus".to_string())).is_err());
+        assert!(log.set_setting(Setting::DefaultBeerSize, SettingValue::Text("lots".to_string())).is_err());
+        
+        // The typed store and the dedicated accessors share storage
+        log.add_beer_entry_at("Late one".to_string(), 5.0, 500.0, String::new(), "2024-03-02T01:30:00+00:00".to_string()).unwrap();
+        assert_eq!(log.get_setting(Setting::EndOfDayHour).unwrap(), SettingValue::Integer(3));
+        log.set_setting(Setting::EndOfDayHour, SettingValue::Number(0.0)).unwrap();
+        assert_eq!(log.get_day_boundary().unwrap().cutoff_hour, 0);
+        assert_eq!(log.get_beer_entries("2024-03-02".to_string(), "2024-03-02".to_string()).unwrap().len(), 1);
+        log.set_start_of_week(7).unwrap();
+        assert_eq!(log.get_setting(Setting::StartOfWeek).unwrap(), SettingValue::Integer(7));
+        
+        log.reset_setting(Setting::DefaultBeerSize).unwrap();
+        let changed: Vec<Setting> = log.list_settings().unwrap().into_iter().filter(|s| !s.is_default).map(|s| s.setting).collect();
+        assert_eq!(changed, [Setting::EndOfDayHour, Setting::StartOfWeek]);
+        assert_eq!(Setting::parse("end_of_day_hour"), Some(Setting::EndOfDayHour));
+        assert_eq!(Setting::parse("goal_daily_ml"), None);
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let log = BrewLog::new().unwrap();
+        
+        let id = log.add_beer_entry_at("Saison".to_string(), 6.5, 330.0, "quote \" and\nnewline".to_string(), "2024-03-01T19:00:00+01:00".to_string()).unwrap();
+        log.set_beer_entry_category(id.clone(), DrinkCategory::Beer, None, Some("Saison".to_string())).unwrap();
+        log.set_consumption_goal(1000.0, 5000.0, "2024-03-01".to_string(), String::new()).unwrap();
+        log.set_goal(GoalKind::AlcoholFreeDays { days_per_week: 3 }, GoalUnit::VolumeMl, "2024-03-01".to_string(), String::new()).unwrap();
+        log.set_baseline(800.0, "2024-02-01".to_string()).unwrap();
+        log.add_preset("Pint".to_string(), DrinkCategory::Beer, 568.0, 4.5, true).unwrap();
+        log.set_setting(Setting::DefaultBeerSize, SettingValue::Number(330.0)).unwrap();
+        let json = log.export_json().unwrap();
+        
+        let restored = BrewLog::new().unwrap();
+        restored.add_beer_entry_at("Stray".to_string(), 5.0, 500.0, String::new(), "2024-03-01T12:00:00+00:00".to_string()).unwrap();
+        
+        let dry = restored.import_json(json.clone(), ImportMode::DryRun).unwrap();
+        assert_eq!((dry.created, dry.failed), (11, 0));
+        assert_eq!(restored.list_goals().unwrap().len(), 0);
+        
+        let report = restored.import_json(json.clone(), ImportMode::Replace).unwrap();
+        assert_eq!((report.created, report.updated, report.failed), (11, 0, 0));
+        let entries = restored.get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].notes, "quote \" and\nnewline");
+        assert_eq!(entries[0].style.as_deref(), Some("Saison"));
+        let created: Vec<String> = log.list_goals().unwrap().into_iter().map(|g| g.created_at).collect();
+        assert_eq!(restored.list_goals().unwrap().into_iter().map(|g| g.created_at).collect::<Vec<_>>(), created);
+        assert_eq!(restored.get_baseline().unwrap().average_daily_consumption, 800.0);
+        assert_eq!(restored.list_presets(true).unwrap()[0].name, "Pint");
+        assert_eq!(restored.get_setting(Setting::DefaultBeerSize).unwrap(), SettingValue::Number(330.0));
+        
+        let again = restored.import_json(json.clone(), ImportMode::Merge).unwrap();
+        assert_eq!((again.created, again.updated), (0, 11));
+        
+        let mut backup: Backup = serde_json::from_str(&json).unwrap();
+        backup.entries[0].volume_ml = -1.0;
+        let report = restored.import_backup(backup.clone(), ImportMode::Merge).unwrap();
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.records.iter().find(|r| r.action == RecordAction::Failed).unwrap().kind, RecordKind::Entry);
+        
+        backup.version = BACKUP_VERSION + 1;
+        assert!(restored.import_backup(backup, ImportMode::Merge).is_err());
+    }
+
+    #[test]
+    fn test_backup_keeps_order_of_untimed_entries() {
+        let log = BrewLog::new().unwrap();
+        log.add_beer_entry_full(Some("first".to_string()), "Mild".to_string(), 3.5, 568.0, "2024-03-01".to_string(), String::new(), None).unwrap();
+        log.add_beer_entry_full(Some("second".to_string()), "Stout".to_string(), 4.2, 568.0, "2024-03-01".to_string(), String::new(), None).unwrap();
+        let ids = |log: &BrewLog| -> Vec<String> {
+            log.get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap().into_iter().map(|e| e.id).collect()
+        };
+        assert_eq!(ids(&log), ["second", "first"]);
+
+        // Restored in the opposite order, the entries still list as they were logged
+        let mut backup = log.export_backup().unwrap();
+        backup.entries.reverse();
+        let restored = BrewLog::new().unwrap();
+        restored.import_backup(backup.clone(), ImportMode::Replace).unwrap();
+        assert_eq!(ids(&restored), ["second", "first"]);
+        let created = |log: &BrewLog| -> Vec<String> { log.export_backup().unwrap().entries.into_iter().map(|e| e.created_at).collect() };
+        assert_eq!(created(&restored), created(&log));
+    }
+
+    #[test]
+    fn test_backup_restores_day_boundary() {
+        let source = BrewLog::new().unwrap();
+        source.set_day_boundary(0, "UTC".to_string()).unwrap();
+        let json = source.export_json().unwrap();
+        
+        // Merged into a log still on the default 3 AM cut-off, the early drink moves to the 2nd
+        let log = BrewLog::new().unwrap();
+        log.add_beer_entry_at("Nightcap".to_string(), 5.0, 500.0, String::new(), "2024-03-02T01:00:00+00:00".to_string()).unwrap();
+        assert_eq!(log.get_daily_consumption("2024-03-01".to_string()).unwrap(), 500.0);
+        log.import_json(json, ImportMode::Merge).unwrap();
+        assert_eq!(log.get_day_boundary().unwrap().cutoff_hour, 0);
+        assert_eq!(log.get_daily_consumption("2024-03-02".to_string()).unwrap(), 500.0);
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let log = BrewLog::new().unwrap();
+        
+        let id = log.add_beer_entry_at("Saison, \"Dupont\"".to_string(), 6.5, 330.0, "line one\nline two".to_string(), "2024-03-01T19:00:00+01:00".to_string()).unwrap();
+        log.set_beer_entry_category(id, DrinkCategory::Beer, None, Some("Saison".to_string())).unwrap();
+        let csv = log.export_csv("2024-03-01".to_string(), "2024-03-01".to_string(), ';').unwrap();
+        assert!(csv.starts_with("id;date;consumed_at;name;"));
+        
+        let restored = BrewLog::new().unwrap();
+        let mapping = CsvMapping { delimiter: ';', ..CsvMapping::default() };
+        let report = restored.import_csv(csv, mapping).unwrap();
+        assert_eq!((report.imported, report.errors.len()), (1, 0));
+        let entry = &restored.get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap()[0];
+        assert_eq!(entry.name, "Saison, \"Dupont\"");
+        assert_eq!(entry.notes, "line one\nline two");
+        assert_eq!(entry.consumed_at.as_deref(), Some("2024-03-01T19:00:00+01:00"));
+        assert_eq!(entry.style.as_deref(), Some("Saison"));
+    }
+
+    #[test]
+    fn test_csv_import_with_mapping() {
+        let log = BrewLog::new().unwrap();
+        log.set_day_boundary(3, "Europe/Berlin".to_string()).unwrap();
+        
+        let sheet = "Day;Drink;Type;Strength;Size (cl);Comment\n\
+                     01/03/2024 22:30;Riesling;Wine;12,5%;15;\"with \"\"friends\"\"\"\n\
+                     02/03/2024 01:15;Pils;;4,8;50;\n\
+                     31/02/2024 20:00;Bad date;Beer;5;33;\n\
+                     03/03/2024 18:00;Cola;non-alcoholic;0;;\n";
+        let mapping = CsvMapping {
+            delimiter: ';',
+            decimal_comma: true,
+            date: "Day".into(),
+            date_format: "%d/%m/%Y %H:%M".to_string(),
+            consumed_at: None,
+            name: "Drink".into(),
+            abv: Some("Strength".into()),
+            volume: Some(ColumnRef::Position(5)),
+            volume_unit: VolumeUnit::Cl,
+            notes: Some("Comment".into()),
+            category: Some("Type".into()),
+            subtype: None,
+            style: None,
+            id: None,
+            ..CsvMapping::default()
+        };
+        let report = log.import_csv(sheet.to_string(), mapping.clone()).unwrap();
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 4);
+        
+        let day = log.get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap();
+        assert_eq!(day.len(), 2, "the 01:15 drink belongs to the evening before");
+        let wine = day.iter().find(|e| e.name == "Riesling").unwrap();
+        assert_eq!((wine.category, wine.alcohol_percentage, wine.volume_ml), (DrinkCategory::Wine, 12.5, 150.0));
+        assert_eq!(wine.notes, "with \"friends\"");
+        assert_eq!(wine.consumed_at.as_deref(), Some("2024-03-01T22:30:00+01:00"));
+        let cola = &log.get_beer_entries("2024-03-03".to_string(), "2024-03-03".to_string()).unwrap()[0];
+        assert_eq!((cola.category, cola.volume_ml), (DrinkCategory::NonAlcoholic, 500.0));
+        
+        let missing = CsvMapping { name: "Beverage".into(), ..mapping.clone() };
+        assert!(log.import_csv(sheet.to_string(), missing).is_err());
+        let misspelt = CsvMapping { abv: Some("Strenght".into()), ..mapping };
+        assert!(log.import_csv(sheet.to_string(), misspelt).is_err());
+
+        // The default mapping's optional columns may be left out of the file
+        let report = log.import_csv("date,name\n2024-03-04,Porter\n".to_string(), CsvMapping::default()).unwrap();
+        assert_eq!((report.imported, report.errors.len()), (1, 0));
+    }
+
     #[test]
     fn test_daily_consumption() {
         let log = BrewLog::new().unwrap();
@@ -766,10 +1616,120 @@ mod tests {
             "Test notes".to_string(),
         ).unwrap();
         
-        let today = chrono::Utc::now().date_naive().to_string();
+        let today = log.current_drinking_day().unwrap();
         let consumption = log.get_daily_consumption(today);
         
         assert!(consumption.is_ok());
         assert_eq!(consumption.unwrap(), 330.0);
     }
+
+    #[test]
+    fn test_daily_totals_account_for_strength() {
+        let log = BrewLog::new().unwrap();
+        
+        log.add_beer_entry("Imperial Stout".to_string(), 12.0, 330.0, String::new()).unwrap();
+        log.add_beer_entry("Shandy".to_string(), 0.5, 330.0, String::new()).unwrap();
+        
+        let today = log.current_drinking_day().unwrap();
+        let totals = log.get_daily_totals(today).unwrap();
+        
+        assert_eq!(totals.volume_ml, 660.0);
+        let expected_grams = 330.0 * 0.125 * ETHANOL_DENSITY_G_PER_ML;
+        assert!((totals.ethanol_grams - expected_grams).abs() < 1e-9);
+        assert!((totals.standard_drinks - expected_grams / 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_baseline_divides_by_calendar_days() {
+        let log = BrewLog::new().unwrap();
+        
+        log.add_beer_entry_full(None, "A".to_string(), 5.0, 500.0, "2024-03-01".to_string(), String::new(), None).unwrap();
+        log.add_beer_entry_full(None, "B".to_string(), 5.0, 500.0, "2024-03-01".to_string(), String::new(), None).unwrap();
+        log.add_beer_entry_full(None, "C".to_string(), 5.0, 400.0, "2024-03-04".to_string(), String::new(), None).unwrap();
+        
+        let baseline = log.calculate_baseline("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+        assert_eq!(baseline.summary.days, 7);
+        assert_eq!(baseline.summary.drinking_days, 2);
+        assert_eq!(baseline.summary.dry_days, 5);
+        assert!((baseline.average_daily_consumption - 1400.0 / 7.0).abs() < 1e-9);
+        assert_eq!(baseline.summary.median_day.volume_ml, 0.0);
+        assert_eq!(baseline.summary.max_day.volume_ml, 1000.0);
+        assert_eq!(baseline.summary.max_day_date.as_deref(), Some("2024-03-01"));
+        
+        let stats = log.get_progress_stats("2024-03-08".to_string(), "2024-03-09".to_string()).unwrap();
+        assert_eq!(stats.summary.dry_days, 2);
+        assert_eq!(stats.current_daily_average, 0.0);
+        
+        log.add_beer_entry_full(None, "Alcohol-free".to_string(), 0.0, 330.0, "2024-03-10".to_string(), String::new(), None).unwrap();
+        let summary = log.get_period_summary("2024-03-10".to_string(), "2024-03-10".to_string()).unwrap();
+        assert_eq!((summary.drinking_days, summary.dry_days), (0, 1));
+        
+        assert!(log.calculate_baseline("2024-03-07".to_string(), "2024-03-01".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_reduction_against_persisted_baseline() {
+        let log = BrewLog::new().unwrap();
+        
+        assert!(log.get_baseline().is_err());
+        for invalid in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(log.set_baseline(invalid, "2024-01-01".to_string()).is_err());
+        }
+        let stats = log.get_progress_stats("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+        assert_eq!(stats.baseline_version, None);
+        assert_eq!(stats.reduction_percentage, 0.0);
+        
+        let first = log.set_baseline(1000.0, "2024-01-01".to_string()).unwrap();
+        let second = log.set_baseline(500.0, "2024-06-01".to_string()).unwrap();
+        assert_eq!((first.version, second.version), (1, 2));
+        assert_eq!(log.get_baseline().unwrap().version, 2);
+        assert_eq!(log.get_baseline_for_date("2024-03-15".to_string()).unwrap().version, 1);
+        assert_eq!(log.list_baselines().unwrap().len(), 2);
+        
+        for day in 1..=7 {
+            log.add_beer_entry_full(None, "Pint".to_string(), 5.0, 500.0, format!("2024-03-0{day}"), String::new(), None).unwrap();
+        }
+        let stats = log.get_progress_stats("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+        assert_eq!(stats.baseline_version, Some(1));
+        assert!((stats.reduction_percentage - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_standard_drink_setting() {
+        let log = BrewLog::new().unwrap();
+        
+        assert_eq!(log.get_standard_drink().unwrap().code, DEFAULT_STANDARD_DRINK);
+        assert!(log.set_standard_drink("nonsense".to_string()).is_err());
+        
+        log.set_standard_drink("us".to_string()).unwrap();
+        assert_eq!(log.get_standard_drink().unwrap().grams, 14.0);
+        
+        log.add_beer_entry("Lager".to_string(), 5.0, 355.0, String::new()).unwrap();
+        let today = log.current_drinking_day().unwrap();
+        let totals = log.get_daily_totals(today).unwrap();
+        assert!((totals.standard_drinks - totals.ethanol_grams / 14.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_catch_panic_reports_internal_error() {
+        let result: Result<(), FfiError> = ffi::catch_panic(|| panic!("boom"));
+        let error = result.unwrap_err();
+        assert_eq!((error.code, error.message.as_str()), (ErrorCode::Internal, "Panicked: boom"));
+    }
+
+    #[test]
+    fn test_log_survives_poisoned_lock() {
+        let log = std::sync::Arc::new(BrewLog::new().unwrap());
+        let holder = log.clone();
+        let _ = std::thread::spawn(move || {
+            let _conn = holder.conn();
+            panic!("panicked while holding the connection");
+        })
+        .join();
+        assert!(log.db.is_poisoned());
+        
+        log.add_beer_entry("Lager".to_string(), 5.0, 330.0, String::new()).unwrap();
+        let today = log.current_drinking_day().unwrap();
+        assert_eq!(log.get_daily_consumption(today).unwrap(), 330.0);
+    }
 } 
\ No newline at end of file
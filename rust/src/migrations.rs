@@ -0,0 +1,124 @@
+use rusqlite::Connection;
+
+use crate::BrewLogError;
+
+/// Schema changes in the order they were made. Migration `i` takes a database from
+/// version `i` to `i + 1`; the version is kept in `PRAGMA user_version`. Never edit
+/// a migration once released: append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: the original schema, shipped before versioning existed
+    "CREATE TABLE beer_entries (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        alcohol_percentage REAL NOT NULL,
+        volume_ml REAL NOT NULL,
+        date TEXT NOT NULL,
+        notes TEXT,
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE consumption_goals (
+        id TEXT PRIMARY KEY,
+        daily_target REAL NOT NULL,
+        weekly_target REAL NOT NULL,
+        start_date TEXT NOT NULL,
+        end_date TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );",
+    // 2: goal units and the settings store
+    "ALTER TABLE consumption_goals ADD COLUMN unit TEXT NOT NULL DEFAULT 'volume_ml';
+    CREATE TABLE settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );",
+    // 3: persisted baselines
+    "CREATE TABLE baselines (
+        id TEXT PRIMARY KEY,
+        version INTEGER NOT NULL UNIQUE,
+        average_daily_consumption REAL NOT NULL,
+        average_weekly_consumption REAL NOT NULL,
+        average_daily_ethanol_grams REAL,
+        source_start TEXT,
+        source_end TEXT,
+        effective_from TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );",
+    // 4: goal history
+    "ALTER TABLE consumption_goals ADD COLUMN effective_from TEXT NOT NULL DEFAULT '';
+    ALTER TABLE consumption_goals ADD COLUMN effective_to TEXT;
+    ALTER TABLE consumption_goals ADD COLUMN retired_at TEXT;
+    UPDATE consumption_goals SET effective_from = start_date, effective_to = NULLIF(end_date, '');",
+    // 5: goal kinds
+    "ALTER TABLE consumption_goals ADD COLUMN kind TEXT NOT NULL DEFAULT 'daily_weekly';
+    ALTER TABLE consumption_goals ADD COLUMN alcohol_free_days INTEGER;
+    ALTER TABLE consumption_goals ADD COLUMN weekday_limits TEXT;
+    ALTER TABLE consumption_goals ADD COLUMN session_cap REAL;
+    ALTER TABLE consumption_goals ADD COLUMN monthly_cap REAL;",
+    // 6: time of day on entries. Entries logged on the day they were drunk have the real time in created_at
+    "ALTER TABLE beer_entries ADD COLUMN consumed_at TEXT;
+    UPDATE beer_entries SET consumed_at = created_at WHERE substr(created_at, 1, 10) = date;",
+    // 7: entries are always looked up by drinking day
+    "CREATE INDEX idx_beer_entries_date ON beer_entries (date);",
+    // 8: drink presets
+    "CREATE TABLE drink_presets (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        drink_type TEXT NOT NULL,
+        volume_ml REAL NOT NULL,
+        alcohol_percentage REAL NOT NULL,
+        favorite INTEGER NOT NULL DEFAULT 0,
+        position INTEGER NOT NULL,
+        created_at TEXT NOT NULL
+    );",
+    // 9: drink categories. Presets already had a free-form type; fold it into the taxonomy
+    "ALTER TABLE beer_entries ADD COLUMN category TEXT NOT NULL DEFAULT 'beer';
+    ALTER TABLE beer_entries ADD COLUMN subtype TEXT;
+    ALTER TABLE beer_entries ADD COLUMN style TEXT;
+    ALTER TABLE drink_presets RENAME COLUMN drink_type TO category;
+    UPDATE drink_presets SET category = CASE replace(lower(category), '-', '_')
+        WHEN 'beer' THEN 'beer'
+        WHEN 'wine' THEN 'wine'
+        WHEN 'spirits' THEN 'spirits'
+        WHEN 'cider' THEN 'cider'
+        WHEN 'cocktail' THEN 'cocktail'
+        WHEN 'non_alcoholic' THEN 'non_alcoholic'
+        ELSE 'other'
+    END;
+    CREATE INDEX idx_beer_entries_category ON beer_entries (category);",
+];
+
+/// The schema version this library writes.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+pub(crate) fn user_version(conn: &Connection) -> Result<u32, BrewLogError> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Brings the database up to [`SCHEMA_VERSION`], one transaction per migration.
+pub(crate) fn migrate(conn: &mut Connection) -> Result<(), BrewLogError> {
+    let mut version = user_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(BrewLogError::IncompatibleDatabase(format!(
+            "schema version {version} is newer than the {SCHEMA_VERSION} this version of BrewLog supports"
+        )));
+    }
+
+    // Databases from before versioning have the original tables but no version
+    if version == 0 {
+        let legacy: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'beer_entries')",
+            [],
+            |row| row.get(0),
+        )?;
+        if legacy {
+            version = 1;
+        }
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", index as u32 + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
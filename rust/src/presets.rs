@@ -0,0 +1,173 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{validate_entry, BeerEntry, BrewLog, BrewLogError, DrinkCategory};
+
+/// A saved drink for one-tap logging. Presets are listed by `position`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct DrinkPreset {
+    pub id: String,
+    pub name: String,
+    pub category: DrinkCategory,
+    pub volume_ml: f64,
+    pub alcohol_percentage: f64,
+    pub favorite: bool,
+    pub position: u32,
+}
+
+const PRESET_COLUMNS: &str = "id, name, category, volume_ml, alcohol_percentage, favorite, position";
+
+fn preset_from_row(row: &rusqlite::Row) -> rusqlite::Result<DrinkPreset> {
+    let category: String = row.get(2)?;
+    Ok(DrinkPreset {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        category: DrinkCategory::parse(&category).unwrap_or(DrinkCategory::Other),
+        volume_ml: row.get(3)?,
+        alcohol_percentage: row.get(4)?,
+        favorite: row.get(5)?,
+        position: row.get(6)?,
+    })
+}
+
+fn not_found(id: &str) -> BrewLogError {
+    BrewLogError::NotFound(format!("Drink preset with id {id} not found"))
+}
+
+impl BrewLog {
+    /// Adds a preset at the end of the list.
+    pub fn add_preset(
+        &self,
+        name: String,
+        category: DrinkCategory,
+        volume_ml: f64,
+        alcohol_percentage: f64,
+        favorite: bool,
+    ) -> Result<DrinkPreset, BrewLogError> {
+        validate_entry(&name, alcohol_percentage, volume_ml)?;
+        let conn = self.conn();
+        let position: u32 = conn.query_row("SELECT COALESCE(MAX(position) + 1, 0) FROM drink_presets", [], |row| row.get(0))?;
+        let preset = DrinkPreset {
+            id: Uuid::new_v4().to_string(),
+            name,
+            category,
+            volume_ml,
+            alcohol_percentage,
+            favorite,
+            position,
+        };
+        conn.execute(
+            "INSERT INTO drink_presets (id, name, category, volume_ml, alcohol_percentage, favorite, position, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            (
+                &preset.id,
+                &preset.name,
+                preset.category.as_str(),
+                &preset.volume_ml,
+                &preset.alcohol_percentage,
+                &preset.favorite,
+                &preset.position,
+                Utc::now().to_rfc3339(),
+            ),
+        )?;
+        Ok(preset)
+    }
+
+    pub fn get_preset(&self, id: String) -> Result<DrinkPreset, BrewLogError> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&format!("SELECT {PRESET_COLUMNS} FROM drink_presets WHERE id = ?1"))?;
+        let mut rows = stmt.query_map([&id], preset_from_row)?;
+        rows.next().transpose()?.ok_or_else(|| not_found(&id))
+    }
+
+    /// Every preset in display order. With `favorites_only`, just the favourites.
+    pub fn list_presets(&self, favorites_only: bool) -> Result<Vec<DrinkPreset>, BrewLogError> {
+        let conn = self.conn();
+        let filter = if favorites_only { "WHERE favorite = 1" } else { "" };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {PRESET_COLUMNS} FROM drink_presets {filter} ORDER BY position, created_at"
+        ))?;
+        let presets = stmt.query_map([], preset_from_row)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(presets)
+    }
+
+    pub fn update_preset(
+        &self,
+        id: String,
+        name: String,
+        category: DrinkCategory,
+        volume_ml: f64,
+        alcohol_percentage: f64,
+        favorite: bool,
+    ) -> Result<(), BrewLogError> {
+        validate_entry(&name, alcohol_percentage, volume_ml)?;
+        let conn = self.conn();
+        let rows = conn.execute(
+            "UPDATE drink_presets
+             SET name = ?1, category = ?2, volume_ml = ?3, alcohol_percentage = ?4, favorite = ?5
+             WHERE id = ?6",
+            (&name, category.as_str(), &volume_ml, &alcohol_percentage, &favorite, &id),
+        )?;
+        if rows == 0 {
+            return Err(not_found(&id));
+        }
+        Ok(())
+    }
+
+    pub fn set_preset_favorite(&self, id: String, favorite: bool) -> Result<(), BrewLogError> {
+        let conn = self.conn();
+        let rows = conn.execute("UPDATE drink_presets SET favorite = ?1 WHERE id = ?2", (&favorite, &id))?;
+        if rows == 0 {
+            return Err(not_found(&id));
+        }
+        Ok(())
+    }
+
+    pub fn delete_preset(&self, id: String) -> Result<(), BrewLogError> {
+        let conn = self.conn();
+        let rows = conn.execute("DELETE FROM drink_presets WHERE id = ?1", [&id])?;
+        if rows == 0 {
+            return Err(not_found(&id));
+        }
+        Ok(())
+    }
+
+    /// Puts presets in the order of `ids`. Presets left out keep their relative
+    /// order after the listed ones.
+    pub fn reorder_presets(&self, ids: Vec<String>) -> Result<(), BrewLogError> {
+        let current = self.list_presets(false)?;
+        if let Some(unknown) = ids.iter().find(|id| !current.iter().any(|p| &p.id == *id)) {
+            return Err(not_found(unknown));
+        }
+        let rest = current.iter().map(|p| &p.id).filter(|id| !ids.contains(id));
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for (position, id) in ids.iter().chain(rest).enumerate() {
+            tx.execute("UPDATE drink_presets SET position = ?1 WHERE id = ?2", (position as u32, id))?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Logs the preset's drink now. Returns the new entry's id.
+    pub fn add_beer_entry_from_preset(&self, preset_id: String, notes: String) -> Result<String, BrewLogError> {
+        let preset = self.get_preset(preset_id)?;
+        let now = self.get_day_boundary()?.now()?;
+        self.save_beer_entry(BeerEntry {
+            id: String::new(),
+            name: preset.name,
+            alcohol_percentage: preset.alcohol_percentage,
+            volume_ml: preset.volume_ml,
+            date: String::new(),
+            notes,
+            consumed_at: Some(now.to_rfc3339()),
+            category: preset.category,
+            subtype: None,
+            style: None,
+            created_at: String::new(),
+        })
+    }
+}
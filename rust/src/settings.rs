@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::{find_standard_drink, weekday_from_iso, BrewLog, BrewLogError, DayBoundary, DEFAULT_STANDARD_DRINK};
+
+/// A user preference kept in the database's `settings` table. Unset settings read as
+/// their default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum Setting {
+    /// Volume in ml pre-filled when logging a drink.
+    DefaultBeerSize,
+    /// ABV in percent pre-filled when logging a drink.
+    DefaultBeerStrength,
+    /// Hour at which one drinking day ends and the next begins.
+    EndOfDayHour,
+    /// IANA time zone drinking days are counted in.
+    TimeZone,
+    /// ISO day number weeks start on (1 = Monday … 7 = Sunday).
+    StartOfWeek,
+    /// Code of the standard-drink definition, e.g. `uk_unit`.
+    StandardDrink,
+}
+
+/// A setting's value. Integers are accepted where a number is expected and whole
+/// numbers where an integer is, so JSON clients need not care about the distinction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(untagged)]
+pub enum SettingValue {
+    Integer(i64),
+    Number(f64),
+    Text(String),
+}
+
+impl fmt::Display for SettingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingValue::Integer(i) => write!(f, "{i}"),
+            SettingValue::Number(n) => write!(f, "{n}"),
+            SettingValue::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+/// A setting with its current value, as returned by [`BrewLog::list_settings`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct SettingEntry {
+    pub setting: Setting,
+    pub value: SettingValue,
+    pub is_default: bool,
+}
+
+enum Kind {
+    Integer,
+    Number,
+    Text,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 6] = [
+        Setting::DefaultBeerSize,
+        Setting::DefaultBeerStrength,
+        Setting::EndOfDayHour,
+        Setting::TimeZone,
+        Setting::StartOfWeek,
+        Setting::StandardDrink,
+    ];
+
+    /// The key the setting is stored under.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Setting::DefaultBeerSize => "default_beer_size",
+            Setting::DefaultBeerStrength => "default_beer_strength",
+            Setting::EndOfDayHour => "end_of_day_hour",
+            Setting::TimeZone => "time_zone",
+            Setting::StartOfWeek => "start_of_week",
+            Setting::StandardDrink => "standard_drink",
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    pub fn default_value(&self) -> SettingValue {
+        match self {
+            Setting::DefaultBeerSize => SettingValue::Number(500.0),
+            Setting::DefaultBeerStrength => SettingValue::Number(5.0),
+            Setting::EndOfDayHour => SettingValue::Integer(DayBoundary::default().cutoff_hour as i64),
+            Setting::TimeZone => SettingValue::Text(DayBoundary::default().time_zone),
+            Setting::StartOfWeek => SettingValue::Integer(1),
+            Setting::StandardDrink => SettingValue::Text(DEFAULT_STANDARD_DRINK.to_string()),
+        }
+    }
+
+    fn kind(&self) -> Kind {
+        match self {
+            Setting::DefaultBeerSize | Setting::DefaultBeerStrength => Kind::Number,
+            Setting::EndOfDayHour | Setting::StartOfWeek => Kind::Integer,
+            Setting::TimeZone | Setting::StandardDrink => Kind::Text,
+        }
+    }
+
+    /// Checks `value` has the right type and is in range, returning it in the setting's own type.
+    pub fn validate(&self, value: SettingValue) -> Result<SettingValue, BrewLogError> {
+        let invalid = |why: &str| BrewLogError::InvalidInput(format!("{}: {why}", self.key()));
+        let value = match (self.kind(), value) {
+            (Kind::Number, SettingValue::Integer(i)) => SettingValue::Number(i as f64),
+            (Kind::Integer, SettingValue::Number(n)) if n.fract() == 0.0 => SettingValue::Integer(n as i64),
+            (Kind::Number, v @ SettingValue::Number(_))
+            | (Kind::Integer, v @ SettingValue::Integer(_))
+            | (Kind::Text, v @ SettingValue::Text(_)) => v,
+            (Kind::Number, _) => return Err(invalid("expected a number")),
+            (Kind::Integer, _) => return Err(invalid("expected a whole number")),
+            (Kind::Text, _) => return Err(invalid("expected text")),
+        };
+
+        match (self, &value) {
+            (Setting::DefaultBeerSize, SettingValue::Number(ml)) if *ml <= 0.0 || !ml.is_finite() => {
+                Err(invalid("must be greater than 0"))
+            }
+            (Setting::DefaultBeerStrength, SettingValue::Number(abv)) if !(0.0..=100.0).contains(abv) => {
+                Err(invalid("must be between 0 and 100"))
+            }
+            (Setting::EndOfDayHour, SettingValue::Integer(hour)) if !(0..=23).contains(hour) => {
+                Err(invalid("must be between 0 and 23"))
+            }
+            (Setting::TimeZone, SettingValue::Text(zone)) => DayBoundary {
+                time_zone: zone.clone(),
+                ..DayBoundary::default()
+            }
+            .validate()
+            .map(|_| value),
+            (Setting::StartOfWeek, SettingValue::Integer(day)) => {
+                weekday_from_iso(u32::try_from(*day).unwrap_or(0)).map(|_| value)
+            }
+            (Setting::StandardDrink, SettingValue::Text(code)) if find_standard_drink(code).is_none() => Err(
+                BrewLogError::InvalidInput(format!("Unknown standard drink definition: {code}")),
+            ),
+            _ => Ok(value),
+        }
+    }
+
+    /// Reads a value back from its stored text.
+    pub(crate) fn decode(&self, stored: &str) -> Result<SettingValue, BrewLogError> {
+        let invalid = || BrewLogError::DatabaseError(format!("Invalid stored {}: {stored}", self.key()));
+        Ok(match self.kind() {
+            Kind::Integer => SettingValue::Integer(stored.parse().map_err(|_| invalid())?),
+            Kind::Number => SettingValue::Number(stored.parse().map_err(|_| invalid())?),
+            Kind::Text => SettingValue::Text(stored.to_string()),
+        })
+    }
+}
+
+impl BrewLog {
+    pub fn get_setting(&self, setting: Setting) -> Result<SettingValue, BrewLogError> {
+        let conn = self.conn();
+        match crate::read_setting(&conn, setting.key())? {
+            Some(stored) => setting.decode(&stored),
+            None => Ok(setting.default_value()),
+        }
+    }
+
+    /// Validates and stores a setting. Changing the end-of-day hour or time zone
+    /// refiles timestamped entries, as [`BrewLog::set_day_boundary`] does.
+    pub fn set_setting(&self, setting: Setting, value: SettingValue) -> Result<(), BrewLogError> {
+        let value = setting.validate(value)?;
+        match (setting, value) {
+            (Setting::EndOfDayHour, SettingValue::Integer(hour)) => {
+                let boundary = self.get_day_boundary()?;
+                self.set_day_boundary(hour as u32, boundary.time_zone)
+            }
+            (Setting::TimeZone, SettingValue::Text(zone)) => {
+                let boundary = self.get_day_boundary()?;
+                self.set_day_boundary(boundary.cutoff_hour, zone)
+            }
+            (setting, value) => {
+                let conn = self.conn();
+                crate::write_setting(&conn, setting.key(), &value.to_string())
+            }
+        }
+    }
+
+    /// Puts a setting back to its default.
+    pub fn reset_setting(&self, setting: Setting) -> Result<(), BrewLogError> {
+        self.set_setting(setting, setting.default_value())
+    }
+
+    /// Every setting with its current value.
+    pub fn list_settings(&self) -> Result<Vec<SettingEntry>, BrewLogError> {
+        Setting::ALL
+            .into_iter()
+            .map(|setting| {
+                let value = self.get_setting(setting)?;
+                Ok(SettingEntry {
+                    setting,
+                    is_default: value == setting.default_value(),
+                    value,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// A national definition of one "standard drink", expressed in grams of pure ethanol.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct StandardDrink {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub grams: f64,
+}
+
+impl StandardDrink {
+    pub fn drinks_from_grams(&self, grams: f64) -> f64 {
+        grams / self.grams
+    }
+
+    pub fn grams_from_drinks(&self, drinks: f64) -> f64 {
+        drinks * self.grams
+    }
+}
+
+/// Code of the definition used when a database has not chosen one.
+pub const DEFAULT_STANDARD_DRINK: &str = "who";
+
+/// Every definition the core knows about, keyed by `code`.
+pub const STANDARD_DRINKS: &[StandardDrink] = &[
+    StandardDrink { code: "who", name: "WHO standard drink", grams: 10.0 },
+    StandardDrink { code: "au", name: "Australian standard drink", grams: 10.0 },
+    StandardDrink { code: "nz", name: "New Zealand standard drink", grams: 10.0 },
+    StandardDrink { code: "ie", name: "Irish standard drink", grams: 10.0 },
+    StandardDrink { code: "uk_unit", name: "UK unit", grams: 8.0 },
+    StandardDrink { code: "eu", name: "European standard drink", grams: 12.0 },
+    StandardDrink { code: "ca", name: "Canadian standard drink", grams: 13.45 },
+    StandardDrink { code: "us", name: "US standard drink", grams: 14.0 },
+];
+
+pub fn find_standard_drink(code: &str) -> Option<&'static StandardDrink> {
+    STANDARD_DRINKS.iter().find(|d| d.code == code)
+}
+
+pub fn default_standard_drink() -> &'static StandardDrink {
+    find_standard_drink(DEFAULT_STANDARD_DRINK).expect("default standard drink is registered")
+}
+
+/// The quantity a goal target is expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum GoalUnit {
+    VolumeMl,
+    EthanolGrams,
+    StandardDrinks,
+}
+
+impl GoalUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalUnit::VolumeMl => "volume_ml",
+            GoalUnit::EthanolGrams => "ethanol_grams",
+            GoalUnit::StandardDrinks => "standard_drinks",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "volume_ml" => Some(GoalUnit::VolumeMl),
+            "ethanol_grams" => Some(GoalUnit::EthanolGrams),
+            "standard_drinks" => Some(GoalUnit::StandardDrinks),
+            _ => None,
+        }
+    }
+
+    /// Converts `value` from this unit into `to`. Volume cannot be converted to or
+    /// from an amount of alcohol without knowing the strength, so those pairs return `None`.
+    pub fn convert(&self, value: f64, to: GoalUnit, definition: &StandardDrink) -> Option<f64> {
+        match (self, to) {
+            (a, b) if *a == b => Some(value),
+            (GoalUnit::EthanolGrams, GoalUnit::StandardDrinks) => Some(definition.drinks_from_grams(value)),
+            (GoalUnit::StandardDrinks, GoalUnit::EthanolGrams) => Some(definition.grams_from_drinks(value)),
+            _ => None,
+        }
+    }
+}
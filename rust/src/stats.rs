@@ -0,0 +1,114 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::category::category_totals;
+use crate::{BeerEntry, BrewLogError, CategoryTotals, Consumption, StandardDrink};
+
+/// Day-by-day statistics for an inclusive date range, with days without entries
+/// counted as zero-drink days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct PeriodSummary {
+    pub start_date: String,
+    pub end_date: String,
+    pub days: u32,
+    pub drinking_days: u32,
+    pub dry_days: u32,
+    pub total: Consumption,
+    pub daily_average: Consumption,
+    pub weekly_average: Consumption,
+    /// Median of each measure across every day in the range, dry days included.
+    pub median_day: Consumption,
+    /// Largest single-day value of each measure.
+    pub max_day: Consumption,
+    /// The day with the most ethanol, if any alcohol was logged.
+    pub max_day_date: Option<String>,
+    /// Totals per drink category, for the categories logged in the period.
+    pub by_category: Vec<CategoryTotals>,
+}
+
+impl PeriodSummary {
+    pub fn from_entries(
+        start: NaiveDate,
+        end: NaiveDate,
+        entries: &[BeerEntry],
+        definition: &StandardDrink,
+    ) -> Result<Self, BrewLogError> {
+        let daily = daily_totals(start, end, entries, definition)?;
+
+        let days = daily.len() as u32;
+        // Days with only alcohol-free drinks are dry, as they are for goals
+        let drinking_days = daily.iter().filter(|(_, c)| c.ethanol_grams > 0.0).count() as u32;
+        let total: Consumption = daily.iter().map(|(_, c)| *c).sum();
+        let daily_average = total / days as f64;
+
+        let max_day_date = daily
+            .iter()
+            .filter(|(_, c)| c.ethanol_grams > 0.0)
+            .max_by(|a, b| a.1.ethanol_grams.total_cmp(&b.1.ethanol_grams))
+            .map(|(d, _)| d.to_string());
+
+        let values: Vec<Consumption> = daily.iter().map(|(_, c)| *c).collect();
+
+        Ok(PeriodSummary {
+            start_date: start.to_string(),
+            end_date: end.to_string(),
+            days,
+            drinking_days,
+            dry_days: days - drinking_days,
+            total,
+            daily_average,
+            weekly_average: daily_average * 7.0,
+            median_day: Consumption {
+                volume_ml: median(values.iter().map(|c| c.volume_ml)),
+                ethanol_grams: median(values.iter().map(|c| c.ethanol_grams)),
+                standard_drinks: median(values.iter().map(|c| c.standard_drinks)),
+            },
+            max_day: Consumption {
+                volume_ml: values.iter().map(|c| c.volume_ml).fold(0.0, f64::max),
+                ethanol_grams: values.iter().map(|c| c.ethanol_grams).fold(0.0, f64::max),
+                standard_drinks: values.iter().map(|c| c.standard_drinks).fold(0.0, f64::max),
+            },
+            max_day_date,
+            by_category: category_totals(entries, definition),
+        })
+    }
+}
+
+/// Totals for every calendar day from `start` to `end` inclusive, zero for days without entries.
+pub(crate) fn daily_totals(
+    start: NaiveDate,
+    end: NaiveDate,
+    entries: &[BeerEntry],
+    definition: &StandardDrink,
+) -> Result<Vec<(NaiveDate, Consumption)>, BrewLogError> {
+    if end < start {
+        return Err(BrewLogError::InvalidInput("End date must not be before start date".to_string()));
+    }
+
+    let mut by_date: HashMap<&str, Consumption> = HashMap::new();
+    for entry in entries {
+        *by_date.entry(entry.date.as_str()).or_default() += Consumption::from_entry(entry, definition);
+    }
+
+    Ok(start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .map(|d| (d, by_date.get(d.to_string().as_str()).copied().unwrap_or_default()))
+        .collect())
+}
+
+fn median(values: impl Iterator<Item = f64>) -> f64 {
+    let mut sorted: Vec<f64> = values.collect();
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
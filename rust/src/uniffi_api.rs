@@ -0,0 +1,558 @@
+//! UniFFI export of `BrewLog`, built with the `uniffi` feature.
+//!
+//! Records, enums and [`BrewLogError`] cross as native Kotlin and Swift types.
+//! Methods here delegate to the core ones of the same name, taking arguments
+//! by value as UniFFI requires; `tests/bindings.rs` fails when a public method
+//! is missing. Bindings are generated with the `uniffi-bindgen` binary (see
+//! `uniffi.toml`).
+//!
+//! Types UniFFI can't carry cross as custom types. Converting one back that
+//! doesn't fit, such as a two-character delimiter, fails the call with
+//! [`BrewLogError::InvalidInput`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use crate::{
+    find_standard_drink, Backup, Baseline, BaselineRecord, BeerEntry, BrewLog, BrewLogError, CategoryTotals, ColumnRef,
+    Consumption, ConsumptionGoal, CsvImportReport, CsvMapping, DayBoundary, DrinkCategory, DrinkPreset, GoalEvaluation,
+    GoalKind, GoalUnit, ImportMode, ImportReport, ImportSource, ImporterReport, PeriodSummary, ProgressStats, Setting,
+    SettingEntry, SettingValue, StandardDrink, UniffiCustomTypeConverter, WeekScheme, WeekTotals,
+};
+
+fn invalid(message: String) -> uniffi::deps::anyhow::Error {
+    BrewLogError::InvalidInput(message).into()
+}
+
+/// [`GoalKind`] as the bindings see it. UniFFI has no fixed-size arrays, so
+/// weekday limits are a list that must hold seven values, Monday first.
+#[derive(uniffi::Enum)]
+pub enum GoalTarget {
+    DailyWeekly { daily_target: f64, weekly_target: f64 },
+    AlcoholFreeDays { days_per_week: u32 },
+    WeekdayLimits { limits: Vec<f64> },
+    SessionCap { max_per_session: f64 },
+    MonthlyCap { max_per_month: f64 },
+}
+
+uniffi::custom_type!(GoalKind, GoalTarget);
+
+impl UniffiCustomTypeConverter for GoalKind {
+    type Builtin = GoalTarget;
+
+    fn into_custom(target: GoalTarget) -> uniffi::Result<Self> {
+        Ok(match target {
+            GoalTarget::DailyWeekly { daily_target, weekly_target } => GoalKind::DailyWeekly { daily_target, weekly_target },
+            GoalTarget::AlcoholFreeDays { days_per_week } => GoalKind::AlcoholFreeDays { days_per_week },
+            GoalTarget::WeekdayLimits { limits } => GoalKind::WeekdayLimits {
+                limits: limits
+                    .try_into()
+                    .map_err(|limits: Vec<f64>| invalid(format!("Expected 7 weekday limits, got {}", limits.len())))?,
+            },
+            GoalTarget::SessionCap { max_per_session } => GoalKind::SessionCap { max_per_session },
+            GoalTarget::MonthlyCap { max_per_month } => GoalKind::MonthlyCap { max_per_month },
+        })
+    }
+
+    fn from_custom(kind: GoalKind) -> GoalTarget {
+        match kind {
+            GoalKind::DailyWeekly { daily_target, weekly_target } => GoalTarget::DailyWeekly { daily_target, weekly_target },
+            GoalKind::AlcoholFreeDays { days_per_week } => GoalTarget::AlcoholFreeDays { days_per_week },
+            GoalKind::WeekdayLimits { limits } => GoalTarget::WeekdayLimits { limits: limits.to_vec() },
+            GoalKind::SessionCap { max_per_session } => GoalTarget::SessionCap { max_per_session },
+            GoalKind::MonthlyCap { max_per_month } => GoalTarget::MonthlyCap { max_per_month },
+        }
+    }
+}
+
+/// [`StandardDrink`] as the bindings see it. Only `code` is read back; it must
+/// name a known definition.
+#[derive(uniffi::Record)]
+pub struct StandardDrinkDefinition {
+    pub code: String,
+    pub name: String,
+    pub grams: f64,
+}
+
+uniffi::custom_type!(StandardDrink, StandardDrinkDefinition);
+
+impl UniffiCustomTypeConverter for StandardDrink {
+    type Builtin = StandardDrinkDefinition;
+
+    fn into_custom(definition: StandardDrinkDefinition) -> uniffi::Result<Self> {
+        find_standard_drink(&definition.code)
+            .copied()
+            .ok_or_else(|| invalid(format!("Unknown standard drink {}", definition.code)))
+    }
+
+    fn from_custom(drink: StandardDrink) -> StandardDrinkDefinition {
+        StandardDrinkDefinition { code: drink.code.to_string(), name: drink.name.to_string(), grams: drink.grams }
+    }
+}
+
+/// [`ColumnRef`] as the bindings see it; positions are 1-based.
+#[derive(uniffi::Enum)]
+pub enum CsvColumn {
+    Position(u64),
+    Name(String),
+}
+
+uniffi::custom_type!(ColumnRef, CsvColumn);
+
+impl UniffiCustomTypeConverter for ColumnRef {
+    type Builtin = CsvColumn;
+
+    fn into_custom(column: CsvColumn) -> uniffi::Result<Self> {
+        Ok(match column {
+            CsvColumn::Position(position) => ColumnRef::Position(
+                usize::try_from(position).map_err(|_| invalid(format!("Column {position} is out of range")))?,
+            ),
+            CsvColumn::Name(name) => ColumnRef::Name(name),
+        })
+    }
+
+    fn from_custom(column: ColumnRef) -> CsvColumn {
+        match column {
+            ColumnRef::Position(position) => CsvColumn::Position(position as u64),
+            ColumnRef::Name(name) => CsvColumn::Name(name),
+        }
+    }
+}
+
+/// A CSV delimiter, which crosses as a one-character string.
+pub type Delimiter = char;
+
+uniffi::custom_type!(Delimiter, String);
+
+impl UniffiCustomTypeConverter for Delimiter {
+    type Builtin = String;
+
+    fn into_custom(text: String) -> uniffi::Result<Self> {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(delimiter), None) => Ok(delimiter),
+            _ => Err(invalid(format!("Delimiter must be a single character, got {text:?}"))),
+        }
+    }
+
+    fn from_custom(delimiter: char) -> String {
+        delimiter.to_string()
+    }
+}
+
+/// [`Backup::settings`], which crosses as an unordered map.
+pub type SettingsMap = BTreeMap<String, SettingValue>;
+
+uniffi::custom_type!(SettingsMap, HashMap<String, SettingValue>);
+
+impl UniffiCustomTypeConverter for SettingsMap {
+    type Builtin = HashMap<String, SettingValue>;
+
+    fn into_custom(settings: HashMap<String, SettingValue>) -> uniffi::Result<Self> {
+        Ok(settings.into_iter().collect())
+    }
+
+    fn from_custom(settings: SettingsMap) -> HashMap<String, SettingValue> {
+        settings.into_iter().collect()
+    }
+}
+
+#[uniffi::export]
+impl BrewLog {
+    /// Opens a new, empty in-memory database.
+    #[uniffi::constructor(name = "new")]
+    fn uniffi_new() -> Result<Arc<Self>, BrewLogError> {
+        BrewLog::new().map(Arc::new)
+    }
+
+    /// Opens (creating if needed) the database at `path`.
+    #[uniffi::constructor(name = "new_with_path")]
+    fn uniffi_new_with_path(path: String) -> Result<Arc<Self>, BrewLogError> {
+        BrewLog::new_with_path(&path).map(Arc::new)
+    }
+
+    // Settings and conventions
+
+    #[uniffi::method(name = "schema_version")]
+    fn uniffi_schema_version(&self) -> Result<u32, BrewLogError> {
+        self.schema_version()
+    }
+
+    #[uniffi::method(name = "get_standard_drink")]
+    fn uniffi_get_standard_drink(&self) -> Result<StandardDrink, BrewLogError> {
+        self.get_standard_drink()
+    }
+
+    #[uniffi::method(name = "set_standard_drink")]
+    fn uniffi_set_standard_drink(&self, code: String) -> Result<(), BrewLogError> {
+        self.set_standard_drink(code)
+    }
+
+    #[uniffi::method(name = "get_day_boundary")]
+    fn uniffi_get_day_boundary(&self) -> Result<DayBoundary, BrewLogError> {
+        self.get_day_boundary()
+    }
+
+    #[uniffi::method(name = "set_day_boundary")]
+    fn uniffi_set_day_boundary(&self, cutoff_hour: u32, time_zone: String) -> Result<(), BrewLogError> {
+        self.set_day_boundary(cutoff_hour, time_zone)
+    }
+
+    #[uniffi::method(name = "current_drinking_day")]
+    fn uniffi_current_drinking_day(&self) -> Result<String, BrewLogError> {
+        self.current_drinking_day()
+    }
+
+    #[uniffi::method(name = "get_start_of_week")]
+    fn uniffi_get_start_of_week(&self) -> Result<u32, BrewLogError> {
+        self.get_start_of_week()
+    }
+
+    #[uniffi::method(name = "set_start_of_week")]
+    fn uniffi_set_start_of_week(&self, day: u32) -> Result<(), BrewLogError> {
+        self.set_start_of_week(day)
+    }
+
+    #[uniffi::method(name = "get_setting")]
+    fn uniffi_get_setting(&self, setting: Setting) -> Result<SettingValue, BrewLogError> {
+        self.get_setting(setting)
+    }
+
+    #[uniffi::method(name = "set_setting")]
+    fn uniffi_set_setting(&self, setting: Setting, value: SettingValue) -> Result<(), BrewLogError> {
+        self.set_setting(setting, value)
+    }
+
+    #[uniffi::method(name = "reset_setting")]
+    fn uniffi_reset_setting(&self, setting: Setting) -> Result<(), BrewLogError> {
+        self.reset_setting(setting)
+    }
+
+    #[uniffi::method(name = "list_settings")]
+    fn uniffi_list_settings(&self) -> Result<Vec<SettingEntry>, BrewLogError> {
+        self.list_settings()
+    }
+
+    // Entries
+
+    #[uniffi::method(name = "add_beer_entry")]
+    fn uniffi_add_beer_entry(&self, name: String, alcohol_percentage: f64, volume_ml: f64, notes: String) -> Result<(), BrewLogError> {
+        self.add_beer_entry(name, alcohol_percentage, volume_ml, notes)
+    }
+
+    #[uniffi::method(name = "add_beer_entry_at")]
+    fn uniffi_add_beer_entry_at(
+        &self,
+        name: String,
+        alcohol_percentage: f64,
+        volume_ml: f64,
+        notes: String,
+        consumed_at: String,
+    ) -> Result<String, BrewLogError> {
+        self.add_beer_entry_at(name, alcohol_percentage, volume_ml, notes, consumed_at)
+    }
+
+    #[uniffi::method(name = "add_beer_entry_full")]
+    #[allow(clippy::too_many_arguments)]
+    fn uniffi_add_beer_entry_full(
+        &self,
+        id: Option<String>,
+        name: String,
+        alcohol_percentage: f64,
+        volume_ml: f64,
+        date: String,
+        notes: String,
+        consumed_at: Option<String>,
+    ) -> Result<(), BrewLogError> {
+        self.add_beer_entry_full(id, name, alcohol_percentage, volume_ml, date, notes, consumed_at)
+    }
+
+    #[uniffi::method(name = "save_beer_entry")]
+    fn uniffi_save_beer_entry(&self, entry: BeerEntry) -> Result<String, BrewLogError> {
+        self.save_beer_entry(entry)
+    }
+
+    #[uniffi::method(name = "get_beer_entries")]
+    fn uniffi_get_beer_entries(&self, start_date: String, end_date: String) -> Result<Vec<BeerEntry>, BrewLogError> {
+        self.get_beer_entries(start_date, end_date)
+    }
+
+    #[uniffi::method(name = "get_beer_entries_by_category")]
+    fn uniffi_get_beer_entries_by_category(
+        &self,
+        start_date: String,
+        end_date: String,
+        category: DrinkCategory,
+    ) -> Result<Vec<BeerEntry>, BrewLogError> {
+        self.get_beer_entries_by_category(start_date, end_date, category)
+    }
+
+    #[uniffi::method(name = "update_beer_entry")]
+    fn uniffi_update_beer_entry(
+        &self,
+        id: String,
+        name: String,
+        alcohol_percentage: f64,
+        volume_ml: f64,
+        notes: String,
+    ) -> Result<(), BrewLogError> {
+        self.update_beer_entry(id, name, alcohol_percentage, volume_ml, notes)
+    }
+
+    #[uniffi::method(name = "update_beer_entry_date")]
+    fn uniffi_update_beer_entry_date(&self, id: String, date: String) -> Result<(), BrewLogError> {
+        self.update_beer_entry_date(id, date)
+    }
+
+    #[uniffi::method(name = "update_beer_entry_consumed_at")]
+    fn uniffi_update_beer_entry_consumed_at(&self, id: String, consumed_at: String) -> Result<(), BrewLogError> {
+        self.update_beer_entry_consumed_at(id, consumed_at)
+    }
+
+    #[uniffi::method(name = "set_beer_entry_category")]
+    fn uniffi_set_beer_entry_category(
+        &self,
+        id: String,
+        category: DrinkCategory,
+        subtype: Option<String>,
+        style: Option<String>,
+    ) -> Result<(), BrewLogError> {
+        self.set_beer_entry_category(id, category, subtype, style)
+    }
+
+    #[uniffi::method(name = "delete_beer_entry")]
+    fn uniffi_delete_beer_entry(&self, id: String) -> Result<(), BrewLogError> {
+        self.delete_beer_entry(id)
+    }
+
+    #[uniffi::method(name = "clear_all_data")]
+    fn uniffi_clear_all_data(&self) -> Result<(), BrewLogError> {
+        self.clear_all_data()
+    }
+
+    // Presets
+
+    #[uniffi::method(name = "add_preset")]
+    fn uniffi_add_preset(
+        &self,
+        name: String,
+        category: DrinkCategory,
+        volume_ml: f64,
+        alcohol_percentage: f64,
+        favorite: bool,
+    ) -> Result<DrinkPreset, BrewLogError> {
+        self.add_preset(name, category, volume_ml, alcohol_percentage, favorite)
+    }
+
+    #[uniffi::method(name = "get_preset")]
+    fn uniffi_get_preset(&self, id: String) -> Result<DrinkPreset, BrewLogError> {
+        self.get_preset(id)
+    }
+
+    #[uniffi::method(name = "list_presets")]
+    fn uniffi_list_presets(&self, favorites_only: bool) -> Result<Vec<DrinkPreset>, BrewLogError> {
+        self.list_presets(favorites_only)
+    }
+
+    #[uniffi::method(name = "update_preset")]
+    fn uniffi_update_preset(
+        &self,
+        id: String,
+        name: String,
+        category: DrinkCategory,
+        volume_ml: f64,
+        alcohol_percentage: f64,
+        favorite: bool,
+    ) -> Result<(), BrewLogError> {
+        self.update_preset(id, name, category, volume_ml, alcohol_percentage, favorite)
+    }
+
+    #[uniffi::method(name = "set_preset_favorite")]
+    fn uniffi_set_preset_favorite(&self, id: String, favorite: bool) -> Result<(), BrewLogError> {
+        self.set_preset_favorite(id, favorite)
+    }
+
+    #[uniffi::method(name = "delete_preset")]
+    fn uniffi_delete_preset(&self, id: String) -> Result<(), BrewLogError> {
+        self.delete_preset(id)
+    }
+
+    #[uniffi::method(name = "reorder_presets")]
+    fn uniffi_reorder_presets(&self, ids: Vec<String>) -> Result<(), BrewLogError> {
+        self.reorder_presets(ids)
+    }
+
+    #[uniffi::method(name = "add_beer_entry_from_preset")]
+    fn uniffi_add_beer_entry_from_preset(&self, preset_id: String, notes: String) -> Result<String, BrewLogError> {
+        self.add_beer_entry_from_preset(preset_id, notes)
+    }
+
+    // Goals
+
+    #[uniffi::method(name = "set_consumption_goal")]
+    fn uniffi_set_consumption_goal(
+        &self,
+        daily_target: f64,
+        weekly_target: f64,
+        start_date: String,
+        end_date: String,
+    ) -> Result<(), BrewLogError> {
+        self.set_consumption_goal(daily_target, weekly_target, start_date, end_date)
+    }
+
+    #[uniffi::method(name = "set_consumption_goal_in_unit")]
+    fn uniffi_set_consumption_goal_in_unit(
+        &self,
+        unit: GoalUnit,
+        daily_target: f64,
+        weekly_target: f64,
+        start_date: String,
+        end_date: String,
+    ) -> Result<(), BrewLogError> {
+        self.set_consumption_goal_in_unit(unit, daily_target, weekly_target, start_date, end_date)
+    }
+
+    #[uniffi::method(name = "set_goal")]
+    fn uniffi_set_goal(&self, kind: GoalKind, unit: GoalUnit, start_date: String, end_date: String) -> Result<String, BrewLogError> {
+        self.set_goal(kind, unit, start_date, end_date)
+    }
+
+    #[uniffi::method(name = "get_current_goal")]
+    fn uniffi_get_current_goal(&self) -> Result<ConsumptionGoal, BrewLogError> {
+        self.get_current_goal()
+    }
+
+    #[uniffi::method(name = "get_goals_for_date")]
+    fn uniffi_get_goals_for_date(&self, date: String) -> Result<Vec<ConsumptionGoal>, BrewLogError> {
+        self.get_goals_for_date(date)
+    }
+
+    #[uniffi::method(name = "get_goal_for_date")]
+    fn uniffi_get_goal_for_date(&self, date: String) -> Result<ConsumptionGoal, BrewLogError> {
+        self.get_goal_for_date(date)
+    }
+
+    #[uniffi::method(name = "list_goals")]
+    fn uniffi_list_goals(&self) -> Result<Vec<ConsumptionGoal>, BrewLogError> {
+        self.list_goals()
+    }
+
+    #[uniffi::method(name = "retire_goal")]
+    fn uniffi_retire_goal(&self, id: String, last_day: String) -> Result<(), BrewLogError> {
+        self.retire_goal(id, last_day)
+    }
+
+    #[uniffi::method(name = "evaluate_goals")]
+    fn uniffi_evaluate_goals(&self, start_date: String, end_date: String) -> Result<GoalEvaluation, BrewLogError> {
+        self.evaluate_goals(start_date, end_date)
+    }
+
+    // Totals and analytics
+
+    #[uniffi::method(name = "get_daily_consumption")]
+    fn uniffi_get_daily_consumption(&self, date: String) -> Result<f64, BrewLogError> {
+        self.get_daily_consumption(date)
+    }
+
+    #[uniffi::method(name = "get_daily_totals")]
+    fn uniffi_get_daily_totals(&self, date: String) -> Result<Consumption, BrewLogError> {
+        self.get_daily_totals(date)
+    }
+
+    #[uniffi::method(name = "get_weekly_consumption")]
+    fn uniffi_get_weekly_consumption(&self, week_start_date: String) -> Result<f64, BrewLogError> {
+        self.get_weekly_consumption(week_start_date)
+    }
+
+    #[uniffi::method(name = "get_weekly_totals")]
+    fn uniffi_get_weekly_totals(&self, week_start_date: String) -> Result<Consumption, BrewLogError> {
+        self.get_weekly_totals(week_start_date)
+    }
+
+    #[uniffi::method(name = "get_week_totals")]
+    fn uniffi_get_week_totals(&self, date: String, scheme: WeekScheme) -> Result<WeekTotals, BrewLogError> {
+        self.get_week_totals(date, scheme)
+    }
+
+    #[uniffi::method(name = "get_category_breakdown")]
+    fn uniffi_get_category_breakdown(&self, start_date: String, end_date: String) -> Result<Vec<CategoryTotals>, BrewLogError> {
+        self.get_category_breakdown(start_date, end_date)
+    }
+
+    #[uniffi::method(name = "get_period_summary")]
+    fn uniffi_get_period_summary(&self, start_date: String, end_date: String) -> Result<PeriodSummary, BrewLogError> {
+        self.get_period_summary(start_date, end_date)
+    }
+
+    #[uniffi::method(name = "calculate_baseline")]
+    fn uniffi_calculate_baseline(&self, start_date: String, end_date: String) -> Result<Baseline, BrewLogError> {
+        self.calculate_baseline(start_date, end_date)
+    }
+
+    #[uniffi::method(name = "save_baseline")]
+    fn uniffi_save_baseline(&self, baseline: Baseline, effective_from: String) -> Result<BaselineRecord, BrewLogError> {
+        self.save_baseline(&baseline, effective_from)
+    }
+
+    #[uniffi::method(name = "set_baseline")]
+    fn uniffi_set_baseline(&self, average_daily_consumption: f64, effective_from: String) -> Result<BaselineRecord, BrewLogError> {
+        self.set_baseline(average_daily_consumption, effective_from)
+    }
+
+    #[uniffi::method(name = "get_baseline")]
+    fn uniffi_get_baseline(&self) -> Result<BaselineRecord, BrewLogError> {
+        self.get_baseline()
+    }
+
+    #[uniffi::method(name = "get_baseline_for_date")]
+    fn uniffi_get_baseline_for_date(&self, date: String) -> Result<BaselineRecord, BrewLogError> {
+        self.get_baseline_for_date(date)
+    }
+
+    #[uniffi::method(name = "list_baselines")]
+    fn uniffi_list_baselines(&self) -> Result<Vec<BaselineRecord>, BrewLogError> {
+        self.list_baselines()
+    }
+
+    #[uniffi::method(name = "get_progress_stats")]
+    fn uniffi_get_progress_stats(&self, period_start: String, period_end: String) -> Result<ProgressStats, BrewLogError> {
+        self.get_progress_stats(period_start, period_end)
+    }
+
+    // Import and export
+
+    #[uniffi::method(name = "export_backup")]
+    fn uniffi_export_backup(&self) -> Result<Backup, BrewLogError> {
+        self.export_backup()
+    }
+
+    #[uniffi::method(name = "export_json")]
+    fn uniffi_export_json(&self) -> Result<String, BrewLogError> {
+        self.export_json()
+    }
+
+    #[uniffi::method(name = "import_backup")]
+    fn uniffi_import_backup(&self, backup: Backup, mode: ImportMode) -> Result<ImportReport, BrewLogError> {
+        self.import_backup(backup, mode)
+    }
+
+    #[uniffi::method(name = "import_json")]
+    fn uniffi_import_json(&self, json: String, mode: ImportMode) -> Result<ImportReport, BrewLogError> {
+        self.import_json(json, mode)
+    }
+
+    #[uniffi::method(name = "export_csv")]
+    fn uniffi_export_csv(&self, start_date: String, end_date: String, delimiter: Delimiter) -> Result<String, BrewLogError> {
+        self.export_csv(start_date, end_date, delimiter)
+    }
+
+    #[uniffi::method(name = "import_csv")]
+    fn uniffi_import_csv(&self, data: String, mapping: CsvMapping) -> Result<CsvImportReport, BrewLogError> {
+        self.import_csv(data, mapping)
+    }
+
+    #[uniffi::method(name = "import_from")]
+    fn uniffi_import_from(&self, source: ImportSource, data: String) -> Result<ImporterReport, BrewLogError> {
+        self.import_from(source, data)
+    }
+}
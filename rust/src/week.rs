@@ -0,0 +1,52 @@
+use chrono::{NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::{BrewLogError, Consumption};
+
+/// How weeks are delimited when asking for the week a date falls in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+#[serde(rename_all = "snake_case")]
+pub enum WeekScheme {
+    /// ISO 8601 weeks: Monday to Sunday, numbered within the ISO year.
+    Iso,
+    /// Weeks starting on the database's configured start-of-week day.
+    Configured,
+}
+
+impl WeekScheme {
+    /// The weekday weeks begin on under this scheme, given the configured start.
+    pub fn start(&self, configured: Weekday) -> Weekday {
+        match self {
+            WeekScheme::Iso => Weekday::Mon,
+            WeekScheme::Configured => configured,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct WeekTotals {
+    pub start_date: String,
+    pub end_date: String,
+    /// Set for ISO weeks only.
+    pub iso_year: Option<i32>,
+    pub iso_week: Option<u32>,
+    pub totals: Consumption,
+}
+
+/// Converts an ISO day number (1 = Monday … 7 = Sunday) into a weekday.
+pub fn weekday_from_iso(day: u32) -> Result<Weekday, BrewLogError> {
+    match day {
+        1..=7 => Ok(Weekday::try_from((day - 1) as u8).expect("day is in range")),
+        _ => Err(BrewLogError::InvalidInput(
+            "Start of week must be between 1 (Monday) and 7 (Sunday)".to_string(),
+        )),
+    }
+}
+
+/// First and last day of the week containing `date`, for weeks beginning on `start`.
+pub fn week_bounds(date: NaiveDate, start: Weekday) -> (NaiveDate, NaiveDate) {
+    let week = date.week(start);
+    (week.first_day(), week.last_day())
+}
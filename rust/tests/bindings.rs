@@ -0,0 +1,167 @@
+//! Every public `BrewLog` method must be listed in `src/bindings.rs`, so C and
+//! JNI callers can reach it through `brewlog_call`, and exported in
+//! `src/uniffi_api.rs`, so the Kotlin and Swift bindings have it too.
+
+use std::collections::BTreeSet;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::path::Path;
+
+use brewlog_core::bindings::METHODS;
+use brewlog_core::ffi::*;
+use brewlog_core::{ffi_json_schema, EntryListDto, ErrorCode, FfiDocument, ResultDto};
+use serde_json::{json, Value};
+
+/// Names of the `pub fn`s taking `self` inside `impl BrewLog` blocks under `src/`.
+fn public_methods() -> Vec<String> {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut methods = Vec::new();
+    for file in fs::read_dir(src).unwrap() {
+        let path = file.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "rs") {
+            continue;
+        }
+        let text = fs::read_to_string(path).unwrap();
+        let mut in_impl = false;
+        let mut lines = text.lines();
+        while let Some(line) = lines.next() {
+            if line.starts_with("impl BrewLog {") {
+                in_impl = true;
+            } else if line == "}" {
+                in_impl = false;
+            } else if let Some(rest) = line.strip_prefix("    pub fn ").filter(|_| in_impl) {
+                let name = rest.split(['(', '<']).next().unwrap().to_string();
+                // The receiver is on the next line when the parameters are wrapped
+                let params = if rest.ends_with('(') { lines.next().unwrap_or_default() } else { rest };
+                if params.contains("self") {
+                    methods.push(name);
+                }
+            }
+        }
+    }
+    assert!(methods.len() > 50, "found only {methods:?}; has the impl layout changed?");
+    methods
+}
+
+#[test]
+fn every_public_method_has_a_binding() {
+    let bound: Vec<&str> = METHODS.iter().map(|(name, _)| *name).collect();
+    let missing: Vec<String> = public_methods().into_iter().filter(|m| !bound.contains(&m.as_str())).collect();
+    assert!(missing.is_empty(), "add these to the bindings! table in src/bindings.rs: {missing:?}");
+}
+
+#[test]
+fn every_public_method_is_exported_to_uniffi() {
+    let text = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("src/uniffi_api.rs")).unwrap();
+    let exported: Vec<&str> = text
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("#[uniffi::method(name = \""))
+        .filter_map(|rest| rest.split('"').next())
+        .collect();
+    let missing: Vec<String> = public_methods().into_iter().filter(|m| !exported.contains(&m.as_str())).collect();
+    assert!(missing.is_empty(), "export these in src/uniffi_api.rs: {missing:?}");
+}
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+fn result(s: *mut std::os::raw::c_char) -> ResultDto {
+    let json = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_owned();
+    unsafe { brewlog_string_free(s) };
+    ResultDto::from_json(&json).unwrap()
+}
+
+fn call(log: BrewLogHandle, method: &str, args: Value) -> ResultDto {
+    result(unsafe { brewlog_call(log, c(method).as_ptr(), c(&args.to_string()).as_ptr()) })
+}
+
+fn ok(log: BrewLogHandle, method: &str, args: Value) -> Value {
+    let result = call(log, method, args);
+    assert_eq!(result.error_code(), Some(ErrorCode::Ok), "{method}: {:?}", result.message);
+    result.payload
+}
+
+#[test]
+fn analytics_are_reachable_through_brewlog_call() {
+    let log = result(brewlog_open_in_memory()).payload.as_u64().unwrap();
+    for day in 1..=7 {
+        let entry = json!({
+            "name": "Pint", "alcohol_percentage": 5.0, "volume_ml": 500.0,
+            "date": format!("2024-03-0{day}"), "notes": "",
+        });
+        ok(log, "add_beer_entry_full", entry);
+    }
+
+    let baseline = ok(log, "calculate_baseline", json!({ "start_date": "2024-03-01", "end_date": "2024-03-07" }));
+    assert_eq!(baseline["average_daily_consumption"], 500.0);
+    let record = ok(log, "save_baseline", json!({ "baseline": baseline, "effective_from": "2024-03-01" }));
+    assert_eq!(record["version"], 1);
+
+    ok(log, "set_consumption_goal", json!({ "daily_target": 250.0, "weekly_target": 1750.0, "start_date": "2024-03-01", "end_date": "2099-12-31" }));
+    assert_eq!(ok(log, "get_current_goal", json!({}))["daily_target"], 250.0);
+
+    let stats = ok(log, "get_progress_stats", json!({ "period_start": "2024-03-01", "period_end": "2024-03-07" }));
+    assert_eq!(stats["baseline_version"], 1);
+
+    let entries = ok(log, "get_beer_entries", json!({ "start_date": "2024-03-01", "end_date": "2024-03-01" }));
+    let dedicated = result(unsafe { get_beer_entries_json(log, c("2024-03-01").as_ptr(), c("2024-03-01").as_ptr()) });
+    assert_eq!(entries, dedicated.payload);
+    let id = EntryListDto::from_json(&entries.to_string()).unwrap().entries[0].id.clone();
+    ok(log, "update_beer_entry_date", json!({ "id": id, "date": "2024-03-08" }));
+    assert_eq!(ok(log, "get_daily_consumption", json!({ "date": "2024-03-08" })), 500.0);
+
+    result(brewlog_close(log));
+}
+
+#[test]
+fn schema_describes_every_method() {
+    let schema = ffi_json_schema();
+    let methods = schema["methods"].as_object().unwrap();
+    assert_eq!(methods.len(), METHODS.len());
+    for (name, params) in METHODS {
+        let properties: BTreeSet<&str> = methods[*name]["params"]["properties"].as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(properties, params.iter().copied().collect(), "{name}");
+    }
+
+    let entries = &methods["get_beer_entries"];
+    assert_eq!(entries["result"]["$ref"], "#/definitions/EntryList");
+    assert_eq!(entries["params"]["required"], json!(["start_date", "end_date"]));
+    assert_eq!(methods["add_beer_entry_full"]["params"]["required"], json!(["name", "alcohol_percentage", "volume_ml", "date", "notes"]));
+}
+
+#[test]
+fn csv_mappings_may_leave_out_fields() {
+    let log = result(brewlog_open_in_memory()).payload.as_u64().unwrap();
+    let mapping = json!({ "delimiter": ";", "date": "Day", "name": "Drink", "abv": "ABV", "volume": "Volume", "consumed_at": null });
+    let report = ok(log, "import_csv", json!({ "data": "Day;Drink;ABV;Volume\n2024-03-01;Pint;5;568\n", "mapping": mapping }));
+    assert_eq!((report["imported"].clone(), report["errors"].clone()), (json!(1), json!([])));
+
+    let entries = ok(log, "get_beer_entries", json!({ "start_date": "2024-03-01", "end_date": "2024-03-01" }));
+    assert_eq!(entries["entries"][0]["volume_ml"], 568.0);
+    result(brewlog_close(log));
+}
+
+#[test]
+fn malformed_calls_are_invalid_input() {
+    let log = result(brewlog_open_in_memory()).payload.as_u64().unwrap();
+    let cases = [
+        ("no_such_method", json!({}), "Unknown method no_such_method"),
+        ("get_daily_consumption", json!({}), "Missing argument date"),
+        ("get_daily_consumption", json!({ "date": 1 }), "Invalid argument date"),
+        ("get_daily_consumption", json!({ "date": "2024-03-01", "dat": "" }), "Unexpected argument dat"),
+        ("list_goals", json!([]), "Arguments must be a JSON object"),
+    ];
+    for (method, args, message) in cases {
+        let result = call(log, method, args);
+        assert_eq!(result.error_code(), Some(ErrorCode::InvalidInput), "{method}");
+        assert!(result.message.as_deref().unwrap().starts_with(message), "{:?}", result.message);
+    }
+
+    let unknown = result(unsafe { brewlog_call(0, c("list_goals").as_ptr(), c("{}").as_ptr()) });
+    assert_eq!(unknown.error_code(), Some(ErrorCode::NotInitialized));
+    let null_args = result(unsafe { brewlog_call(log, c("list_goals").as_ptr(), std::ptr::null()) });
+    assert_eq!(null_args.error_code(), Some(ErrorCode::NullArgument));
+
+    result(brewlog_close(log));
+}
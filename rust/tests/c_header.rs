@@ -0,0 +1,19 @@
+const HEADER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/include/brewlog.h");
+
+/// The committed header must match the C ABI. Regenerate it with
+/// `BREWLOG_BLESS=1 cargo test --test c_header`.
+#[test]
+fn c_header_is_up_to_date() {
+    let crate_dir = env!("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::generate_with_config(crate_dir, config).unwrap().write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    if std::env::var_os("BREWLOG_BLESS").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(HEADER_PATH).parent().unwrap()).unwrap();
+        std::fs::write(HEADER_PATH, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(HEADER_PATH).unwrap_or_default();
+    assert!(committed == generated, "{HEADER_PATH} is stale; rerun with BREWLOG_BLESS=1");
+}
@@ -0,0 +1,166 @@
+//! Drives the C ABI the way a C caller would and checks that every string it
+//! hands out is released by `brewlog_string_free`.
+//!
+//! Live heap bytes are counted per thread by a wrapping allocator, so the
+//! checks act like a valgrind leak report scoped to the calls under test. The
+//! database-free part also runs under Miri (`cargo +nightly miri test --test
+//! ffi_abi`), which reports leaks and use-after-free itself; SQLite is C code
+//! Miri can't execute, so the rest is skipped there.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use brewlog_core::ffi::*;
+use brewlog_core::{DayBoundary, ErrorCode, FfiDocument, ResultDto, FFI_SCHEMA_VERSION};
+
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track(delta: isize) {
+    // Ignored while the thread is being torn down
+    let _ = LIVE_BYTES.try_with(|live| live.set(live.get() + delta));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        track(layout.size() as isize);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track(-(layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        track(new_size as isize - layout.size() as isize);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn live_bytes() -> isize {
+    LIVE_BYTES.with(Cell::get)
+}
+
+/// Bytes still allocated after running `calls` a few times, once caches filled by
+/// the first run are discounted.
+fn leaked_by(mut calls: impl FnMut()) -> isize {
+    calls();
+    let before = live_bytes();
+    for _ in 0..20 {
+        calls();
+    }
+    live_bytes() - before
+}
+
+/// Copies a returned string and frees the original.
+fn take(s: *mut c_char) -> String {
+    assert!(!s.is_null());
+    let copy = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_owned();
+    unsafe { brewlog_string_free(s) };
+    copy
+}
+
+fn c(s: &str) -> CString {
+    CString::new(s).unwrap()
+}
+
+/// Frees a returned envelope and parses it.
+fn result(s: *mut c_char) -> ResultDto {
+    ResultDto::from_json(&take(s)).unwrap()
+}
+
+fn code(s: *mut c_char) -> Option<ErrorCode> {
+    result(s).error_code()
+}
+
+/// The handle from an `open` envelope.
+fn opened(s: *mut c_char) -> BrewLogHandle {
+    let opened = result(s);
+    assert_eq!(opened.error_code(), Some(ErrorCode::Ok), "{:?}", opened.message);
+    opened.payload.as_u64().unwrap()
+}
+
+#[test]
+fn c_abi_releases_everything_it_returns() {
+    let date = c("2024-03-01");
+    let name = c("Pale Ale");
+    let notes = c("");
+
+    // With an unknown handle, and with null arguments, nothing touches a database
+    let unknown = result(unsafe { add_beer_entry(0, name.as_ptr(), 5.0, 500.0, notes.as_ptr()) });
+    assert_eq!(unknown.error_code(), Some(ErrorCode::NotInitialized));
+    assert_eq!(unknown.message.as_deref(), Some("No open log for handle 0"));
+    let null_path = result(unsafe { brewlog_open(ptr::null()) });
+    assert_eq!((null_path.error_code(), null_path.message.as_deref()), (Some(ErrorCode::NullArgument), Some("path is null")));
+    assert_eq!(code(unsafe { get_daily_consumption(0, ptr::null()) }), Some(ErrorCode::NullArgument));
+    unsafe { brewlog_string_free(ptr::null_mut()) };
+    let leaked = leaked_by(|| unsafe {
+        take(get_beer_entries_json(0, date.as_ptr(), ptr::null()));
+        take(delete_beer_entry_jni(0, ptr::null()));
+        take(update_beer_entry_jni(0, ptr::null(), name.as_ptr(), 5.0, 500.0, ptr::null()));
+        take(set_consumption_goal(0, 1.0, 7.0, ptr::null(), date.as_ptr()));
+        take(brewlog_close(0));
+    });
+    assert_eq!(leaked, 0);
+
+    if cfg!(miri) {
+        return;
+    }
+
+    let log = opened(brewlog_open_in_memory());
+    assert_eq!(code(unsafe { set_consumption_goal(log, 500.0, 2000.0, date.as_ptr(), date.as_ptr()) }), Some(ErrorCode::Ok));
+    let leaked = leaked_by(|| unsafe {
+        assert_eq!(code(add_beer_entry(log, name.as_ptr(), 5.0, 500.0, notes.as_ptr())), Some(ErrorCode::Ok));
+        let today = c(&DayBoundary::default().today().unwrap().to_string());
+        let entries = result(get_beer_entries_json(log, today.as_ptr(), today.as_ptr()));
+        assert_eq!(entries.payload["schema_version"], FFI_SCHEMA_VERSION);
+        assert!(!entries.payload["entries"].as_array().unwrap().is_empty());
+        assert!(result(get_daily_consumption(log, today.as_ptr())).payload.as_f64().unwrap() >= 500.0);
+        assert!(result(get_weekly_consumption(log, today.as_ptr())).payload.as_f64().unwrap() >= 0.0);
+        assert_eq!(code(delete_beer_entry_jni(log, c("missing").as_ptr())), Some(ErrorCode::NotFound));
+        assert_eq!(code(add_beer_entry(log, name.as_ptr(), -1.0, 500.0, notes.as_ptr())), Some(ErrorCode::InvalidInput));
+    });
+    assert_eq!(leaked, 0);
+    assert_eq!(code(brewlog_close(log)), Some(ErrorCode::Ok));
+}
+
+#[test]
+fn handles_open_close_and_reopen_independently() {
+    if cfg!(miri) {
+        return;
+    }
+    let path = std::env::temp_dir().join(format!("brewlog-ffi-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let path_c = c(path.to_str().unwrap());
+    let today = c(&DayBoundary::default().today().unwrap().to_string());
+    let daily = |log| result(unsafe { get_daily_consumption(log, today.as_ptr()) }).payload.as_f64();
+
+    let on_disk = opened(unsafe { brewlog_open(path_c.as_ptr()) });
+    let in_memory = opened(brewlog_open_in_memory());
+    assert_ne!(on_disk, in_memory);
+    assert_eq!(code(unsafe { add_beer_entry(on_disk, c("Stout").as_ptr(), 4.2, 330.0, c("").as_ptr()) }), Some(ErrorCode::Ok));
+    assert_eq!((daily(on_disk), daily(in_memory)), (Some(330.0), Some(0.0)));
+
+    assert_eq!(code(brewlog_close(on_disk)), Some(ErrorCode::Ok));
+    assert_eq!(code(unsafe { get_daily_consumption(on_disk, today.as_ptr()) }), Some(ErrorCode::NotInitialized));
+    assert_eq!(code(brewlog_close(on_disk)), Some(ErrorCode::NotInitialized));
+    assert_eq!(daily(in_memory), Some(0.0));
+
+    let reopened = opened(unsafe { brewlog_open(path_c.as_ptr()) });
+    assert_ne!(reopened, on_disk);
+    assert_eq!(daily(reopened), Some(330.0));
+
+    assert_eq!(code(brewlog_close(reopened)), Some(ErrorCode::Ok));
+    assert_eq!(code(brewlog_close(in_memory)), Some(ErrorCode::Ok));
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,53 @@
+-- BrewLog database at schema version 8
+BEGIN TRANSACTION;
+CREATE TABLE baselines (
+        id TEXT PRIMARY KEY,
+        version INTEGER NOT NULL UNIQUE,
+        average_daily_consumption REAL NOT NULL,
+        average_weekly_consumption REAL NOT NULL,
+        average_daily_ethanol_grams REAL,
+        source_start TEXT,
+        source_end TEXT,
+        effective_from TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+INSERT INTO "baselines" VALUES('baseline-1',1,800.0,5600.0,NULL,NULL,NULL,'2024-02-01','2024-02-01T08:00:00+00:00');
+CREATE TABLE beer_entries (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        alcohol_percentage REAL NOT NULL,
+        volume_ml REAL NOT NULL,
+        date TEXT NOT NULL,
+        notes TEXT,
+        created_at TEXT NOT NULL
+    , consumed_at TEXT);
+INSERT INTO "beer_entries" VALUES('entry-1','Pilsner',5.0,500.0,'2024-03-01','after work','2024-03-01T19:45:00+00:00','2024-03-01T19:45:00+00:00');
+INSERT INTO "beer_entries" VALUES('entry-2','Imperial Stout',11.0,330.0,'2024-03-02','','2024-03-05T08:00:00+00:00',NULL);
+CREATE TABLE consumption_goals (
+        id TEXT PRIMARY KEY,
+        daily_target REAL NOT NULL,
+        weekly_target REAL NOT NULL,
+        start_date TEXT NOT NULL,
+        end_date TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    , unit TEXT NOT NULL DEFAULT 'volume_ml', effective_from TEXT NOT NULL DEFAULT '', effective_to TEXT, retired_at TEXT, kind TEXT NOT NULL DEFAULT 'daily_weekly', alcohol_free_days INTEGER, weekday_limits TEXT, session_cap REAL, monthly_cap REAL);
+INSERT INTO "consumption_goals" VALUES('goal-1',1000.0,5000.0,'2024-03-01','2024-03-28','2024-03-01T08:00:00+00:00','volume_ml','2024-03-01','2024-03-28',NULL,'daily_weekly',NULL,NULL,NULL,NULL);
+CREATE TABLE drink_presets (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        drink_type TEXT NOT NULL,
+        volume_ml REAL NOT NULL,
+        alcohol_percentage REAL NOT NULL,
+        favorite INTEGER NOT NULL DEFAULT 0,
+        position INTEGER NOT NULL,
+        created_at TEXT NOT NULL
+    );
+INSERT INTO "drink_presets" VALUES('preset-1','Pint','BEER',568.0,4.5,1,0,'2024-02-01T08:00:00+00:00');
+CREATE TABLE settings (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+INSERT INTO "settings" VALUES('standard_drink','us');
+CREATE INDEX idx_beer_entries_date ON beer_entries (date);
+COMMIT;
+PRAGMA user_version = 8;
@@ -0,0 +1,106 @@
+use brewlog_core::{BrewLog, DrinkCategory, ImportSource, SPREADSHEET_TEMPLATE};
+
+const UNTAPPD_CSV: &str = include_str!("fixtures/importers/untappd.csv");
+const UNTAPPD_JSON: &str = include_str!("fixtures/importers/untappd.json");
+const DRINK_DIARY: &str = include_str!("fixtures/importers/drink_diary.csv");
+const SPREADSHEET: &str = include_str!("fixtures/importers/spreadsheet_template.csv");
+
+fn entries(log: &BrewLog, start: &str, end: &str) -> Vec<brewlog_core::BeerEntry> {
+    log.get_beer_entries(start.to_string(), end.to_string()).unwrap()
+}
+
+#[test]
+fn imports_untappd_csv() {
+    let log = BrewLog::new().unwrap();
+    let report = log.import_from(ImportSource::UntappdCsv, UNTAPPD_CSV.to_string()).unwrap();
+    assert_eq!((report.imported, report.duplicates), (3, 0));
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, 6);
+
+    let elder = entries(&log, "2024-03-01", "2024-03-01").remove(0);
+    assert_eq!(elder.id, "untappd-1001");
+    assert_eq!(elder.name, "Pliny the Elder (Russian River Brewing Company)");
+    assert_eq!((elder.alcohol_percentage, elder.volume_ml), (8.0, 500.0));
+    assert_eq!(elder.style.as_deref(), Some("IPA - Imperial / Double"));
+    assert_eq!(elder.notes, "Finally! \"Worth the wait\", as they say");
+    assert_eq!(elder.consumed_at.as_deref(), Some("2024-03-01T19:12:33+00:00"));
+
+    let day_two = entries(&log, "2024-03-02", "2024-03-02");
+    let cider = day_two.iter().find(|e| e.id == "untappd-1002").unwrap();
+    assert_eq!(cider.category, DrinkCategory::Cider);
+    let taster = day_two.iter().find(|e| e.id == "untappd-1003").unwrap();
+    assert_eq!(taster.volume_ml, 150.0);
+    assert_eq!(taster.notes, "Line was\naround the block");
+
+    // The JSON export overlaps on check-in 1001
+    let report = log.import_from(ImportSource::UntappdJson, UNTAPPD_JSON.to_string()).unwrap();
+    assert_eq!((report.imported, report.duplicates, report.errors.len()), (1, 1, 0));
+    let na = &entries(&log, "2024-03-03", "2024-03-03")[0];
+    assert_eq!((na.category, na.alcohol_percentage), (DrinkCategory::NonAlcoholic, 0.5));
+
+    // A check-in moved to a day the export doesn't cover stays where it was put
+    log.update_beer_entry_date("untappd-1001".to_string(), "2024-02-20".to_string()).unwrap();
+    let report = log.import_from(ImportSource::UntappdCsv, UNTAPPD_CSV.to_string()).unwrap();
+    assert_eq!((report.imported, report.duplicates), (0, 3));
+    assert_eq!(entries(&log, "2024-02-20", "2024-02-20")[0].id, "untappd-1001");
+    assert!(entries(&log, "2024-03-01", "2024-03-01").is_empty());
+}
+
+#[test]
+fn imports_drink_diary_csv() {
+    let log = BrewLog::new().unwrap();
+    log.set_day_boundary(3, "Europe/Madrid".to_string()).unwrap();
+
+    let report = log.import_from(ImportSource::DrinkDiaryCsv, DRINK_DIARY.to_string()).unwrap();
+    assert_eq!(report.imported, 3);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, 5);
+
+    let first = entries(&log, "2024-03-01", "2024-03-01");
+    let rioja = first.iter().find(|e| e.name == "Rioja Reserva").unwrap();
+    assert_eq!((rioja.category, rioja.alcohol_percentage, rioja.volume_ml), (DrinkCategory::Wine, 13.5, 175.0));
+    assert_eq!(rioja.notes, "Dinner at Marta's; \"excellent\"");
+    assert_eq!(rioja.consumed_at.as_deref(), Some("2024-03-01T20:30:00+01:00"));
+    let lager = &entries(&log, "2024-03-02", "2024-03-02")[0];
+    assert_eq!((lager.volume_ml, lager.consumed_at.as_deref()), (500.0, None));
+
+    let again = log.import_from(ImportSource::DrinkDiaryCsv, DRINK_DIARY.to_string()).unwrap();
+    assert_eq!((again.imported, again.duplicates), (0, 3));
+}
+
+#[test]
+fn imports_spreadsheet_template() {
+    assert!(SPREADSHEET.starts_with(SPREADSHEET_TEMPLATE));
+    let log = BrewLog::new().unwrap();
+
+    // An entry logged in the app before the spreadsheet was imported
+    log.add_beer_entry_at("Negroni".to_string(), 24.0, 90.0, String::new(), "2024-03-01T21:00:00+00:00".to_string())
+        .unwrap();
+
+    let report = log.import_from(ImportSource::SpreadsheetTemplate, SPREADSHEET.to_string()).unwrap();
+    assert_eq!((report.imported, report.duplicates, report.errors.len()), (2, 1, 0));
+
+    let first = entries(&log, "2024-03-01", "2024-03-01");
+    assert_eq!(first.len(), 2);
+    let weizen = first.iter().find(|e| e.name == "Hefeweizen").unwrap();
+    assert_eq!(weizen.style.as_deref(), Some("Weissbier"));
+    assert_eq!(weizen.notes, "Beer garden, sunny");
+
+    // Semicolon-separated copies from European spreadsheet apps use decimal commas
+    let semicolons = "Date;Time;Drink;Category;Style;ABV %;Volume ml;Notes\n2024-03-05;19:00;Kölsch;Beer;;4,8;200;\n";
+    let report = log.import_from(ImportSource::SpreadsheetTemplate, semicolons.to_string()).unwrap();
+    assert_eq!(report.imported, 1);
+    assert_eq!(entries(&log, "2024-03-05", "2024-03-05")[0].alcohol_percentage, 4.8);
+}
+
+#[test]
+fn timed_rows_duplicate_the_same_drink_logged_without_a_time() {
+    let log = BrewLog::new().unwrap();
+    let elder = "Pliny the Elder (Russian River Brewing Company)";
+    log.add_beer_entry_full(None, elder.to_string(), 8.0, 500.0, "2024-03-01".to_string(), String::new(), None)
+        .unwrap();
+
+    let report = log.import_from(ImportSource::UntappdCsv, UNTAPPD_CSV.to_string()).unwrap();
+    assert_eq!((report.imported, report.duplicates), (2, 1));
+    assert_eq!(entries(&log, "2024-03-01", "2024-03-01").len(), 1);
+}
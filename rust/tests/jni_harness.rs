@@ -0,0 +1,295 @@
+//! Drives the JNI entry points without a JVM. `FakeJvm` fills in the handful
+//! of `JNIEnv` functions the bridge uses over plain Rust values — strings,
+//! classes and the exception objects it constructs — and leaves the rest
+//! null, which the `jni` crate reports as an error rather than calling.
+
+use std::cell::{Cell, RefCell};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+use brewlog_core::jni_bridge::*;
+use brewlog_core::{DayBoundary, ErrorCode, FfiDocument, ResultDto};
+use jni::objects::{JClass, JString};
+use jni::sys::{jboolean, jclass, jint, jmethodID, jobject, jstring, jthrowable, jvalue, JNINativeInterface_, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+
+enum FakeObject {
+    Class(String),
+    Str(CString),
+    Exception { class: String, code: i32, message: String },
+}
+
+/// An exception left pending when an entry point returned.
+#[derive(Debug, PartialEq)]
+struct Thrown {
+    class: String,
+    code: Option<i32>,
+    message: String,
+}
+
+thread_local! {
+    static PENDING: RefCell<Option<Thrown>> = const { RefCell::new(None) };
+    static FAIL_NEW_STRING: Cell<bool> = const { Cell::new(false) };
+}
+
+// Objects are leaked: each test makes only a few.
+fn alloc(object: FakeObject) -> jobject {
+    Box::into_raw(Box::new(object)) as jobject
+}
+
+unsafe fn object<'a>(obj: jobject) -> &'a FakeObject {
+    unsafe { &*(obj as *const FakeObject) }
+}
+
+unsafe fn class_name(class: jclass) -> String {
+    match unsafe { object(class) } {
+        FakeObject::Class(name) => name.clone(),
+        _ => panic!("not a class"),
+    }
+}
+
+unsafe fn string(s: jobject) -> String {
+    match unsafe { object(s) } {
+        FakeObject::Str(s) => s.to_str().unwrap().to_owned(),
+        _ => panic!("not a string"),
+    }
+}
+
+type Env = *mut jni::sys::JNIEnv;
+
+unsafe extern "system" fn find_class(_: Env, name: *const c_char) -> jclass {
+    alloc(FakeObject::Class(unsafe { CStr::from_ptr(name) }.to_str().unwrap().to_owned()))
+}
+
+unsafe extern "system" fn get_object_class(_: Env, obj: jobject) -> jclass {
+    let name = match unsafe { object(obj) } {
+        FakeObject::Class(_) => "java/lang/Class".to_string(),
+        FakeObject::Str(_) => "java/lang/String".to_string(),
+        FakeObject::Exception { class, .. } => class.clone(),
+    };
+    alloc(FakeObject::Class(name))
+}
+
+unsafe extern "system" fn is_assignable_from(_: Env, sub: jclass, sup: jclass) -> jboolean {
+    if unsafe { class_name(sub) == class_name(sup) } {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+unsafe extern "system" fn new_string_utf(_: Env, utf: *const c_char) -> jstring {
+    if FAIL_NEW_STRING.get() {
+        return ptr::null_mut();
+    }
+    alloc(FakeObject::Str(unsafe { CStr::from_ptr(utf) }.to_owned()))
+}
+
+unsafe extern "system" fn get_string_utf_chars(_: Env, s: jstring, _: *mut jboolean) -> *const c_char {
+    match unsafe { object(s) } {
+        FakeObject::Str(s) => s.as_ptr(),
+        _ => ptr::null(),
+    }
+}
+
+unsafe extern "system" fn release_string_utf_chars(_: Env, _: jstring, _: *const c_char) {}
+
+unsafe extern "system" fn get_method_id(_: Env, _: jclass, name: *const c_char, sig: *const c_char) -> jmethodID {
+    assert_eq!(unsafe { CStr::from_ptr(name) }.to_str().unwrap(), "<init>");
+    assert_eq!(unsafe { CStr::from_ptr(sig) }.to_str().unwrap(), "(ILjava/lang/String;)V");
+    ptr::NonNull::dangling().as_ptr()
+}
+
+unsafe extern "system" fn new_object_a(_: Env, class: jclass, _: jmethodID, args: *const jvalue) -> jobject {
+    let (code, message) = unsafe { ((*args).i, string((*args.add(1)).l)) };
+    alloc(FakeObject::Exception { class: unsafe { class_name(class) }, code, message })
+}
+
+unsafe extern "system" fn throw(_: Env, obj: jthrowable) -> jint {
+    let FakeObject::Exception { class, code, message } = (unsafe { object(obj) }) else { panic!("not throwable") };
+    PENDING.set(Some(Thrown { class: class.clone(), code: Some(*code), message: message.clone() }));
+    0
+}
+
+unsafe extern "system" fn throw_new(_: Env, class: jclass, msg: *const c_char) -> jint {
+    let message = unsafe { CStr::from_ptr(msg) }.to_str().unwrap().to_owned();
+    PENDING.set(Some(Thrown { class: unsafe { class_name(class) }, code: None, message }));
+    0
+}
+
+unsafe extern "system" fn exception_check(_: Env) -> jboolean {
+    if PENDING.with_borrow(Option::is_some) {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+unsafe extern "system" fn delete_local_ref(_: Env, _: jobject) {}
+
+struct FakeJvm {
+    _table: Box<JNINativeInterface_>,
+    env: Box<*const JNINativeInterface_>,
+}
+
+impl FakeJvm {
+    fn new() -> Self {
+        // SAFETY: every field is a nullable function pointer or a reserved raw pointer
+        let mut table: Box<JNINativeInterface_> = Box::new(unsafe { std::mem::zeroed() });
+        table.FindClass = Some(find_class);
+        table.GetObjectClass = Some(get_object_class);
+        table.IsAssignableFrom = Some(is_assignable_from);
+        table.NewStringUTF = Some(new_string_utf);
+        table.GetStringUTFChars = Some(get_string_utf_chars);
+        table.ReleaseStringUTFChars = Some(release_string_utf_chars);
+        table.GetMethodID = Some(get_method_id);
+        table.NewObjectA = Some(new_object_a);
+        table.Throw = Some(throw);
+        table.ThrowNew = Some(throw_new);
+        table.ExceptionCheck = Some(exception_check);
+        table.DeleteLocalRef = Some(delete_local_ref);
+        let env = Box::new(&*table as *const JNINativeInterface_);
+        FakeJvm { _table: table, env }
+    }
+
+    fn env(&self) -> JNIEnv<'static> {
+        unsafe { JNIEnv::from_raw(&*self.env as *const _ as *mut _) }.unwrap()
+    }
+
+    fn class(&self) -> JClass<'static> {
+        unsafe { JClass::from_raw(ptr::null_mut()) }
+    }
+
+    fn string(&self, s: &str) -> JString<'static> {
+        unsafe { JString::from_raw(alloc(FakeObject::Str(CString::new(s).unwrap()))) }
+    }
+
+    fn null(&self) -> JString<'static> {
+        unsafe { JString::from_raw(ptr::null_mut()) }
+    }
+
+    /// The envelope an entry point returned, or the exception it threw.
+    fn outcome(&self, returned: jstring) -> Result<ResultDto, Thrown> {
+        let pending = PENDING.take();
+        if returned.is_null() {
+            return Err(pending.expect("null return without a pending exception"));
+        }
+        assert_eq!(pending, None);
+        Ok(ResultDto::from_json(&unsafe { string(returned) }).unwrap())
+    }
+}
+
+#[test]
+fn null_argument_throws_brew_log_exception() {
+    let jvm = FakeJvm::new();
+    let returned = Java_com_brewlog_android_BrewLogNative_add_1beer_1entry(
+        jvm.env(),
+        jvm.class(),
+        0,
+        jvm.null(),
+        5.0,
+        500.0,
+        jvm.string(""),
+    );
+    assert_eq!(
+        jvm.outcome(returned),
+        Err(Thrown {
+            class: EXCEPTION_CLASS.to_string(),
+            code: Some(ErrorCode::NullArgument.code()),
+            message: "name is null".to_string(),
+        })
+    );
+}
+
+#[test]
+fn falls_back_to_runtime_exception_when_strings_cannot_be_created() {
+    let jvm = FakeJvm::new();
+    FAIL_NEW_STRING.set(true);
+    let returned = Java_com_brewlog_android_BrewLogNative_delete_1all_1data(jvm.env(), jvm.class(), 0);
+    FAIL_NEW_STRING.set(false);
+
+    let thrown = jvm.outcome(returned).unwrap_err();
+    assert_eq!((thrown.class.as_str(), thrown.code), ("java/lang/RuntimeException", None));
+    assert!(thrown.message.starts_with("Couldn't return result"), "{}", thrown.message);
+}
+
+#[test]
+fn core_results_come_back_as_envelopes() {
+    let jvm = FakeJvm::new();
+    let today = DayBoundary::default().today().unwrap().to_string();
+
+    let unknown = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_get_1daily_1consumption(jvm.env(), jvm.class(), 0, jvm.string(&today)))
+        .unwrap();
+    assert_eq!(unknown.error_code(), Some(ErrorCode::NotInitialized));
+
+    let opened = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_brewlog_1open(jvm.env(), jvm.class(), jvm.string(":memory:")))
+        .unwrap();
+    assert_eq!(opened.error_code(), Some(ErrorCode::Ok));
+    let log = opened.payload.as_i64().unwrap();
+
+    let added = Java_com_brewlog_android_BrewLogNative_add_1beer_1entry(
+        jvm.env(),
+        jvm.class(),
+        log,
+        jvm.string("Pale Ale"),
+        5.0,
+        500.0,
+        jvm.string("ünïcode"),
+    );
+    assert_eq!(jvm.outcome(added).unwrap().error_code(), Some(ErrorCode::Ok));
+
+    let entries = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_get_1beer_1entries_1json(
+            jvm.env(),
+            jvm.class(),
+            log,
+            jvm.string(&today),
+            jvm.string(&today),
+        ))
+        .unwrap();
+    let entry = &entries.payload["entries"][0];
+    assert_eq!((entry["name"].as_str(), entry["notes"].as_str()), (Some("Pale Ale"), Some("ünïcode")));
+
+    let daily = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_get_1daily_1consumption(jvm.env(), jvm.class(), log, jvm.string(&today)))
+        .unwrap();
+    assert_eq!(daily.payload.as_f64(), Some(500.0));
+
+    let missing = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_delete_1beer_1entry_1jni(jvm.env(), jvm.class(), log, jvm.string("missing")))
+        .unwrap();
+    assert_eq!(missing.error_code(), Some(ErrorCode::NotFound));
+
+    let invalid = Java_com_brewlog_android_BrewLogNative_add_1beer_1entry(
+        jvm.env(),
+        jvm.class(),
+        log,
+        jvm.string("Pale Ale"),
+        5.0,
+        -1.0,
+        jvm.string(""),
+    );
+    let invalid = jvm.outcome(invalid).unwrap();
+    assert_eq!((invalid.error_code(), invalid.message.as_deref()), (Some(ErrorCode::InvalidInput), Some("Invalid input: Volume must be positive")));
+
+    let totals = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_brewlog_1call(
+            jvm.env(),
+            jvm.class(),
+            log,
+            jvm.string("get_daily_totals"),
+            jvm.string(&format!(r#"{{"date":"{today}"}}"#)),
+        ))
+        .unwrap();
+    assert_eq!(totals.payload["totals"]["volume_ml"].as_f64(), Some(500.0));
+
+    let closed = jvm.outcome(Java_com_brewlog_android_BrewLogNative_brewlog_1close(jvm.env(), jvm.class(), log)).unwrap();
+    assert_eq!(closed.error_code(), Some(ErrorCode::Ok));
+    let after_close = jvm
+        .outcome(Java_com_brewlog_android_BrewLogNative_get_1daily_1consumption(jvm.env(), jvm.class(), log, jvm.string(&today)))
+        .unwrap();
+    assert_eq!(after_close.error_code(), Some(ErrorCode::NotInitialized));
+}
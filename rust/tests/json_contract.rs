@@ -0,0 +1,268 @@
+use std::collections::BTreeSet;
+
+use brewlog_core::{
+    ffi_json_schema, BackupDto, BaselineCalculationDto, BaselineListDto, BeerEntry, BrewLog, BrewLogError,
+    CategoryBreakdownDto, DrinkCategory, DrinkCategoryDto, EntryDto, EntryListDto, ErrorCode, FfiDocument, FfiError,
+    GoalEvaluationDto, GoalKind, GoalKindDto, GoalListDto, GoalUnit, GoalUnitDto, ProgressStatsDto, ResultDto,
+    SettingListDto, TotalsDto, WeekScheme, WeekTotalsDto, FFI_SCHEMA_VERSION,
+};
+
+const SCHEMA_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/schema/brewlog-ffi.schema.json");
+
+fn sample_log() -> BrewLog {
+    let log = BrewLog::new().unwrap();
+    log.add_beer_entry_at("Pale Ale".to_string(), 5.0, 500.0, "with \"friends\"".to_string(), "2024-03-01T19:30:00+00:00".to_string())
+        .unwrap();
+    log.save_beer_entry(BeerEntry {
+        id: String::new(),
+        name: "Rioja".to_string(),
+        alcohol_percentage: 13.5,
+        volume_ml: 175.0,
+        date: "2024-03-02".to_string(),
+        notes: String::new(),
+        consumed_at: None,
+        category: DrinkCategory::Wine,
+        subtype: Some("red".to_string()),
+        style: Some("Reserva".to_string()),
+        created_at: String::new(),
+    })
+    .unwrap();
+    log
+}
+
+#[test]
+fn entry_list_round_trips() {
+    let log = sample_log();
+    let entries = log.get_beer_entries("2000-01-01".to_string(), "2100-01-01".to_string()).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let json = EntryListDto::new(entries.clone()).to_json().unwrap();
+    let parsed = EntryListDto::from_json(&json).unwrap();
+    assert_eq!(parsed.schema_version, FFI_SCHEMA_VERSION);
+    assert_eq!(parsed, EntryListDto::new(entries.clone()));
+
+    let back: Vec<BeerEntry> = parsed.entries.into_iter().map(Into::into).collect();
+    assert_eq!(serde_json::to_value(&back).unwrap(), serde_json::to_value(&entries).unwrap());
+}
+
+#[test]
+fn entry_fields_use_snake_case_on_the_wire() {
+    let dto = EntryDto::from(sample_log().get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap().remove(0));
+    let value = serde_json::to_value(&dto).unwrap();
+    let keys: Vec<&str> = value.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(
+        keys,
+        ["alcohol_percentage", "category", "consumed_at", "created_at", "date", "id", "name", "notes", "style", "subtype", "volume_ml"]
+    );
+
+    let camel = value.to_string().replace("alcohol_percentage", "alcoholPercentage");
+    let document = format!("{{\"schema_version\":{FFI_SCHEMA_VERSION},\"entries\":[{camel}]}}");
+    assert!(matches!(EntryListDto::from_json(&document), Err(BrewLogError::InvalidInput(_))));
+}
+
+#[test]
+fn rejects_unversioned_and_newer_documents() {
+    assert!(matches!(EntryListDto::from_json("[]"), Err(BrewLogError::InvalidInput(_))));
+    assert!(matches!(EntryListDto::from_json("{\"entries\":[]}"), Err(BrewLogError::InvalidInput(_))));
+    let newer = format!("{{\"schema_version\":{},\"entries\":[]}}", FFI_SCHEMA_VERSION + 1);
+    assert!(matches!(EntryListDto::from_json(&newer), Err(BrewLogError::IncompatibleDatabase(_))));
+}
+
+#[test]
+fn schema_describes_the_serialised_fields() {
+    let schema = ffi_json_schema();
+    let definitions = &schema["definitions"];
+    let properties = |name: &str| -> BTreeSet<String> {
+        definitions[name]["properties"].as_object().unwrap().keys().cloned().collect()
+    };
+
+    let entry = serde_json::to_value(EntryDto::from(
+        sample_log().get_beer_entries("2024-03-01".to_string(), "2024-03-01".to_string()).unwrap().remove(0),
+    ))
+    .unwrap();
+    assert_eq!(properties("Entry"), entry.as_object().unwrap().keys().cloned().collect());
+
+    let list = serde_json::to_value(EntryListDto::new(Vec::new())).unwrap();
+    assert_eq!(properties("EntryList"), list.as_object().unwrap().keys().cloned().collect());
+
+    let result = serde_json::to_value(ResultDto::ok(list)).unwrap();
+    assert_eq!(properties("Result"), result.as_object().unwrap().keys().cloned().collect());
+
+    let log = report_log();
+    let keys = |value: serde_json::Value| -> BTreeSet<String> { value.as_object().unwrap().keys().cloned().collect() };
+    let goals = serde_json::to_value(GoalListDto::new(log.list_goals().unwrap())).unwrap();
+    assert_eq!(properties("GoalList"), keys(goals.clone()));
+    assert_eq!(properties("Goal"), keys(goals["goals"][0].clone()));
+    let baselines = serde_json::to_value(BaselineListDto::new(log.list_baselines().unwrap())).unwrap();
+    assert_eq!(properties("BaselineList"), keys(baselines.clone()));
+    assert_eq!(properties("Baseline"), keys(baselines["baselines"][0].clone()));
+    let calculated = log.calculate_baseline("2024-03-01".to_string(), "2024-03-02".to_string()).unwrap();
+    let calculated = serde_json::to_value(BaselineCalculationDto::from(calculated)).unwrap();
+    assert_eq!(properties("BaselineCalculation"), keys(calculated.clone()));
+    assert_eq!(properties("PeriodSummary"), keys(calculated["summary"].clone()));
+    let stats = log.get_progress_stats("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+    assert_eq!(properties("ProgressStats"), keys(serde_json::to_value(ProgressStatsDto::new(stats)).unwrap()));
+    let week = log.get_week_totals("2024-03-01".to_string(), WeekScheme::Iso).unwrap();
+    assert_eq!(properties("WeekTotals"), keys(serde_json::to_value(WeekTotalsDto::new(week)).unwrap()));
+    let totals = serde_json::to_value(TotalsDto::new(log.get_daily_totals("2024-03-01".to_string()).unwrap())).unwrap();
+    assert_eq!(properties("Totals"), keys(totals.clone()));
+    assert_eq!(properties("Consumption"), keys(totals["totals"].clone()));
+    let breakdown = log.get_category_breakdown("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+    let breakdown = serde_json::to_value(CategoryBreakdownDto::new(breakdown)).unwrap();
+    assert_eq!(properties("CategoryBreakdown"), keys(breakdown.clone()));
+    assert_eq!(properties("CategoryTotals"), keys(breakdown["categories"][0].clone()));
+    let evaluation = log.evaluate_goals("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap();
+    assert_eq!(properties("GoalEvaluation"), keys(serde_json::to_value(GoalEvaluationDto::new(evaluation)).unwrap()));
+    let settings = serde_json::to_value(SettingListDto::new(log.list_settings().unwrap())).unwrap();
+    assert_eq!(properties("SettingList"), keys(settings.clone()));
+    let backup = serde_json::to_value(BackupDto::from(log.export_backup().unwrap())).unwrap();
+    assert_eq!(properties("Backup"), keys(backup));
+
+    // Every record is described once, under its own name
+    let collided: Vec<&String> =
+        definitions.as_object().unwrap().keys().filter(|name| name.ends_with(|c: char| c.is_ascii_digit())).collect();
+    assert!(collided.is_empty(), "{collided:?}");
+
+    let documents: Vec<&str> = schema["oneOf"].as_array().unwrap().iter().map(|d| d["$ref"].as_str().unwrap()).collect();
+    assert_eq!(
+        documents,
+        [
+            "#/definitions/Result",
+            "#/definitions/EntryList",
+            "#/definitions/GoalList",
+            "#/definitions/BaselineList",
+            "#/definitions/ProgressStats",
+            "#/definitions/WeekTotals",
+            "#/definitions/Totals",
+            "#/definitions/CategoryBreakdown",
+            "#/definitions/GoalEvaluation",
+            "#/definitions/SettingList",
+            "#/definitions/PresetList",
+            "#/definitions/ImportReport",
+            "#/definitions/CsvImportReport",
+            "#/definitions/ImporterReport",
+        ]
+    );
+}
+
+/// The sample log with a goal and a baseline, so every report has something in it.
+fn report_log() -> BrewLog {
+    let log = sample_log();
+    log.set_goal(GoalKind::SessionCap { max_per_session: 2.0 }, GoalUnit::StandardDrinks, "2024-03-01".to_string(), String::new())
+        .unwrap();
+    log.set_baseline(400.0, "2024-03-01".to_string()).unwrap();
+    log
+}
+
+#[test]
+fn report_documents_round_trip() {
+    let log = report_log();
+    let goals = GoalListDto::new(log.list_goals().unwrap());
+    assert_eq!(GoalListDto::from_json(&goals.to_json().unwrap()).unwrap(), goals);
+    assert_eq!(goals.goals[0].kind, GoalKindDto::SessionCap { max_per_session: 2.0 });
+
+    let baselines = BaselineListDto::new(log.list_baselines().unwrap());
+    assert_eq!(BaselineListDto::from_json(&baselines.to_json().unwrap()).unwrap(), baselines);
+
+    let stats = ProgressStatsDto::new(log.get_progress_stats("2024-03-01".to_string(), "2024-03-07".to_string()).unwrap());
+    let parsed = ProgressStatsDto::from_json(&stats.to_json().unwrap()).unwrap();
+    assert_eq!(parsed.summary.drinking_days, 2);
+    assert_eq!(parsed.summary.by_category.len(), 2);
+    assert_eq!(stats.baseline_version, Some(1));
+
+    let week = WeekTotalsDto::new(log.get_week_totals("2024-03-01".to_string(), WeekScheme::Iso).unwrap());
+    let parsed = WeekTotalsDto::from_json(&week.to_json().unwrap()).unwrap();
+    assert_eq!((parsed.iso_year, parsed.iso_week, parsed.totals.volume_ml), (Some(2024), Some(9), 675.0));
+}
+
+/// The mirrored enums are sent exactly as the core ones serialise, so records
+/// stored or exported by the core read the same over the FFI.
+#[test]
+fn mirrored_enums_match_the_core_serde_form() {
+    for category in DrinkCategory::ALL {
+        let dto = DrinkCategoryDto::from(category);
+        assert_eq!(serde_json::to_value(dto).unwrap(), serde_json::to_value(category).unwrap());
+        assert_eq!(DrinkCategory::from(dto), category);
+    }
+    for unit in [GoalUnit::VolumeMl, GoalUnit::EthanolGrams, GoalUnit::StandardDrinks] {
+        assert_eq!(serde_json::to_value(GoalUnitDto::from(unit)).unwrap(), serde_json::to_value(unit).unwrap());
+    }
+    let kinds = [
+        GoalKind::DailyWeekly { daily_target: 1.0, weekly_target: 5.0 },
+        GoalKind::AlcoholFreeDays { days_per_week: 2 },
+        GoalKind::WeekdayLimits { limits: [1.0, 1.0, 1.0, 1.0, 2.0, 3.0, 0.0] },
+        GoalKind::SessionCap { max_per_session: 3.0 },
+        GoalKind::MonthlyCap { max_per_month: 40.0 },
+    ];
+    for kind in kinds {
+        let json = serde_json::to_value(GoalKindDto::from(kind.clone())).unwrap();
+        assert_eq!(json, serde_json::to_value(&kind).unwrap());
+        assert_eq!(GoalKind::from(serde_json::from_value::<GoalKindDto>(json).unwrap()), kind);
+    }
+    let extra = serde_json::json!({ "type": "session_cap", "max_per_session": 3.0, "max": 1.0 });
+    assert!(serde_json::from_value::<GoalKindDto>(extra).is_err());
+}
+
+#[test]
+fn result_envelope_round_trips() {
+    let ok = ResultDto::ok(serde_json::json!(500.0));
+    let parsed = ResultDto::from_json(&ok.clone().into_json()).unwrap();
+    assert_eq!(parsed, ok);
+    assert_eq!((parsed.error_code(), parsed.message), (Some(ErrorCode::Ok), None));
+
+    let missing = ResultDto::from_outcome(Err(FfiError::from(BrewLogError::NotFound("Entry x".to_string()))));
+    let json: serde_json::Value = serde_json::from_str(&missing.clone().into_json()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "schema_version": FFI_SCHEMA_VERSION,
+            "code": 4,
+            "message": "Not found: Entry x",
+            "payload": null,
+        })
+    );
+    assert_eq!(ResultDto::from_json(&json.to_string()).unwrap(), missing);
+}
+
+/// Codes are part of the published contract and must never change.
+#[test]
+fn error_codes_are_stable() {
+    let codes: Vec<(ErrorCode, i32)> = ErrorCode::ALL.into_iter().map(|c| (c, c.code())).collect();
+    assert_eq!(
+        codes,
+        [
+            (ErrorCode::Ok, 0),
+            (ErrorCode::NotInitialized, 1),
+            (ErrorCode::NullArgument, 2),
+            (ErrorCode::InvalidInput, 3),
+            (ErrorCode::NotFound, 4),
+            (ErrorCode::Database, 5),
+            (ErrorCode::IncompatibleDatabase, 6),
+            (ErrorCode::Internal, 7),
+        ]
+    );
+    assert_eq!(ErrorCode::from_code(99), None);
+
+    let mapped = [
+        (BrewLogError::DatabaseError(String::new()), ErrorCode::Database),
+        (BrewLogError::InvalidInput(String::new()), ErrorCode::InvalidInput),
+        (BrewLogError::NotFound(String::new()), ErrorCode::NotFound),
+        (BrewLogError::IncompatibleDatabase(String::new()), ErrorCode::IncompatibleDatabase),
+    ];
+    for (error, code) in mapped {
+        assert_eq!(ErrorCode::from(&error), code);
+    }
+}
+
+/// The published schema must match the DTOs. Regenerate it with
+/// `BREWLOG_BLESS=1 cargo test --test json_contract`.
+#[test]
+fn published_schema_is_up_to_date() {
+    let generated = serde_json::to_string_pretty(&ffi_json_schema()).unwrap() + "\n";
+    if std::env::var_os("BREWLOG_BLESS").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(SCHEMA_PATH).parent().unwrap()).unwrap();
+        std::fs::write(SCHEMA_PATH, &generated).unwrap();
+    }
+    let published = std::fs::read_to_string(SCHEMA_PATH).unwrap_or_default();
+    assert!(published == generated, "{SCHEMA_PATH} is stale; rerun with BREWLOG_BLESS=1");
+}
@@ -0,0 +1,99 @@
+use brewlog_core::{BrewLog, BrewLogError, DrinkCategory, SCHEMA_VERSION};
+use rusqlite::Connection;
+use std::path::PathBuf;
+
+/// Every released schema, oldest first. Add the outgoing version here whenever a migration is appended.
+const FIXTURES: &[(u32, &str)] = &[
+    (1, include_str!("fixtures/schema_v1.sql")),
+    (2, include_str!("fixtures/schema_v2.sql")),
+    (3, include_str!("fixtures/schema_v3.sql")),
+    (4, include_str!("fixtures/schema_v4.sql")),
+    (5, include_str!("fixtures/schema_v5.sql")),
+    (6, include_str!("fixtures/schema_v6.sql")),
+    (7, include_str!("fixtures/schema_v7.sql")),
+    (8, include_str!("fixtures/schema_v8.sql")),
+];
+
+struct TempDb(PathBuf);
+
+impl TempDb {
+    fn new() -> Self {
+        TempDb(std::env::temp_dir().join(format!("brewlog-{}.db", uuid::Uuid::new_v4())))
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap()
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn upgrades_every_past_version() {
+    assert_eq!(FIXTURES.len() as u32, SCHEMA_VERSION - 1, "missing fixture for a past schema version");
+
+    for (version, sql) in FIXTURES {
+        let db = TempDb::new();
+        Connection::open(db.path()).unwrap().execute_batch(sql).unwrap();
+
+        let log = BrewLog::new_with_path(db.path()).unwrap_or_else(|e| panic!("v{version}: {e}"));
+        assert_eq!(log.schema_version().unwrap(), SCHEMA_VERSION, "v{version}");
+
+        let entries = log.get_beer_entries("2024-03-01".to_string(), "2024-03-02".to_string()).unwrap();
+        assert_eq!(entries.len(), 2, "v{version}");
+        let pilsner = entries.iter().find(|e| e.id == "entry-1").unwrap();
+        let stout = entries.iter().find(|e| e.id == "entry-2").unwrap();
+        assert_eq!(pilsner.consumed_at.as_deref(), Some("2024-03-01T19:45:00+00:00"), "v{version}");
+        assert_eq!(stout.consumed_at, None, "v{version}");
+        assert_eq!(stout.category, DrinkCategory::Beer, "v{version}");
+
+        let goal = log.get_goal_for_date("2024-03-10".to_string()).unwrap();
+        assert_eq!(goal.daily_target, 1000.0, "v{version}");
+        assert_eq!(goal.effective_to.as_deref(), Some("2024-03-28"), "v{version}");
+        assert!(log.get_goal_for_date("2024-03-29".to_string()).is_err(), "v{version}");
+
+        if *version >= 2 {
+            assert_eq!(log.get_standard_drink().unwrap().code, "us", "v{version}");
+        }
+        if *version >= 3 {
+            assert_eq!(log.get_baseline().unwrap().average_daily_consumption, 800.0, "v{version}");
+        }
+        if *version >= 8 {
+            let pint = &log.list_presets(true).unwrap()[0];
+            assert_eq!((pint.name.as_str(), pint.category), ("Pint", DrinkCategory::Beer), "v{version}");
+        }
+
+        // The upgraded database keeps working and reopens without re-running migrations
+        log.add_beer_entry("Lager".to_string(), 5.0, 330.0, String::new()).unwrap();
+        drop(log);
+        let reopened = BrewLog::new_with_path(db.path()).unwrap();
+        assert_eq!(reopened.schema_version().unwrap(), SCHEMA_VERSION);
+    }
+}
+
+#[test]
+fn creates_fresh_database_at_current_version() {
+    let db = TempDb::new();
+    let log = BrewLog::new_with_path(db.path()).unwrap();
+    assert_eq!(log.schema_version().unwrap(), SCHEMA_VERSION);
+    assert_eq!(BrewLog::new().unwrap().schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+#[test]
+fn refuses_newer_database() {
+    let db = TempDb::new();
+    drop(BrewLog::new_with_path(db.path()).unwrap());
+    Connection::open(db.path())
+        .unwrap()
+        .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+        .unwrap();
+
+    assert!(matches!(
+        BrewLog::new_with_path(db.path()),
+        Err(BrewLogError::IncompatibleDatabase(_))
+    ));
+}
@@ -0,0 +1,70 @@
+# Drives the generated Python bindings; run by tests/uniffi_bindings.rs.
+import brewlog_core as b
+
+log = b.BrewLog()
+for day in range(1, 8):
+    entry = b.BeerEntry(
+        id="", name="Pint", alcohol_percentage=5.0, volume_ml=500.0, date=f"2024-03-0{day}", notes="",
+        consumed_at=None, category=b.DrinkCategory.BEER, subtype=None, style=None, created_at="",
+    )
+    log.save_beer_entry(entry)
+
+entries = log.get_beer_entries("2024-03-01", "2024-03-07")
+assert len(entries) == 7 and isinstance(entries[0], b.BeerEntry), entries
+
+baseline = log.calculate_baseline("2024-03-01", "2024-03-07")
+assert baseline.average_daily_consumption == 500.0, baseline
+assert log.save_baseline(baseline, "2024-03-01").version == 1
+
+log.set_goal(b.GoalTarget.WEEKDAY_LIMITS([500.0] * 7), b.GoalUnit.VOLUME_ML, "2024-03-01", "")
+assert log.list_goals()[0].kind == b.GoalTarget.WEEKDAY_LIMITS([500.0] * 7)
+
+stats = log.get_progress_stats("2024-03-01", "2024-03-07")
+assert isinstance(stats, b.ProgressStats) and stats.baseline_version == 1, stats
+
+try:
+    log.delete_beer_entry("missing")
+    raise AssertionError("deleting a missing entry succeeded")
+except b.BrewLogError.NotFound:
+    pass
+
+try:
+    log.set_goal(b.GoalTarget.WEEKDAY_LIMITS([500.0]), b.GoalUnit.VOLUME_ML, "2024-03-01", "")
+    raise AssertionError("a goal with one weekday limit was accepted")
+except b.BrewLogError.InvalidInput as e:
+    assert "Expected 7 weekday limits" in str(e), e
+
+evaluation = log.evaluate_goals("2024-03-01", "2024-03-07")
+assert evaluation.days[0].status == b.GoalStatus.WITHIN, evaluation
+week = log.get_week_totals("2024-03-01", b.WeekScheme.ISO)
+assert week.iso_week == 9, week
+assert log.get_category_breakdown("2024-03-01", "2024-03-07")[0].entries == 7
+
+log.set_setting(b.Setting.DEFAULT_BEER_SIZE, b.SettingValue.NUMBER(330.0))
+assert log.get_setting(b.Setting.DEFAULT_BEER_SIZE) == b.SettingValue.NUMBER(330.0)
+log.set_standard_drink("uk_unit")
+assert log.get_standard_drink().grams == 8.0
+
+preset = log.add_preset("Half", b.DrinkCategory.BEER, 284.0, 4.0, True)
+assert [p.id for p in log.list_presets(True)] == [preset.id]
+
+csv = log.export_csv("2024-03-01", "2024-03-07", ";")
+other = b.BrewLog()
+mapping = b.CsvMapping(
+    delimiter=";", has_header=True, decimal_comma=False, date=b.CsvColumn.NAME("date"), date_format="%Y-%m-%d",
+    time=None, consumed_at=None, name=b.CsvColumn.POSITION(4), abv=b.CsvColumn.NAME("alcohol_percentage"),
+    volume=b.CsvColumn.NAME("volume_ml"), volume_unit=b.VolumeUnit.ML, notes=None, category=None, subtype=None,
+    style=None, id=None,
+)
+assert other.import_csv(csv, mapping).imported == 7
+try:
+    log.export_csv("2024-03-01", "2024-03-07", ";;")
+    raise AssertionError("a two-character delimiter was accepted")
+except b.BrewLogError.InvalidInput:
+    pass
+
+backup = log.export_backup()
+assert backup.settings["default_beer_size"] == b.SettingValue.NUMBER(330.0), backup.settings
+restored = b.BrewLog()
+assert restored.import_backup(backup, b.ImportMode.REPLACE).failed == 0
+assert len(restored.list_presets(False)) == 1
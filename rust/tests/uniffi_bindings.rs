@@ -0,0 +1,97 @@
+//! Generates the UniFFI bindings from the built library and checks them. The
+//! Kotlin and Swift sources are inspected; the Python ones, which need no
+//! compiler, are also run against the library when `python3` is installed.
+
+#![cfg(feature = "uniffi-cli")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const BINDGEN: &str = env!("CARGO_BIN_EXE_uniffi-bindgen");
+
+/// Builds the cdylib with this test's features and returns its path. The copy
+/// in `target/debug` may be from a build without the scaffolding, and bindgen
+/// silently generates nothing from that.
+fn build_library() -> PathBuf {
+    let output = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--features", "uniffi-cli", "--message-format=json"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact" && message["target"]["name"] == "brewlog_core")
+        .flat_map(|message| message["filenames"].as_array().cloned().unwrap_or_default())
+        .filter_map(|file| file.as_str().map(PathBuf::from))
+        .find(|file| file.to_string_lossy().ends_with(std::env::consts::DLL_SUFFIX))
+        .expect("cargo reported no cdylib")
+}
+
+fn generate(library: &Path, out_dir: &Path) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new(BINDGEN)
+        .args(["generate", "--no-format", "--library"])
+        .arg(library)
+        .args(["--language", "kotlin", "--language", "swift", "--language", "python", "--out-dir"])
+        .arg(out_dir)
+        .arg("--config")
+        .arg(manifest_dir.join("uniffi.toml"))
+        .current_dir(manifest_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn assert_declares(source: &Path, declarations: &[&str]) {
+    let text = fs::read_to_string(source).unwrap();
+    for declaration in declarations {
+        assert!(text.contains(declaration), "{} lacks `{declaration}`", source.display());
+    }
+}
+
+#[test]
+fn generated_bindings_expose_native_types() {
+    let out_dir = std::env::temp_dir().join(format!("brewlog-uniffi-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&out_dir);
+    let library = build_library();
+    generate(&library, &out_dir);
+
+    assert_declares(
+        &out_dir.join("com/brewlog/core/brewlog_core.kt"),
+        &[
+            "open class BrewLog",
+            "data class BeerEntry",
+            "data class ConsumptionGoal",
+            "data class Baseline",
+            "data class ProgressStats",
+            "sealed class BrewLogException",
+            "fun `getProgressStats`(`periodStart`: kotlin.String, `periodEnd`: kotlin.String): ProgressStats",
+        ],
+    );
+    assert_declares(
+        &out_dir.join("BrewLogCore.swift"),
+        &[
+            "open class BrewLog",
+            "public struct BeerEntry",
+            "public struct ConsumptionGoal",
+            "public struct Baseline",
+            "public struct ProgressStats",
+            "public enum BrewLogError",
+        ],
+    );
+
+    if Command::new("python3").arg("--version").output().is_err() {
+        eprintln!("python3 not found; skipping the Python run");
+    } else {
+        fs::copy(&library, out_dir.join(library.file_name().unwrap())).unwrap();
+        let smoke = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/uniffi/smoke.py");
+        let output = Command::new("python3").arg(smoke).env("PYTHONPATH", &out_dir).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    fs::remove_dir_all(&out_dir).unwrap();
+}